@@ -0,0 +1,243 @@
+//! # Async network adapter
+//!
+//! Async counterpart of the blocking [Adapter](crate::wifi::Adapter), based on [atat::asynch::AtatClient].
+//! Requires the `async` feature.
+//!
+//! Instead of polling a timer, the adapter awaits URC messages of ESP-AT. Timeouts are measured by a
+//! [DelayNs] implementation, e.g. `embassy_time::Delay`.
+//!
+//! [Adapter] implements [embedded_nal_async::TcpConnect] and the async [WifiAdapter](wifi::WifiAdapter).
+//! All methods take a shared reference, so that multiple connections may be used in parallel.
+//! Commands are serialized internally.
+//!
+//! ## Example
+//!
+//! ````
+//! # use core::str::FromStr;
+//! # use core::net::SocketAddr;
+//! # use embedded_io_async::{Read, Write};
+//! # use embedded_nal_async::TcpConnect;
+//! # use esp_at_nal::asynch::Adapter;
+//! # use esp_at_nal::asynch::wifi::WifiAdapter;
+//! # use esp_at_nal::example::ExampleDelay;
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! # embassy_futures::block_on(async {
+//! let urc_channel = AtClient::urc_channel();
+//! let client = AtClient::init(&urc_channel);
+//! let adapter: Adapter<_, _, 1024, 128, 8> = Adapter::new(client, urc_channel.subscriber().unwrap(), ExampleDelay::default());
+//!
+//! // Joining WIFI access point
+//! let state = adapter.join("test_wifi", "secret").await.unwrap();
+//! assert!(state.connected);
+//!
+//! // Creating a TCP connection
+//! let mut connection = adapter.connect(SocketAddr::from_str("10.0.0.1:21").unwrap()).await.unwrap();
+//!
+//! // Sending some data
+//! connection.write_all(b"hallo!").await.unwrap();
+//!
+//! // Receiving some data
+//! let mut rx_buffer = [0x0; 64];
+//! let length = connection.read(&mut rx_buffer).await.unwrap();
+//! assert_eq!(b"nice to see you!", &rx_buffer[..length]);
+//!
+//! // Closing the connection
+//! connection.close().await.unwrap();
+//! # });
+//! ````
+
+pub mod stack;
+pub mod wifi;
+
+use crate::commands::CommandErrorHandler;
+#[cfg(feature = "bbqueue")]
+use crate::queue::BbQueue;
+use crate::queue::{ReceiveQueue, VecQueue};
+use crate::stack::{Error, ReceiveMode};
+use crate::urc::URCMessages;
+use crate::wifi::{Session, DEFAULT_MAX_SOCKETS};
+use atat::asynch::AtatClient;
+use atat::{AtatCmd, UrcSubscription};
+#[cfg(feature = "bbqueue")]
+use bbqueue::BBBuffer;
+use core::cell::{Cell, RefCell};
+use embassy_futures::select::{select, Either};
+use embassy_futures::yield_now;
+use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+use embassy_sync::mutex::Mutex;
+use embedded_hal_async::delay::DelayNs;
+
+/// Timeout for URC messages confirming a command, e.g. the connection state after CIPSTART
+const URC_TIMEOUT_MS: u32 = 1_000;
+
+/// Timeout for the ready message after restarting the module
+const RESTART_TIMEOUT_MS: u32 = 5_000;
+
+/// Timeout for the WIFI DISCONNECT message after leaving the access point
+const DISCONNECT_TIMEOUT_MS: u32 = 5_000;
+
+/// Async client for network communication
+///
+/// TX_SIZE: Chunk size in bytes when sending data. Higher value results in better performance, but
+/// introduces also higher stack memory footprint. Max. value: 8192
+///
+/// RX_SIZE: Chunk size in bytes when receiving data. Value should be matched to buffer size of `read()` calls.
+///
+/// URC_CAPACITY: URC buffer size. It's the same value, as used when initializing the UrcChannel of atat
+///
+/// MAX_SOCKETS: Number of link ids, defaults to [DEFAULT_MAX_SOCKETS]. Must not exceed the max. connection count
+/// of the ESP-AT firmware.
+///
+/// Q: Receive queue of each connection, just required for [active receiving mode](ReceiveMode::Active).
+/// Defaults to [VecQueue<0>](VecQueue), so no buffer is allocated. s. [queue module](crate::queue).
+pub struct Adapter<
+    'urc_sub,
+    A: AtatClient,
+    D: DelayNs,
+    const TX_SIZE: usize,
+    const RX_SIZE: usize,
+    const URC_CAPACITY: usize,
+    const MAX_SOCKETS: usize = DEFAULT_MAX_SOCKETS,
+    Q: ReceiveQueue = VecQueue<0>,
+> {
+    /// ATAT client + delay, locked for the duration of a command sequence
+    pub(crate) client: Mutex<NoopRawMutex, ClientState<A, D>>,
+
+    /// URC message subscriber, locked while awaiting URC messages
+    pub(crate) urc_subscription: Mutex<NoopRawMutex, UrcSubscription<'urc_sub, URCMessages<RX_SIZE>, URC_CAPACITY, 1>>,
+
+    /// Network state
+    pub(crate) session: RefCell<Session<RX_SIZE, MAX_SOCKETS, Q>>,
+
+    /// Link ids of connections dropped without closing. Get closed by the next `connect()` call.
+    pub(crate) dropped: Cell<[bool; MAX_SOCKETS]>,
+
+    /// Timeout for data transmission in ms
+    pub(crate) send_timeout_ms: u32,
+}
+
+/// State guarded by the command lock
+pub(crate) struct ClientState<A: AtatClient, D: DelayNs> {
+    /// ATAT client
+    pub(crate) client: A,
+
+    /// Delay used for timeout measurement
+    pub(crate) delay: D,
+}
+
+impl<A: AtatClient, D: DelayNs> ClientState<A, D> {
+    /// Sends a command and maps the error if the command failed
+    pub(crate) async fn send_command<Cmd: AtatCmd + CommandErrorHandler>(
+        &mut self,
+        command: Cmd,
+    ) -> Result<Cmd::Response, Cmd::Error> {
+        self.client.send(&command).await.map_err(|e| command.command_error(e))
+    }
+}
+
+impl<
+        'urc_sub,
+        A: AtatClient,
+        D: DelayNs,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > Adapter<'urc_sub, A, D, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    /// Creates a new async network adapter
+    pub fn new(
+        client: A,
+        urc_subscription: UrcSubscription<'urc_sub, URCMessages<RX_SIZE>, URC_CAPACITY, 1>,
+        delay: D,
+    ) -> Self {
+        Self {
+            client: Mutex::new(ClientState { client, delay }),
+            urc_subscription: Mutex::new(urc_subscription),
+            session: RefCell::new(Session::default()),
+            dropped: Cell::new([false; MAX_SOCKETS]),
+            send_timeout_ms: 5_000,
+        }
+    }
+
+    /// Sets the timeout for sending TCP data in ms
+    pub fn set_send_timeout_ms(&mut self, timeout: u32) {
+        self.send_timeout_ms = timeout;
+    }
+
+    /// Sets the socket receiving mode, which is passive by default. Gets applied by next `connect()` call,
+    /// so it should be set before opening the first connection. See [ReceiveMode] for details.
+    ///
+    /// Returns [Error::ReceiveBufferDisabled] when enabling active mode without receive queue (`Q` generic).
+    pub fn set_receive_mode(&mut self, mode: ReceiveMode) -> Result<(), Error> {
+        self.session.get_mut().set_receive_mode(mode)
+    }
+
+    /// Processes all pending messages in the queue.
+    /// Skipped if another task is currently awaiting URC messages, as it's processing them anyway.
+    pub(crate) fn process_urc_messages(&self) {
+        if let Ok(mut subscription) = self.urc_subscription.try_lock() {
+            while let Some(message) = subscription.try_next_message_pure() {
+                self.session.borrow_mut().handle_urc(message);
+            }
+        }
+    }
+
+    /// Awaits URC messages until the given condition is met by the session state
+    pub(crate) async fn wait_until(&self, condition: impl Fn(&Session<RX_SIZE, MAX_SOCKETS, Q>) -> bool) {
+        loop {
+            let mut subscription = self.urc_subscription.lock().await;
+            while let Some(message) = subscription.try_next_message_pure() {
+                self.session.borrow_mut().handle_urc(message);
+            }
+
+            if condition(&self.session.borrow()) {
+                return;
+            }
+
+            let message = subscription.next_message_pure().await;
+            self.session.borrow_mut().handle_urc(message);
+            drop(subscription);
+
+            // Session has changed, so other waiting tasks get the chance to check their condition
+            yield_now().await;
+        }
+    }
+
+    /// Awaits URC messages until the given condition is met or the timeout is reached.
+    /// Returns false in case of timeout.
+    pub(crate) async fn wait_until_timeout(
+        &self,
+        delay: &mut D,
+        timeout_ms: u32,
+        condition: impl Fn(&Session<RX_SIZE, MAX_SOCKETS, Q>) -> bool,
+    ) -> bool {
+        match select(self.wait_until(condition), delay.delay_ms(timeout_ms)).await {
+            Either::First(_) => true,
+            Either::Second(_) => false,
+        }
+    }
+}
+
+#[cfg(feature = "bbqueue")]
+impl<
+        A: AtatClient,
+        D: DelayNs,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        const N: usize,
+    > Adapter<'_, A, D, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, BbQueue<N>>
+{
+    /// Assigns the storage of the [BbQueue] of each link id, which is required for enabling
+    /// [active receiving mode](ReceiveMode::Active). Array index = link id.
+    ///
+    /// Returns [Error::ReceiveQueueInUse] if any storage is already split, e.g. by another adapter. In this case no
+    /// storage is assigned. Otherwise storages of a previous call get released, so they may be assigned again.
+    pub fn set_receive_queues(&mut self, storages: [&'static BBBuffer<N>; MAX_SOCKETS]) -> Result<(), Error> {
+        self.session.get_mut().set_receive_queues(storages)
+    }
+}
@@ -0,0 +1,475 @@
+//! # Async TCP client stack
+//!
+//! [Adapter] implements [TcpConnect]. Connections implement [Read] and [Write] of [embedded_io_async].
+//!
+//! Connections should be closed by [TcpConnection::close()]. Connections dropped without closing keep their
+//! link id occupied, until they get closed by the next `connect()` call.
+use crate::asynch::{Adapter, ClientState, URC_TIMEOUT_MS};
+use crate::commands::{
+    CloseSocketCommand, ConnectCommand, ConnectionStateCommand, ReceiveDataCommand, SetMultipleConnectionsCommand,
+    SetSocketReceivingModeCommand, TransmissionCommand, TransmissionPrepareCommand,
+};
+use crate::queue::{ReceiveQueue, VecQueue};
+use crate::stack::{Buffer, ConnectionState, Error, ReceiveMode, Socket, SocketState};
+use crate::wifi::DEFAULT_MAX_SOCKETS;
+use atat::asynch::AtatClient;
+use atat::Error as AtError;
+use core::net::SocketAddr;
+use embedded_hal_async::delay::DelayNs;
+use embedded_io_async::{ErrorKind, ErrorType, Read, Write};
+use embedded_nal_async::TcpConnect;
+
+impl embedded_io_async::Error for Error {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Error::ClosingSocket => ErrorKind::ConnectionReset,
+            Error::SocketUnconnected => ErrorKind::NotConnected,
+            Error::SendFailed(AtError::Timeout) => ErrorKind::TimedOut,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+impl<
+        'urc_sub,
+        A: AtatClient,
+        D: DelayNs,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > TcpConnect for Adapter<'urc_sub, A, D, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    type Error = Error;
+
+    type Connection<'a>
+        = TcpConnection<'a, 'urc_sub, A, D, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+    where
+        Self: 'a;
+
+    /// Opens a new TCP connection. Both IPv4 and IPv6 are supported.
+    /// Up to `MAX_SOCKETS` parallel connections are supported. If no link id is available
+    /// [Error::NoSocketAvailable] is returned.
+    ///
+    /// On first call ESP-AT is configured for multiple connections and the socket receiving mode,
+    /// s. [set_receive_mode()](Adapter::set_receive_mode).
+    async fn connect<'a>(&'a self, remote: SocketAddr) -> Result<Self::Connection<'a>, Self::Error> {
+        let mut client = self.client.lock().await;
+        self.process_urc_messages();
+
+        self.close_dropped_sockets(&mut client).await;
+        self.enable_multiple_connections(&mut client).await?;
+        self.configure_receiving_mode(&mut client).await?;
+
+        let socket = self.open_socket()?;
+        if let Err(error) = self.open_connection(&mut client, &socket, remote).await {
+            self.session.borrow_mut().sockets[socket.link_id] = SocketState::default();
+            return Err(error);
+        }
+
+        Ok(TcpConnection { adapter: self, socket })
+    }
+}
+
+/// TCP connection established by [Adapter::connect()](TcpConnect::connect)
+pub struct TcpConnection<
+    'a,
+    'urc_sub,
+    A: AtatClient,
+    D: DelayNs,
+    const TX_SIZE: usize,
+    const RX_SIZE: usize,
+    const URC_CAPACITY: usize,
+    const MAX_SOCKETS: usize = DEFAULT_MAX_SOCKETS,
+    Q: ReceiveQueue = VecQueue<0>,
+> {
+    adapter: &'a Adapter<'urc_sub, A, D, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>,
+    socket: Socket,
+}
+
+impl<
+        A: AtatClient,
+        D: DelayNs,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > TcpConnection<'_, '_, A, D, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    /// Returns true if the connection is still open. Connection aborts by the remote side are also taken into account.
+    pub fn is_connected(&self) -> bool {
+        self.adapter.process_urc_messages();
+        self.adapter.session.borrow().is_socket_connected(&self.socket)
+    }
+
+    /// Closes the connection
+    ///
+    /// In case of an error (which is returned) the link id is released anyway.
+    pub async fn close(self) -> Result<(), Error> {
+        let mut client = self.adapter.client.lock().await;
+        self.adapter.close_socket(&mut client, &self.socket).await
+    }
+}
+
+impl<
+        A: AtatClient,
+        D: DelayNs,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > Drop for TcpConnection<'_, '_, A, D, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    /// Releases the link id if the connection is not open anymore. Otherwise, the link id
+    /// is marked for getting closed by the next `connect()` call.
+    fn drop(&mut self) {
+        let mut session = self.adapter.session.borrow_mut();
+        let link_id = self.socket.link_id;
+
+        match session.sockets[link_id].state {
+            ConnectionState::Closed => {}
+            ConnectionState::Connected => {
+                let mut dropped = self.adapter.dropped.get();
+                dropped[link_id] = true;
+                self.adapter.dropped.set(dropped);
+            }
+            _ => session.sockets[link_id] = SocketState::default(),
+        }
+    }
+}
+
+impl<
+        A: AtatClient,
+        D: DelayNs,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > ErrorType for TcpConnection<'_, '_, A, D, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    type Error = Error;
+}
+
+impl<
+        A: AtatClient,
+        D: DelayNs,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > Read for TcpConnection<'_, '_, A, D, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    /// Waits until data is available and writes it to the given buffer.
+    /// Returns zero if the connection was closed by the remote side.
+    ///
+    /// The data is read internally in blocks. The block size is defined by the generic constant RX_SIZE.
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let socket = &self.socket;
+        self.adapter
+            .wait_until(|session| {
+                session.is_data_available(socket)
+                    || session.sockets[socket.link_id].rx_overflow
+                    || !session.is_socket_connected(socket)
+            })
+            .await;
+
+        let mut client = self.adapter.client.lock().await;
+        self.adapter.receive_data(&mut client, socket, buf).await
+    }
+}
+
+impl<
+        A: AtatClient,
+        D: DelayNs,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > Write for TcpConnection<'_, '_, A, D, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    /// Sends the given buffer and returns the length (in bytes) sent.
+    /// The data is divided into smaller blocks. The block size is determined by the generic constant TX_SIZE.
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        let mut client = self.adapter.client.lock().await;
+        self.adapter.process_urc_messages();
+        self.adapter.assert_socket_connected(&self.socket)?;
+
+        for chunk in buf.chunks(TX_SIZE) {
+            client
+                .send_command(TransmissionPrepareCommand::new(self.socket.link_id, chunk.len()))
+                .await?;
+            self.adapter.send_chunk(&mut client, chunk).await?;
+        }
+
+        Ok(buf.len())
+    }
+}
+
+impl<
+        'urc_sub,
+        A: AtatClient,
+        D: DelayNs,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > Adapter<'urc_sub, A, D, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    /// Queries the state of all connections by CIPSTATE command and updates the internal socket states.
+    ///
+    /// Socket states are usually tracked by URC messages. If a message got lost, e.g. by an overflow of the URC
+    /// channel, the internal state diverges from ESP-AT. Connections no longer reported by ESP-AT are treated as
+    /// closed by the remote side.
+    ///
+    /// Gets called automatically if a connect was not confirmed by URC message.
+    pub async fn sync_sockets(&self) -> Result<(), Error> {
+        let mut client = self.client.lock().await;
+        self.sync_socket_states(&mut client).await
+    }
+
+    /// Establishes a TCP connection of the given socket
+    async fn open_connection(
+        &self,
+        client: &mut ClientState<A, D>,
+        socket: &Socket,
+        remote: SocketAddr,
+    ) -> Result<(), Error> {
+        let command = match remote {
+            SocketAddr::V4(address) => ConnectCommand::tcp_v4(socket.link_id, address),
+            SocketAddr::V6(address) => ConnectCommand::tcp_v6(socket.link_id, address),
+        };
+
+        {
+            let mut session = self.session.borrow_mut();
+            session.already_connected = false;
+            session.reset_available_data(socket);
+            session.sockets[socket.link_id].state = ConnectionState::Connecting;
+        }
+
+        let result = client.send_command(command).await;
+        self.process_urc_messages();
+
+        // ESP-AT returned that given socket is already connected. This indicates that a URC Connect message was missed.
+        if self.session.borrow().already_connected {
+            self.session.borrow_mut().sockets[socket.link_id].state = ConnectionState::Connected;
+        } else {
+            result?;
+
+            let connected = self
+                .wait_until_timeout(&mut client.delay, URC_TIMEOUT_MS, |session| {
+                    session.is_socket_connected(socket)
+                })
+                .await;
+
+            // URC message may got lost, so the actual state is queried
+            if !connected
+                && (self.sync_socket_states(client).await.is_err()
+                    || !self.session.borrow().is_socket_connected(socket))
+            {
+                return Err(Error::UnconfirmedSocketState);
+            }
+        }
+
+        self.session.borrow_mut().sockets[socket.link_id].remote = Some(remote);
+        Ok(())
+    }
+
+    /// Receives available data of the given socket and writes it to the given buffer.
+    /// Returns zero if no data is available and the socket was closed by the remote side.
+    async fn receive_data(
+        &self,
+        client: &mut ClientState<A, D>,
+        socket: &Socket,
+        buffer: &mut [u8],
+    ) -> Result<usize, Error> {
+        self.process_urc_messages();
+
+        // Data may be dropped completely, so the overflow is reported even if no data is available
+        self.session.borrow_mut().take_receive_overflow(socket)?;
+
+        if !self.session.borrow().is_data_available(socket) {
+            if self.session.borrow().is_socket_closing(socket) {
+                return Ok(0);
+            }
+
+            return Err(Error::SocketUnconnected);
+        }
+
+        if self.session.borrow().receive_mode == ReceiveMode::Active {
+            return self.session.borrow_mut().read_received_data(socket, buffer);
+        }
+
+        let mut buffer: Buffer<RX_SIZE> = Buffer::new(buffer);
+
+        while self.session.borrow().is_data_available(socket) && !buffer.is_full() {
+            let command = ReceiveDataCommand::<RX_SIZE>::new(socket.link_id, buffer.get_next_length());
+            client.send_command(command).await?;
+
+            let received = self
+                .wait_until_timeout(&mut client.delay, URC_TIMEOUT_MS, |session| session.data.is_some())
+                .await;
+
+            if !received {
+                return Err(Error::ReceiveFailed(AtError::InvalidResponse));
+            }
+
+            let mut session = self.session.borrow_mut();
+            let data = session.data.take().unwrap();
+            session.reduce_available_data(socket, data.len());
+            buffer.append(data)?;
+        }
+
+        Ok(buffer.len())
+    }
+
+    /// Sends a chunk of data and waits for the confirmation
+    async fn send_chunk(&self, client: &mut ClientState<A, D>, data: &[u8]) -> Result<(), Error> {
+        {
+            let mut session = self.session.borrow_mut();
+            session.send_confirmed = None;
+            session.recv_byte_count = None;
+        }
+
+        client
+            .send_command::<TransmissionCommand<'_, TX_SIZE>>(TransmissionCommand::new(data))
+            .await?;
+
+        let confirmed = self
+            .wait_until_timeout(&mut client.delay, self.send_timeout_ms, |session| {
+                session.send_confirmed.is_some()
+            })
+            .await;
+
+        if !confirmed {
+            return Err(Error::SendFailed(AtError::Timeout));
+        }
+
+        let session = self.session.borrow();
+
+        // Transmission failed
+        if session.send_confirmed == Some(false) {
+            return Err(Error::SendFailed(AtError::Error));
+        }
+
+        // Byte count does not match
+        if session.is_received_byte_count_incorrect(data.len()) {
+            return Err(Error::PartialSend);
+        }
+
+        Ok(())
+    }
+
+    /// Closes the given socket and releases its link id, even in case of an error
+    ///
+    /// If the socket has already been closed by the remote side, no command is sent to the ESP-AT.
+    async fn close_socket(&self, client: &mut ClientState<A, D>, socket: &Socket) -> Result<(), Error> {
+        self.process_urc_messages();
+
+        if !self.session.borrow().is_socket_connected(socket) {
+            self.session.borrow_mut().sockets[socket.link_id] = SocketState::default();
+            return Ok(());
+        }
+
+        let mut result = client.send_command(CloseSocketCommand::new(socket.link_id)).await;
+
+        if result.is_ok() {
+            let closed = self
+                .wait_until_timeout(&mut client.delay, URC_TIMEOUT_MS, |session| {
+                    session.is_socket_closing(socket)
+                })
+                .await;
+
+            if !closed {
+                result = Err(Error::UnconfirmedSocketState);
+            }
+        }
+
+        // Setting to Closed even on error. Otherwise link id can not be reused in future.
+        self.session.borrow_mut().sockets[socket.link_id] = SocketState::default();
+
+        result?;
+        Ok(())
+    }
+
+    /// Updates the socket states by the connections reported by CIPSTATE command
+    async fn sync_socket_states(&self, client: &mut ClientState<A, D>) -> Result<(), Error> {
+        self.process_urc_messages();
+        let connections = client.send_command(ConnectionStateCommand::<MAX_SOCKETS>::new()).await?;
+        self.session.borrow_mut().sync_sockets(&connections);
+        Ok(())
+    }
+
+    /// Closes the connections which were dropped without closing, so that their link ids get released
+    async fn close_dropped_sockets(&self, client: &mut ClientState<A, D>) {
+        let dropped = self.dropped.get();
+        self.dropped.set([false; MAX_SOCKETS]);
+
+        for (link_id, _) in dropped.iter().enumerate().filter(|(_, dropped)| **dropped) {
+            // Link id is released in any case, so the error is not relevant
+            let _ = self.close_socket(client, &Socket::new(link_id)).await;
+        }
+    }
+
+    /// Enables multiple connections.
+    /// Stores internal state, so command is just sent once for saving bandwidth
+    async fn enable_multiple_connections(&self, client: &mut ClientState<A, D>) -> Result<(), Error> {
+        if self.session.borrow().multi_connections_enabled {
+            return Ok(());
+        }
+
+        client.send_command(SetMultipleConnectionsCommand::multiple()).await?;
+        self.session.borrow_mut().multi_connections_enabled = true;
+        Ok(())
+    }
+
+    /// Configures the socket receiving mode
+    /// Stores internal state, so command is just sent once for saving bandwidth
+    async fn configure_receiving_mode(&self, client: &mut ClientState<A, D>) -> Result<(), Error> {
+        if self.session.borrow().receive_mode_configured {
+            return Ok(());
+        }
+
+        let mode = self.session.borrow().receive_mode;
+        client.send_command(SetSocketReceivingModeCommand::new(mode)).await?;
+        self.session.borrow_mut().receive_mode_configured = true;
+        Ok(())
+    }
+
+    /// Assigns a free link_id. Returns an error in case no more free sockets are available
+    fn open_socket(&self) -> Result<Socket, Error> {
+        let mut session = self.session.borrow_mut();
+
+        if let Some(link_id) = session.get_next_open() {
+            session.sockets[link_id].state = ConnectionState::Open;
+            return Ok(Socket::new(link_id));
+        }
+
+        Err(Error::NoSocketAvailable)
+    }
+
+    /// Asserts that the given socket is connected and returns otherwise the appropriate error
+    fn assert_socket_connected(&self, socket: &Socket) -> Result<(), Error> {
+        let session = self.session.borrow();
+
+        if session.is_socket_closing(socket) {
+            return Err(Error::ClosingSocket);
+        }
+
+        if !session.is_socket_connected(socket) {
+            return Err(Error::SocketUnconnected);
+        }
+
+        Ok(())
+    }
+}
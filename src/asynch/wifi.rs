@@ -0,0 +1,362 @@
+//! # Async WIFI access point client
+//!
+//! Async counterpart of [WifiAdapter](crate::wifi::WifiAdapter). Errors and return types are shared with the
+//! blocking adapter.
+use crate::asynch::{Adapter, DISCONNECT_TIMEOUT_MS, RESTART_TIMEOUT_MS};
+use crate::commands::{
+    AccessPointConnectCommand, AccessPointDisconnectCommand, AccessPointInfoCommand, AutoConnectCommand, DhcpCommand,
+    GetHostnameCommand, IpConfigQueryCommand, ListAccessPointsCommand, ObtainLocalAddressCommand, RestartCommand,
+    ScanOptionsCommand, SetHostnameCommand, SoftApMacCommand, StaticIpCommand, StationMacCommand, StationStateCommand,
+    WifiModeCommand, WifiModeQueryCommand,
+};
+use crate::queue::ReceiveQueue;
+use crate::wifi::{
+    AccessPoint, AddressErrors, CommandError, ConnectedApInfo, DhcpInterface, HostnameError, JoinError, JoinState,
+    LocalAddress, MacAddress, ScanOptions, StationState, WifiMode, MAX_HOSTNAME_LENGTH,
+};
+use atat::asynch::AtatClient;
+use atat::Error as AtError;
+use core::fmt::Debug;
+use core::net::Ipv4Addr;
+use core::str::FromStr;
+use embedded_hal_async::delay::DelayNs;
+use heapless::{String, Vec};
+
+/// Async WIFI network adapter trait
+#[allow(async_fn_in_trait)]
+pub trait WifiAdapter {
+    /// Error when joining a WIFI network
+    type JoinError: Debug;
+
+    /// Error when receiving local address information
+    type AddressError: Debug;
+
+    /// Errors for configuration commands
+    type ConfigurationErrors: Debug;
+
+    /// Errors when restarting the module
+    type RestartError: Debug;
+
+    /// Errors when scanning for access points
+    type ScanError: Debug;
+
+    /// Errors when leaving the access point
+    type LeaveError: Debug;
+
+    /// Errors when querying the connection state from ESP-AT
+    type StatusError: Debug;
+
+    /// Errors when setting or querying the hostname
+    type HostnameError: Debug;
+
+    /// Connects to an WIFI access point and returns the connection state
+    async fn join(&self, ssid: &str, key: &str) -> Result<JoinState, Self::JoinError>;
+
+    /// Sets the WIFI mode. Auto connect defines if ESP-AT connects to the stored AP when switching from SoftAP
+    /// to station mode. Just supported by newer firmware versions, ESP-AT default is used if None.
+    async fn set_mode(&self, mode: WifiMode, auto_connect: Option<bool>) -> Result<(), Self::ConfigurationErrors>;
+
+    /// Returns the current WIFI mode
+    async fn get_mode(&self) -> Result<WifiMode, Self::ConfigurationErrors>;
+
+    /// Returns the current WIFI connection status
+    fn get_join_status(&self) -> JoinState;
+
+    /// Disconnects from the current access point and waits until disconnected
+    async fn leave(&self) -> Result<(), Self::LeaveError>;
+
+    /// Returns information about the joined access point, or None if not connected
+    async fn get_access_point_info(&self) -> Result<Option<ConnectedApInfo>, Self::StatusError>;
+
+    /// Queries the WIFI state of the station from ESP-AT
+    async fn get_station_state(&self) -> Result<StationState, Self::StatusError>;
+
+    /// Returns local address information
+    async fn get_address(&self) -> Result<LocalAddress, Self::AddressError>;
+
+    /// Enables/Disables DHCP of the given interface
+    async fn set_dhcp(&self, interface: DhcpInterface, enabled: bool) -> Result<(), Self::ConfigurationErrors>;
+
+    /// Sets a static IPv4 configuration of the station
+    async fn set_static_ip(
+        &self,
+        ip: Ipv4Addr,
+        gateway: Ipv4Addr,
+        netmask: Ipv4Addr,
+    ) -> Result<(), Self::ConfigurationErrors>;
+
+    /// Returns the active IP configuration of the station, including gateway and netmask
+    async fn get_ip_config(&self) -> Result<LocalAddress, Self::AddressError>;
+
+    /// Sets the MAC address of the station
+    async fn set_station_mac(&self, mac: MacAddress) -> Result<(), Self::ConfigurationErrors>;
+
+    /// Sets the MAC address of the SoftAP
+    async fn set_ap_mac(&self, mac: MacAddress) -> Result<(), Self::ConfigurationErrors>;
+
+    /// Sets the hostname of the station, e.g. as shown in DHCP tables of routers
+    async fn set_hostname(&self, hostname: &str) -> Result<(), Self::HostnameError>;
+
+    /// Returns the hostname of the station
+    async fn get_hostname(&self) -> Result<String<MAX_HOSTNAME_LENGTH>, Self::HostnameError>;
+
+    /// Enables/Disables auto connect, so that ESP-AT whether automatically joins to the stored AP when powered on.
+    async fn set_auto_connect(&self, enabled: bool) -> Result<(), Self::ConfigurationErrors>;
+
+    /// Restarts the module and waits until ready
+    async fn restart(&self) -> Result<(), Self::RestartError>;
+
+    /// Scans for access points in range. Returns up to N access points, further results and malformed ones are skipped.
+    async fn scan<const N: usize>(&self) -> Result<Vec<AccessPoint, N>, Self::ScanError>;
+
+    /// Configures sorting, reported fields and filtering of [scan()](WifiAdapter::scan) results
+    async fn set_scan_options(&self, options: ScanOptions) -> Result<(), Self::ConfigurationErrors>;
+}
+
+impl<
+        A: AtatClient,
+        D: DelayNs,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > WifiAdapter for Adapter<'_, A, D, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    type JoinError = JoinError;
+    type AddressError = AddressErrors;
+    type ConfigurationErrors = CommandError;
+    type RestartError = CommandError;
+    type ScanError = CommandError;
+    type LeaveError = CommandError;
+    type StatusError = CommandError;
+    type HostnameError = HostnameError;
+
+    /// Connects to an WIFI access point and returns the connection state
+    ///
+    /// Note:
+    /// If the connection was not successful or is lost, the ESP-AT will try independently fro time
+    /// to time (by default every second) to establish connection to the network. The status can be
+    /// queried using `get_join_state()`.
+    async fn join(&self, ssid: &str, key: &str) -> Result<JoinState, JoinError> {
+        if ssid.len() > 32 {
+            return Err(JoinError::InvalidSSDLength);
+        }
+
+        if key.len() > 63 {
+            return Err(JoinError::InvalidPasswordLength);
+        }
+
+        let mut client = self.client.lock().await;
+
+        // Keeps an active access point running, s. blocking adapter
+        let mode = match self.session.borrow().wifi_mode {
+            Some(WifiMode::SoftApStation) => None,
+            Some(WifiMode::SoftAp) => Some(WifiMode::SoftApStation),
+            _ => Some(WifiMode::Station),
+        };
+
+        if let Some(mode) = mode {
+            client
+                .send_command(WifiModeCommand::new(mode, None))
+                .await
+                .map_err(JoinError::from_mode_error)?;
+            self.session.borrow_mut().wifi_mode = Some(mode);
+        }
+
+        // Discarding reasons of previous attempts
+        self.process_urc_messages();
+        self.session.borrow_mut().join_failure = None;
+
+        let command = AccessPointConnectCommand::new(String::from_str(ssid).unwrap(), String::from_str(key).unwrap());
+        if let Err(error) = client.send_command(command).await {
+            self.process_urc_messages();
+            return Err(error.with_failure_reason(self.session.borrow_mut().join_failure.take()));
+        }
+
+        Ok(self.get_join_status())
+    }
+
+    /// Sets the WIFI mode. Auto connect defines if ESP-AT connects to the stored AP when switching from SoftAP
+    /// to station mode. Just supported by newer firmware versions, ESP-AT default is used if None.
+    async fn set_mode(&self, mode: WifiMode, auto_connect: Option<bool>) -> Result<(), CommandError> {
+        let mut client = self.client.lock().await;
+        client.send_command(WifiModeCommand::new(mode, auto_connect)).await?;
+
+        self.session.borrow_mut().wifi_mode = Some(mode);
+        Ok(())
+    }
+
+    /// Returns the current WIFI mode
+    async fn get_mode(&self) -> Result<WifiMode, CommandError> {
+        let response = self.client.lock().await.send_command(WifiModeQueryCommand::default()).await?;
+        let mode = WifiMode::try_from(response.mode).map_err(|_| CommandError::CommandFailed(AtError::Parse))?;
+
+        self.session.borrow_mut().wifi_mode = Some(mode);
+        Ok(mode)
+    }
+
+    /// Returns the current WIFI connection status
+    fn get_join_status(&self) -> JoinState {
+        self.process_urc_messages();
+
+        let session = self.session.borrow();
+        JoinState {
+            connected: session.joined,
+            ip_assigned: session.ip_assigned,
+        }
+    }
+
+    /// Disconnects from the current access point and waits until the WIFI DISCONNECT message arrived.
+    /// If not disconnected within five seconds, [CommandError::DisconnectTimeout] is returned.
+    ///
+    /// Open connections get lost, so all connections are marked as closing.
+    async fn leave(&self) -> Result<(), CommandError> {
+        let mut client = self.client.lock().await;
+
+        self.process_urc_messages();
+        client.send_command(AccessPointDisconnectCommand::default()).await?;
+        self.session.borrow_mut().close_connections();
+
+        if !self
+            .wait_until_timeout(&mut client.delay, DISCONNECT_TIMEOUT_MS, |session| !session.joined)
+            .await
+        {
+            return Err(CommandError::DisconnectTimeout);
+        }
+
+        Ok(())
+    }
+
+    /// Returns information about the joined access point, e.g. for diagnosing the link quality.
+    /// Returns None if not connected.
+    async fn get_access_point_info(&self) -> Result<Option<ConnectedApInfo>, CommandError> {
+        let mut client = self.client.lock().await;
+        Ok(client.send_command(AccessPointInfoCommand::default()).await?.pop())
+    }
+
+    /// Queries the WIFI state of the station from ESP-AT.
+    ///
+    /// As the state is authoritative, the join status gets updated accordingly, e.g. in case URC messages got lost.
+    /// Requires ESP-AT version >= 2.1.
+    async fn get_station_state(&self) -> Result<StationState, CommandError> {
+        let mut client = self.client.lock().await;
+
+        self.process_urc_messages();
+        let response = client.send_command(StationStateCommand::default()).await?;
+
+        let state = StationState::from(response.state);
+        self.session.borrow_mut().sync_station_state(state);
+        Ok(state)
+    }
+
+    /// Returns local address information
+    async fn get_address(&self) -> Result<LocalAddress, AddressErrors> {
+        let responses = self.client.lock().await.send_command(ObtainLocalAddressCommand::new()).await?;
+        LocalAddress::from_responses(responses)
+    }
+
+    /// Enables/Disables DHCP of the given interface. Disabling station DHCP without setting a static IP by
+    /// [set_static_ip()](WifiAdapter::set_static_ip) leaves the station without an IP address.
+    async fn set_dhcp(&self, interface: DhcpInterface, enabled: bool) -> Result<(), CommandError> {
+        self.client
+            .lock()
+            .await
+            .send_command(DhcpCommand::new(interface, enabled))
+            .await?;
+        Ok(())
+    }
+
+    /// Sets a static IPv4 configuration of the station. ESP-AT disables station DHCP implicitly.
+    ///
+    /// Note: The configuration is stored in flash if system storage (AT+SYSSTORE) is enabled.
+    async fn set_static_ip(&self, ip: Ipv4Addr, gateway: Ipv4Addr, netmask: Ipv4Addr) -> Result<(), CommandError> {
+        let command = StaticIpCommand::new(&ip, &gateway, &netmask);
+        self.client.lock().await.send_command(command).await?;
+        Ok(())
+    }
+
+    /// Returns the active IP configuration of the station (CIPSTA), including gateway and netmask.
+    /// In contrast to [get_address()](WifiAdapter::get_address), the MAC address is not included.
+    async fn get_ip_config(&self) -> Result<LocalAddress, AddressErrors> {
+        let responses = self.client.lock().await.send_command(IpConfigQueryCommand::default()).await?;
+        LocalAddress::from_ip_config(responses)
+    }
+
+    /// Sets the MAC address of the station. Requires station mode to be enabled.
+    ///
+    /// Note: MAC addresses of station and SoftAP need to differ. Multicast addresses are rejected by ESP-AT.
+    async fn set_station_mac(&self, mac: MacAddress) -> Result<(), CommandError> {
+        self.client.lock().await.send_command(StationMacCommand::new(&mac)).await?;
+        Ok(())
+    }
+
+    /// Sets the MAC address of the SoftAP. Requires SoftAP mode to be enabled.
+    ///
+    /// Note: MAC addresses of station and SoftAP need to differ. Multicast addresses are rejected by ESP-AT.
+    async fn set_ap_mac(&self, mac: MacAddress) -> Result<(), CommandError> {
+        self.client.lock().await.send_command(SoftApMacCommand::new(&mac)).await?;
+        Ok(())
+    }
+
+    /// Sets the hostname of the station. Hostname must consist of 1-32 alphanumeric chars or hyphens.
+    /// Requires station mode to be enabled and gets applied when (re)joining the access point.
+    async fn set_hostname(&self, hostname: &str) -> Result<(), HostnameError> {
+        let hostname = HostnameError::validate(hostname)?;
+        self.client.lock().await.send_command(SetHostnameCommand::new(hostname)).await?;
+        Ok(())
+    }
+
+    /// Returns the hostname of the station
+    async fn get_hostname(&self) -> Result<String<MAX_HOSTNAME_LENGTH>, HostnameError> {
+        Ok(self
+            .client
+            .lock()
+            .await
+            .send_command(GetHostnameCommand::default())
+            .await?
+            .hostname)
+    }
+
+    /// Enables auto connect, so that ESP-AT automatically connects to the stored AP when powered on.
+    async fn set_auto_connect(&self, enabled: bool) -> Result<(), CommandError> {
+        self.client.lock().await.send_command(AutoConnectCommand::new(enabled)).await?;
+        Ok(())
+    }
+
+    /// Restarts the module and waits until the module is ready.
+    /// If module is not ready within five seconds, [CommandError::ReadyTimeout] is returned
+    async fn restart(&self) -> Result<(), CommandError> {
+        let mut client = self.client.lock().await;
+
+        self.session.borrow_mut().ready = false;
+        client.send_command(RestartCommand::default()).await?;
+
+        self.session.borrow_mut().reset();
+        self.dropped.set([false; MAX_SOCKETS]);
+
+        if !self
+            .wait_until_timeout(&mut client.delay, RESTART_TIMEOUT_MS, |session| session.ready)
+            .await
+        {
+            return Err(CommandError::ReadyTimeout);
+        }
+
+        Ok(())
+    }
+
+    /// Scans for access points in range. Returns up to N access points, further results and malformed ones are skipped.
+    /// Sorting and filtering may be configured by [set_scan_options()](WifiAdapter::set_scan_options).
+    async fn scan<const N: usize>(&self) -> Result<Vec<AccessPoint, N>, CommandError> {
+        let mut client = self.client.lock().await;
+        let fields = self.session.borrow().scan_fields.unwrap_or(ScanOptions::ALL_FIELDS);
+        client.send_command(ListAccessPointsCommand::new(fields)).await
+    }
+
+    /// Configures sorting, reported fields and filtering of [scan()](WifiAdapter::scan) results
+    async fn set_scan_options(&self, options: ScanOptions) -> Result<(), CommandError> {
+        self.client.lock().await.send_command(ScanOptionsCommand::new(&options)).await?;
+        self.session.borrow_mut().scan_fields = Some(options.fields);
+        Ok(())
+    }
+}
@@ -1,12 +1,30 @@
+use crate::dns::{DnsConfig, DnsError, MAX_DNS_SERVERS, MAX_DOMAIN_LENGTH};
+use crate::ping::PingError;
+use crate::responses::ConnectionStateResponse;
+use crate::responses::DomainResolveResponse;
+use crate::responses::HostnameResponse;
+use crate::responses::IpConfigResponse;
 use crate::responses::LocalAddressResponse;
 use crate::responses::NoResponse;
-use crate::stack::Error as StackError;
-use crate::wifi::{AddressErrors, CommandError, JoinError};
+use crate::responses::PingResponse;
+use crate::responses::ReceiveLengthResponse;
+use crate::responses::SntpTimeResponse;
+use crate::responses::StationResponse;
+use crate::responses::StationStateResponse;
+use crate::responses::WifiModeResponse;
+use crate::sntp::{SntpError, MAX_SNTP_SERVERS, MAX_SNTP_SERVER_LENGTH};
+use crate::softap::{SoftApConfig, SoftApError};
+use crate::stack::{Error as StackError, ReceiveMode};
+use crate::tls::{TlsAuthMode, MAX_ALPN_COUNT, MAX_ALPN_LENGTH, MAX_SNI_LENGTH};
+use crate::wifi::{
+    AccessPoint, AddressErrors, AuthMode, CommandError, ConnectedApInfo, DhcpInterface, HostnameError, JoinError,
+    MacAddress, ScanOptions, WifiMode, MAX_ADDRESS_RESPONSES, MAX_HOSTNAME_LENGTH,
+};
 use atat::atat_derive::AtatCmd;
 use atat::heapless::{String, Vec};
 use atat::{AtatCmd, Error as AtError, InternalError};
 use core::fmt::Write;
-use core::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
 use core::str::FromStr;
 use numtoa::NumToA;
 
@@ -34,20 +52,42 @@ pub struct WifiModeCommand {
     ///     3: SoftAP+Station mode.
     #[at_arg(position = 0)]
     mode: usize,
+
+    /// Whether to automatically connect to the stored AP when switching from SoftAP to station mode.
+    /// Just supported by newer firmware versions, ESP-AT default (enabled) is used if None.
+    #[at_arg(position = 1)]
+    auto_connect: Option<usize>,
 }
 
 impl WifiModeCommand {
-    pub fn station_mode() -> Self {
-        Self { mode: 1 }
+    pub fn new(mode: WifiMode, auto_connect: Option<bool>) -> Self {
+        Self {
+            mode: mode.into(),
+            auto_connect: auto_connect.map(|enabled| enabled as usize),
+        }
     }
 }
 
 impl CommandErrorHandler for WifiModeCommand {
-    type Error = JoinError;
-    const WOULD_BLOCK_ERROR: Self::Error = JoinError::UnexpectedWouldBlock;
+    type Error = CommandError;
+    const WOULD_BLOCK_ERROR: Self::Error = CommandError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        CommandError::CommandFailed(error)
+    }
+}
+
+/// Queries the current WIFI mode
+#[derive(Clone, Default, AtatCmd)]
+#[at_cmd("+CWMODE?", WifiModeResponse, timeout_ms = 1_000)]
+pub struct WifiModeQueryCommand {}
+
+impl CommandErrorHandler for WifiModeQueryCommand {
+    type Error = CommandError;
+    const WOULD_BLOCK_ERROR: Self::Error = CommandError::UnexpectedWouldBlock;
 
     fn command_error(&self, error: AtError) -> Self::Error {
-        JoinError::ModeError(error)
+        CommandError::CommandFailed(error)
     }
 }
 
@@ -77,7 +117,10 @@ impl CommandErrorHandler for AutoConnectCommand {
     }
 }
 
-/// Command for setting the target WIFI access point parameters
+/// Command for setting the target WIFI access point parameters.
+///
+/// On failure ESP-AT reports the reason by `+CWJAP:<code>` in front of the ERROR response. As the generic error
+/// matcher of atat drops this line, it is handled as URC message ([URCMessages::JoinFailed](crate::urc::URCMessages)).
 #[derive(Clone, Default, AtatCmd)]
 #[at_cmd("+CWJAP", NoResponse, timeout_ms = 20_000)]
 pub struct AccessPointConnectCommand {
@@ -106,6 +149,68 @@ impl CommandErrorHandler for AccessPointConnectCommand {
     }
 }
 
+/// Disconnects from the current access point
+#[derive(Clone, Default, AtatCmd)]
+#[at_cmd("+CWQAP", NoResponse, timeout_ms = 5_000)]
+pub struct AccessPointDisconnectCommand {}
+
+impl CommandErrorHandler for AccessPointDisconnectCommand {
+    type Error = CommandError;
+    const WOULD_BLOCK_ERROR: Self::Error = CommandError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        CommandError::CommandFailed(error)
+    }
+}
+
+/// Queries information about the currently joined access point
+#[derive(Clone, Default)]
+pub struct AccessPointInfoCommand {}
+
+impl AtatCmd for AccessPointInfoCommand {
+    /// Empty if not connected to an access point
+    type Response = Vec<ConnectedApInfo, 1>;
+
+    const MAX_LEN: usize = 11;
+    const MAX_TIMEOUT_MS: u32 = 1_000;
+
+    fn write(&self, buf: &mut [u8]) -> usize {
+        buf[..11].copy_from_slice(b"AT+CWJAP?\r\n");
+        11
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, AtError> {
+        if resp.is_err() {
+            return Err(AtError::InvalidResponse);
+        }
+
+        ConnectedApInfo::from_response(resp.unwrap()).ok_or(AtError::Parse)
+    }
+}
+
+impl CommandErrorHandler for AccessPointInfoCommand {
+    type Error = CommandError;
+    const WOULD_BLOCK_ERROR: Self::Error = CommandError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        CommandError::CommandFailed(error)
+    }
+}
+
+/// Queries the WIFI state of the station
+#[derive(Clone, Default, AtatCmd)]
+#[at_cmd("+CWSTATE?", StationStateResponse, timeout_ms = 1_000)]
+pub struct StationStateCommand {}
+
+impl CommandErrorHandler for StationStateCommand {
+    type Error = CommandError;
+    const WOULD_BLOCK_ERROR: Self::Error = CommandError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        CommandError::CommandFailed(error)
+    }
+}
+
 /// Command for receiving local address information including IP and MAC
 #[derive(Clone)]
 pub struct ObtainLocalAddressCommand {}
@@ -117,7 +222,7 @@ impl ObtainLocalAddressCommand {
 }
 
 impl AtatCmd for ObtainLocalAddressCommand {
-    type Response = Vec<LocalAddressResponse, 4>;
+    type Response = Vec<LocalAddressResponse, MAX_ADDRESS_RESPONSES>;
 
     const MAX_LEN: usize = 10;
     const MAX_TIMEOUT_MS: u32 = 5_000;
@@ -132,7 +237,7 @@ impl AtatCmd for ObtainLocalAddressCommand {
             return Err(AtError::InvalidResponse);
         }
 
-        atat::serde_at::from_slice::<Vec<LocalAddressResponse, 4>>(resp.unwrap()).map_err(|_| AtError::Parse)
+        atat::serde_at::from_slice::<Self::Response>(resp.unwrap()).map_err(|_| AtError::Parse)
     }
 }
 
@@ -145,220 +250,1144 @@ impl CommandErrorHandler for ObtainLocalAddressCommand {
     }
 }
 
-/// Enables/Disables multiple connections
+/// Enables/Disables DHCP of station and/or SoftAP
 #[derive(Clone, AtatCmd)]
-#[at_cmd("+CIPMUX", NoResponse, timeout_ms = 1_000)]
-pub struct SetMultipleConnectionsCommand {
-    /// 0: single connection, 1: multiple connections
+#[at_cmd("+CWDHCP", NoResponse, timeout_ms = 1_000)]
+pub struct DhcpCommand {
+    /// 0: Disables DHCP, 1: Enables DHCP
+    #[at_arg(position = 0)]
+    operate: usize,
+
+    /// Bit 0: Station DHCP, bit 1: SoftAP DHCP
+    #[at_arg(position = 1)]
     mode: usize,
 }
 
-impl SetMultipleConnectionsCommand {
-    /// Enables multiple connections
-    pub fn multiple() -> Self {
-        Self { mode: 1 }
+impl DhcpCommand {
+    pub fn new(interface: DhcpInterface, enabled: bool) -> Self {
+        Self {
+            operate: enabled as usize,
+            mode: interface.into(),
+        }
     }
 }
 
-impl CommandErrorHandler for SetMultipleConnectionsCommand {
-    type Error = StackError;
-    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+impl CommandErrorHandler for DhcpCommand {
+    type Error = CommandError;
+    const WOULD_BLOCK_ERROR: Self::Error = CommandError::UnexpectedWouldBlock;
 
     fn command_error(&self, error: AtError) -> Self::Error {
-        StackError::EnablingMultiConnectionsFailed(error)
+        CommandError::CommandFailed(error)
     }
 }
 
-/// Sets the socket receiving mode
+/// Sets a static IPv4 address of the station. Disables DHCP of the station.
 #[derive(Clone, AtatCmd)]
-#[at_cmd("+CIPRECVMODE", NoResponse, timeout_ms = 1_000)]
-pub struct SetSocketReceivingModeCommand {
-    /// 0: active mode => ESP-AT will send all the received socket data instantly to the host MCU
-    /// 1: passive mode => ESP-AT will keep the received socket data in an internal buffer
-    mode: usize,
+#[at_cmd("+CIPSTA", NoResponse, timeout_ms = 1_000)]
+pub struct StaticIpCommand {
+    /// IPv4 address
+    #[at_arg(position = 0)]
+    ip: String<MAX_IP_LENGTH>,
+
+    /// IPv4 address of the gateway
+    #[at_arg(position = 1)]
+    gateway: String<MAX_IP_LENGTH>,
+
+    /// Netmask, e.g. 255.255.255.0
+    #[at_arg(position = 2)]
+    netmask: String<MAX_IP_LENGTH>,
 }
 
-impl SetSocketReceivingModeCommand {
-    /// Enables the passive receiving mode
-    pub fn passive_mode() -> Self {
-        Self { mode: 1 }
+impl StaticIpCommand {
+    pub fn new(ip: &Ipv4Addr, gateway: &Ipv4Addr, netmask: &Ipv4Addr) -> Self {
+        Self {
+            ip: ipv4_to_string(ip),
+            gateway: ipv4_to_string(gateway),
+            netmask: ipv4_to_string(netmask),
+        }
     }
 }
 
-impl CommandErrorHandler for SetSocketReceivingModeCommand {
-    type Error = StackError;
-    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+impl CommandErrorHandler for StaticIpCommand {
+    type Error = CommandError;
+    const WOULD_BLOCK_ERROR: Self::Error = CommandError::UnexpectedWouldBlock;
 
     fn command_error(&self, error: AtError) -> Self::Error {
-        StackError::EnablingPassiveSocketModeFailed(error)
+        CommandError::CommandFailed(error)
     }
 }
 
-/// Establish TCP Connection, UDP Transmission, or SSL Connection
-#[derive(Clone, AtatCmd)]
-#[at_cmd("+CIPSTART", NoResponse, timeout_ms = 5_000)]
-pub struct ConnectCommand {
-    /// Socket ID
-    link_id: usize,
+/// Queries the active IP configuration of the station
+#[derive(Clone, Default)]
+pub struct IpConfigQueryCommand {}
 
-    /// Connection type, e.g. TCP, TCPv6, SSL, etc.
-    connection_type: String<5>,
+impl AtatCmd for IpConfigQueryCommand {
+    type Response = Vec<IpConfigResponse, 5>;
 
-    /// Remote IPv4 or IPV6 address
-    remote_host: String<MAX_IP_LENGTH>,
+    const MAX_LEN: usize = 12;
+    const MAX_TIMEOUT_MS: u32 = 1_000;
 
-    /// Remote port
-    port: u16,
-}
+    fn write(&self, buf: &mut [u8]) -> usize {
+        buf[..12].copy_from_slice(b"AT+CIPSTA?\r\n");
+        12
+    }
 
-/// Convert a `IPv4Addr` to a heapless `String`
-fn ipv4_to_string(ip: &Ipv4Addr) -> String<MAX_IP_LENGTH> {
-    let mut ip_string = String::new();
-    let mut num_buf = [0u8; 3];
-    for (i, octet) in ip.octets().iter().enumerate() {
-        ip_string.write_str(octet.numtoa_str(10, &mut num_buf)).unwrap();
-        if i != 3 {
-            ip_string.write_char('.').unwrap();
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, AtError> {
+        if resp.is_err() {
+            return Err(AtError::InvalidResponse);
         }
+
+        IpConfigResponse::from_response(resp.unwrap()).ok_or(AtError::Parse)
     }
-    ip_string
 }
 
-/// Convert a `SocketAddrV6` IP to a heapless `String`
-fn ipv6_to_string(ip: &Ipv6Addr) -> String<MAX_IP_LENGTH> {
-    let mut ip_string = String::new();
-    let mut hex_buf = [0u8; 4];
-    for (i, segment) in ip.segments().iter().enumerate() {
-        // Write segment (hexadectet)
-        if segment == &0 {
-            // All-zero-segments can be shortened
-            ip_string.write_str("0").unwrap()
-        } else {
-            // Hex-encode IPv6 segment
-            base16::encode_config_slice(&segment.to_be_bytes(), base16::EncodeLower, &mut hex_buf);
-            ip_string
-                // Safety: The result from hex-encoding will always be valid UTF-8
-                .write_str(unsafe { core::str::from_utf8_unchecked(&hex_buf) })
-                .unwrap();
-        }
+impl CommandErrorHandler for IpConfigQueryCommand {
+    type Error = AddressErrors;
+    const WOULD_BLOCK_ERROR: Self::Error = AddressErrors::UnexpectedWouldBlock;
 
-        // Write separator
-        if i != 7 {
-            ip_string.write_char(':').unwrap();
-        }
+    fn command_error(&self, error: AtError) -> Self::Error {
+        AddressErrors::CommandError(error)
     }
-    ip_string
 }
 
-impl ConnectCommand {
-    /// Establishes a IPv4 TCP connection
-    pub fn tcp_v4(link_id: usize, remote: SocketAddrV4) -> Self {
-        Self {
-            link_id,
-            connection_type: String::from_str("TCP").unwrap(),
-            remote_host: ipv4_to_string(remote.ip()),
-            port: remote.port(),
-        }
+/// Sets the hostname of the station
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CWHOSTNAME", NoResponse, timeout_ms = 1_000)]
+pub struct SetHostnameCommand {
+    /// Hostname, max. 32 chars
+    hostname: String<MAX_HOSTNAME_LENGTH>,
+}
+
+impl SetHostnameCommand {
+    pub fn new(hostname: String<MAX_HOSTNAME_LENGTH>) -> Self {
+        Self { hostname }
     }
+}
 
-    /// Establishes a IPv6 TCP connection
-    pub fn tcp_v6(link_id: usize, remote: SocketAddrV6) -> Self {
+impl CommandErrorHandler for SetHostnameCommand {
+    type Error = HostnameError;
+    const WOULD_BLOCK_ERROR: Self::Error = HostnameError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        HostnameError::CommandFailed(error)
+    }
+}
+
+/// Queries the hostname of the station
+#[derive(Clone, Default, AtatCmd)]
+#[at_cmd("+CWHOSTNAME?", HostnameResponse, timeout_ms = 1_000)]
+pub struct GetHostnameCommand {}
+
+impl CommandErrorHandler for GetHostnameCommand {
+    type Error = HostnameError;
+    const WOULD_BLOCK_ERROR: Self::Error = HostnameError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        HostnameError::CommandFailed(error)
+    }
+}
+
+/// Sets the MAC address of the station
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSTAMAC", NoResponse, timeout_ms = 1_000)]
+pub struct StationMacCommand {
+    /// MAC address, e.g. "1a:fe:36:97:d5:7b"
+    mac: String<17>,
+}
+
+impl StationMacCommand {
+    pub fn new(mac: &MacAddress) -> Self {
         Self {
-            link_id,
-            connection_type: String::from_str("TCPv6").unwrap(),
-            remote_host: ipv6_to_string(remote.ip()),
-            port: remote.port(),
+            mac: mac_to_string(mac),
         }
     }
 }
 
-impl CommandErrorHandler for ConnectCommand {
-    type Error = StackError;
-    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+impl CommandErrorHandler for StationMacCommand {
+    type Error = CommandError;
+    const WOULD_BLOCK_ERROR: Self::Error = CommandError::UnexpectedWouldBlock;
 
     fn command_error(&self, error: AtError) -> Self::Error {
-        StackError::ConnectError(error)
+        CommandError::CommandFailed(error)
     }
 }
 
-/// Initiates the transmission of data
+/// Sets the MAC address of the SoftAP
 #[derive(Clone, AtatCmd)]
-#[at_cmd("+CIPSEND", NoResponse, timeout_ms = 1_000)]
-pub struct TransmissionPrepareCommand {
-    /// Socket ID
-    link_id: usize,
-
-    /// Length of the data to transmit
-    length: usize,
+#[at_cmd("+CIPAPMAC", NoResponse, timeout_ms = 1_000)]
+pub struct SoftApMacCommand {
+    /// MAC address, e.g. "1a:fe:36:97:d5:7b"
+    mac: String<17>,
 }
 
-impl TransmissionPrepareCommand {
-    pub fn new(link_id: usize, length: usize) -> Self {
-        Self { link_id, length }
+impl SoftApMacCommand {
+    pub fn new(mac: &MacAddress) -> Self {
+        Self {
+            mac: mac_to_string(mac),
+        }
     }
 }
 
-impl CommandErrorHandler for TransmissionPrepareCommand {
-    type Error = StackError;
-    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+impl CommandErrorHandler for SoftApMacCommand {
+    type Error = CommandError;
+    const WOULD_BLOCK_ERROR: Self::Error = CommandError::UnexpectedWouldBlock;
 
     fn command_error(&self, error: AtError) -> Self::Error {
-        StackError::TransmissionStartFailed(error)
+        CommandError::CommandFailed(error)
     }
 }
 
-/// The actual transmission of data. Max. data length: 256 bytes
-pub struct TransmissionCommand<'a, const MAX_LEN: usize> {
-    data: &'a [u8],
+/// Lists the access points in range
+#[derive(Clone)]
+pub struct ListAccessPointsCommand<const N: usize> {
+    /// Fields included in the response lines, as configured by CWLAPOPT
+    fields: u16,
 }
 
-impl<'a, const MAX_LEN: usize> TransmissionCommand<'a, MAX_LEN> {
-    pub fn new(data: &'a [u8]) -> Self {
-        Self { data }
+impl<const N: usize> ListAccessPointsCommand<N> {
+    pub fn new(fields: u16) -> Self {
+        Self { fields }
     }
 }
 
-impl<const MAX_LEN: usize> AtatCmd for TransmissionCommand<'_, MAX_LEN> {
-    type Response = NoResponse;
-    const MAX_LEN: usize = MAX_LEN;
+impl<const N: usize> AtatCmd for ListAccessPointsCommand<N> {
+    type Response = Vec<AccessPoint, N>;
 
-    const MAX_TIMEOUT_MS: u32 = 5000;
-    const EXPECTS_RESPONSE_CODE: bool = false;
+    const MAX_LEN: usize = 10;
+    const MAX_TIMEOUT_MS: u32 = 10_000;
 
     fn write(&self, buf: &mut [u8]) -> usize {
-        buf[..self.data.len()].copy_from_slice(self.data);
-        self.data.len()
+        buf[..10].copy_from_slice(b"AT+CWLAP\r\n");
+        10
     }
 
-    fn parse(&self, _resp: Result<&[u8], InternalError>) -> Result<Self::Response, AtError> {
-        Ok(NoResponse {})
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, AtError> {
+        if resp.is_err() {
+            return Err(AtError::InvalidResponse);
+        }
+
+        AccessPoint::from_response(resp.unwrap(), self.fields).ok_or(AtError::Parse)
     }
 }
 
-impl<const MAX_LEN: usize> CommandErrorHandler for TransmissionCommand<'_, MAX_LEN> {
-    type Error = StackError;
-    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+impl<const N: usize> CommandErrorHandler for ListAccessPointsCommand<N> {
+    type Error = CommandError;
+    const WOULD_BLOCK_ERROR: Self::Error = CommandError::UnexpectedWouldBlock;
 
     fn command_error(&self, error: AtError) -> Self::Error {
-        StackError::SendFailed(error)
+        CommandError::CommandFailed(error)
     }
 }
 
-/// Command for receiving data
+/// Configures sorting, fields and filtering of the access point list
 #[derive(Clone, AtatCmd)]
-#[at_cmd("+CIPRECVDATA", NoResponse, timeout_ms = 1_000)]
-pub struct ReceiveDataCommand<const RESP_LEN: usize> {
-    /// Socket ID
-    link_id: usize,
+#[at_cmd("+CWLAPOPT", NoResponse, timeout_ms = 1_000)]
+pub struct ScanOptionsCommand {
+    /// 0: unsorted, 1: sorted by RSSI
+    sort_enabled: usize,
 
-    /// Length in bytes to receive
-    length: usize,
-}
+    /// Bitmask of the fields included in the CWLAP response
+    print_mask: u16,
 
-impl<const RESP_LEN: usize> ReceiveDataCommand<RESP_LEN> {
-    pub fn new(link_id: usize, length: usize) -> Self {
-        Self { link_id, length }
-    }
+    /// Min. RSSI of listed access points
+    rssi_filter: Option<i16>,
+
+    /// Bitmask of listed authentication modes
+    auth_mode_mask: Option<u16>,
+}
+
+impl ScanOptionsCommand {
+    pub fn new(options: &ScanOptions) -> Self {
+        // Parameters are positional, so RSSI filter is required when setting the auth mode mask
+        let rssi_filter = match options.auth_mode_mask {
+            None => options.rssi_filter,
+            Some(_) => Some(options.rssi_filter.unwrap_or(-100)),
+        };
+
+        Self {
+            sort_enabled: options.sort_by_rssi as usize,
+            print_mask: options.fields,
+            rssi_filter,
+            auth_mode_mask: options.auth_mode_mask,
+        }
+    }
+}
+
+impl CommandErrorHandler for ScanOptionsCommand {
+    type Error = CommandError;
+    const WOULD_BLOCK_ERROR: Self::Error = CommandError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        CommandError::CommandFailed(error)
+    }
+}
+
+/// Configures the access point of SoftAP mode
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CWSAP", NoResponse, timeout_ms = 5_000)]
+pub struct SoftApConfigurationCommand {
+    /// SSID of the access point
+    ssid: String<32>,
+
+    /// Password of the access point
+    password: String<63>,
+
+    /// WIFI channel
+    channel: u8,
+
+    /// Encryption: 0: OPEN, 2: WPA_PSK, 3: WPA2_PSK, 4: WPA_WPA2_PSK
+    encryption: usize,
+
+    /// Max. number of connected stations
+    max_connections: Option<u8>,
+
+    /// 0: SSID is broadcast, 1: SSID is hidden
+    hidden: Option<usize>,
+}
+
+impl SoftApConfigurationCommand {
+    pub fn new(ssid: String<32>, password: String<63>, config: &SoftApConfig) -> Result<Self, SoftApError> {
+        let encryption = match config.encryption {
+            AuthMode::Open => 0,
+            AuthMode::WpaPsk => 2,
+            AuthMode::Wpa2Psk => 3,
+            AuthMode::WpaWpa2Psk => 4,
+            _ => return Err(SoftApError::UnsupportedEncryption),
+        };
+
+        // Parameters are positional, so max. connections are required when hiding the SSID
+        let max_connections = match config.hidden {
+            false => config.max_connections,
+            true => Some(config.max_connections.unwrap_or(10)),
+        };
+
+        Ok(Self {
+            ssid,
+            password,
+            channel: config.channel,
+            encryption,
+            max_connections,
+            hidden: config.hidden.then_some(1),
+        })
+    }
+}
+
+impl CommandErrorHandler for SoftApConfigurationCommand {
+    type Error = SoftApError;
+    const WOULD_BLOCK_ERROR: Self::Error = SoftApError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        SoftApError::ConfigurationError(error)
+    }
+}
+
+/// Lists the stations connected to the access point
+#[derive(Clone)]
+pub struct ListStationsCommand<const N: usize> {}
+
+impl<const N: usize> ListStationsCommand<N> {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<const N: usize> AtatCmd for ListStationsCommand<N> {
+    type Response = Vec<StationResponse, N>;
+
+    const MAX_LEN: usize = 10;
+    const MAX_TIMEOUT_MS: u32 = 1_000;
+
+    fn write(&self, buf: &mut [u8]) -> usize {
+        buf[..10].copy_from_slice(b"AT+CWLIF\r\n");
+        10
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, AtError> {
+        if resp.is_err() {
+            return Err(AtError::InvalidResponse);
+        }
+
+        StationResponse::from_response(resp.unwrap()).ok_or(AtError::Parse)
+    }
+}
+
+impl<const N: usize> CommandErrorHandler for ListStationsCommand<N> {
+    type Error = SoftApError;
+    const WOULD_BLOCK_ERROR: Self::Error = SoftApError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        SoftApError::ListStationsError(error)
+    }
+}
+
+/// Disconnects stations from the access point
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CWQIF", NoResponse, timeout_ms = 1_000)]
+pub struct DisconnectStationCommand {
+    /// MAC address of the station, all stations are disconnected if None
+    mac: Option<String<17>>,
+}
+
+impl DisconnectStationCommand {
+    pub fn new(mac: Option<&MacAddress>) -> Self {
+        Self {
+            mac: mac.map(mac_to_string),
+        }
+    }
+}
+
+impl CommandErrorHandler for DisconnectStationCommand {
+    type Error = SoftApError;
+    const WOULD_BLOCK_ERROR: Self::Error = SoftApError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        SoftApError::DisconnectStationError(error)
+    }
+}
+
+/// Enables/Disables multiple connections
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPMUX", NoResponse, timeout_ms = 1_000)]
+pub struct SetMultipleConnectionsCommand {
+    /// 0: single connection, 1: multiple connections
+    mode: usize,
+}
+
+impl SetMultipleConnectionsCommand {
+    /// Enables multiple connections
+    pub fn multiple() -> Self {
+        Self { mode: 1 }
+    }
+}
+
+impl CommandErrorHandler for SetMultipleConnectionsCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::EnablingMultiConnectionsFailed(error)
+    }
+}
+
+/// Sets the socket receiving mode
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPRECVMODE", NoResponse, timeout_ms = 1_000)]
+pub struct SetSocketReceivingModeCommand {
+    /// 0: active mode => ESP-AT will send all the received socket data instantly to the host MCU
+    /// 1: passive mode => ESP-AT will keep the received socket data in an internal buffer
+    mode: usize,
+}
+
+impl SetSocketReceivingModeCommand {
+    /// Sets the given receiving mode
+    pub fn new(mode: ReceiveMode) -> Self {
+        let mode = match mode {
+            ReceiveMode::Active => 0,
+            ReceiveMode::Passive => 1,
+        };
+
+        Self { mode }
+    }
+}
+
+impl CommandErrorHandler for SetSocketReceivingModeCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::ConfiguringReceiveModeFailed(error)
+    }
+}
+
+/// Enables/Disables showing the remote IP and port with +IPD and +CIPRECVDATA
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPDINFO", NoResponse, timeout_ms = 1_000)]
+pub struct SetRemoteInfoCommand {
+    /// 0: remote address is not shown, 1: remote address is shown
+    mode: usize,
+}
+
+impl SetRemoteInfoCommand {
+    /// Enables showing the remote address
+    pub fn enabled() -> Self {
+        Self { mode: 1 }
+    }
+}
+
+impl CommandErrorHandler for SetRemoteInfoCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::EnablingRemoteInfoFailed(error)
+    }
+}
+
+/// Establish TCP Connection, UDP Transmission, or SSL Connection
+/// Timeout covers the TLS handshake of SSL connections
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSTART", NoResponse, timeout_ms = 10_000)]
+pub struct ConnectCommand {
+    /// Socket ID
+    link_id: usize,
+
+    /// Connection type, e.g. TCP, TCPv6, SSL, etc.
+    connection_type: String<5>,
+
+    /// Remote IPv4 or IPV6 address
+    remote_host: String<MAX_IP_LENGTH>,
+
+    /// Remote port
+    port: u16,
+
+    /// Local port, just relevant for UDP
+    local_port: Option<u16>,
+
+    /// UDP mode
+    ///     0: Remote address and port are fixed
+    ///     1: Remote address and port will change once, by the first received datagram
+    ///     2: Remote address and port will change by any received datagram
+    mode: Option<usize>,
+}
+
+/// Convert a `IpAddr` to a heapless `String`
+fn ip_to_string(ip: &IpAddr) -> String<MAX_IP_LENGTH> {
+    match ip {
+        IpAddr::V4(ip) => ipv4_to_string(ip),
+        IpAddr::V6(ip) => ipv6_to_string(ip),
+    }
+}
+
+/// Convert a `IPv4Addr` to a heapless `String`
+fn ipv4_to_string(ip: &Ipv4Addr) -> String<MAX_IP_LENGTH> {
+    let mut ip_string = String::new();
+    let mut num_buf = [0u8; 3];
+    for (i, octet) in ip.octets().iter().enumerate() {
+        ip_string.write_str(octet.numtoa_str(10, &mut num_buf)).unwrap();
+        if i != 3 {
+            ip_string.write_char('.').unwrap();
+        }
+    }
+    ip_string
+}
+
+/// Convert a `MacAddress` to a heapless `String`
+fn mac_to_string(mac: &MacAddress) -> String<17> {
+    let mut mac_string = String::new();
+    write!(mac_string, "{}", mac).unwrap();
+    mac_string
+}
+
+/// Convert a `SocketAddrV6` IP to a heapless `String`
+fn ipv6_to_string(ip: &Ipv6Addr) -> String<MAX_IP_LENGTH> {
+    let mut ip_string = String::new();
+    let mut hex_buf = [0u8; 4];
+    for (i, segment) in ip.segments().iter().enumerate() {
+        // Write segment (hexadectet)
+        if segment == &0 {
+            // All-zero-segments can be shortened
+            ip_string.write_str("0").unwrap()
+        } else {
+            // Hex-encode IPv6 segment
+            base16::encode_config_slice(&segment.to_be_bytes(), base16::EncodeLower, &mut hex_buf);
+            ip_string
+                // Safety: The result from hex-encoding will always be valid UTF-8
+                .write_str(unsafe { core::str::from_utf8_unchecked(&hex_buf) })
+                .unwrap();
+        }
+
+        // Write separator
+        if i != 7 {
+            ip_string.write_char(':').unwrap();
+        }
+    }
+    ip_string
+}
+
+impl ConnectCommand {
+    /// Establishes a IPv4 TCP connection
+    pub fn tcp_v4(link_id: usize, remote: SocketAddrV4) -> Self {
+        Self {
+            link_id,
+            connection_type: String::from_str("TCP").unwrap(),
+            remote_host: ipv4_to_string(remote.ip()),
+            port: remote.port(),
+            local_port: None,
+            mode: None,
+        }
+    }
+
+    /// Establishes a IPv6 TCP connection
+    pub fn tcp_v6(link_id: usize, remote: SocketAddrV6) -> Self {
+        Self {
+            link_id,
+            connection_type: String::from_str("TCPv6").unwrap(),
+            remote_host: ipv6_to_string(remote.ip()),
+            port: remote.port(),
+            local_port: None,
+            mode: None,
+        }
+    }
+
+    /// Establishes a IPv4 UDP transmission
+    pub fn udp_v4(link_id: usize, remote: SocketAddrV4) -> Self {
+        Self {
+            link_id,
+            connection_type: String::from_str("UDP").unwrap(),
+            remote_host: ipv4_to_string(remote.ip()),
+            port: remote.port(),
+            local_port: None,
+            mode: None,
+        }
+    }
+
+    /// Establishes a IPv6 UDP transmission
+    pub fn udp_v6(link_id: usize, remote: SocketAddrV6) -> Self {
+        Self {
+            link_id,
+            connection_type: String::from_str("UDPv6").unwrap(),
+            remote_host: ipv6_to_string(remote.ip()),
+            port: remote.port(),
+            local_port: None,
+            mode: None,
+        }
+    }
+
+    /// Establishes a IPv4 SSL connection
+    pub fn ssl_v4(link_id: usize, remote: SocketAddrV4) -> Self {
+        Self {
+            link_id,
+            connection_type: String::from_str("SSL").unwrap(),
+            remote_host: ipv4_to_string(remote.ip()),
+            port: remote.port(),
+            local_port: None,
+            mode: None,
+        }
+    }
+
+    /// Establishes a IPv6 SSL connection
+    pub fn ssl_v6(link_id: usize, remote: SocketAddrV6) -> Self {
+        Self {
+            link_id,
+            connection_type: String::from_str("SSLv6").unwrap(),
+            remote_host: ipv6_to_string(remote.ip()),
+            port: remote.port(),
+            local_port: None,
+            mode: None,
+        }
+    }
+
+    /// Opens a IPv4 UDP transmission bound to the given local port, accepting datagrams of any remote
+    pub fn udp_bind(link_id: usize, local_port: u16) -> Self {
+        Self {
+            link_id,
+            connection_type: String::from_str("UDP").unwrap(),
+            remote_host: String::from_str("0.0.0.0").unwrap(),
+            port: 0,
+            local_port: Some(local_port),
+            mode: Some(2),
+        }
+    }
+
+    /// Returns the connection type, e.g. TCP, TCPv6, SSL, etc.
+    pub(crate) fn connection_type(&self) -> &str {
+        self.connection_type.as_str()
+    }
+
+    /// Returns the local port, just set for bound UDP sockets
+    pub(crate) fn local_port(&self) -> Option<u16> {
+        self.local_port
+    }
+}
+
+impl CommandErrorHandler for ConnectCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        if self.connection_type.starts_with("SSL") {
+            return StackError::TlsConnectError(error);
+        }
+
+        StackError::ConnectError(error)
+    }
+}
+
+/// Configures the certificate verification of a SSL connection
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSSLCCONF", NoResponse, timeout_ms = 1_000)]
+pub struct SslConfigurationCommand {
+    /// Socket ID
+    link_id: usize,
+
+    /// Authentication mode
+    ///     0: no authentication
+    ///     1: client provides certificate for the server to verify
+    ///     2: client loads CA certificate to verify the server certificate
+    ///     3: mutual authentication
+    auth_mode: usize,
+
+    /// Index of the client certificate and private key
+    pki_number: Option<usize>,
+
+    /// Index of the CA certificate
+    ca_number: Option<usize>,
+}
+
+impl SslConfigurationCommand {
+    pub fn new(link_id: usize, auth_mode: TlsAuthMode) -> Self {
+        let (auth_mode, pki_number, ca_number) = match auth_mode {
+            TlsAuthMode::None => (0, None, None),
+            TlsAuthMode::ClientCertificate { pki_number } => (1, Some(pki_number), Some(0)),
+            TlsAuthMode::ServerVerification { ca_number } => (2, Some(0), Some(ca_number)),
+            TlsAuthMode::Mutual { pki_number, ca_number } => (3, Some(pki_number), Some(ca_number)),
+        };
+
+        Self {
+            link_id,
+            auth_mode,
+            pki_number,
+            ca_number,
+        }
+    }
+}
+
+impl CommandErrorHandler for SslConfigurationCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::TlsConfigurationError(error)
+    }
+}
+
+/// Sets the server name indication (SNI) of a SSL connection
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSSLCSNI", NoResponse, timeout_ms = 1_000)]
+pub struct SslServerNameCommand {
+    /// Socket ID
+    link_id: usize,
+
+    /// Server name sent in ClientHello
+    sni: String<MAX_SNI_LENGTH>,
+}
+
+impl SslServerNameCommand {
+    pub fn new(link_id: usize, sni: String<MAX_SNI_LENGTH>) -> Self {
+        Self { link_id, sni }
+    }
+}
+
+impl CommandErrorHandler for SslServerNameCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::TlsServerNameError(error)
+    }
+}
+
+/// Sets the application layer protocols (ALPN) of a SSL connection
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSSLCALPN", NoResponse, timeout_ms = 1_000)]
+pub struct SslAlpnCommand {
+    /// Socket ID
+    link_id: usize,
+
+    /// Number of protocols. 0 clears the ALPN configuration.
+    count: usize,
+
+    /// First protocol
+    alpn1: Option<String<MAX_ALPN_LENGTH>>,
+
+    /// Second protocol
+    alpn2: Option<String<MAX_ALPN_LENGTH>>,
+
+    /// Third protocol
+    alpn3: Option<String<MAX_ALPN_LENGTH>>,
+}
+
+impl SslAlpnCommand {
+    pub fn new(link_id: usize, mut protocols: Vec<String<MAX_ALPN_LENGTH>, MAX_ALPN_COUNT>) -> Self {
+        let count = protocols.len();
+        let alpn3 = if count > 2 { protocols.pop() } else { None };
+        let alpn2 = if count > 1 { protocols.pop() } else { None };
+
+        Self {
+            link_id,
+            count,
+            alpn1: protocols.pop(),
+            alpn2,
+            alpn3,
+        }
+    }
+}
+
+impl CommandErrorHandler for SslAlpnCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::TlsAlpnError(error)
+    }
+}
+
+/// Resolves the IP address of a domain name
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPDOMAIN", DomainResolveResponse, timeout_ms = 10_000)]
+pub struct ResolveDomainCommand {
+    /// Domain name to resolve
+    domain: String<MAX_DOMAIN_LENGTH>,
+
+    /// Address type, not supported by older firmwares
+    ///     1: IPv4 address preferred (default)
+    ///     2: IPv4 address only
+    ///     3: IPv6 address only
+    ip_network: Option<usize>,
+}
+
+impl ResolveDomainCommand {
+    /// Resolves the preferred address type of the ESP-AT (IPv4), compatible with older firmwares
+    pub fn new(domain: String<MAX_DOMAIN_LENGTH>) -> Self {
+        Self {
+            domain,
+            ip_network: None,
+        }
+    }
+
+    /// Resolves IPv4 addresses only
+    pub fn ipv4(domain: String<MAX_DOMAIN_LENGTH>) -> Self {
+        Self {
+            domain,
+            ip_network: Some(2),
+        }
+    }
+
+    /// Resolves IPv6 addresses only
+    pub fn ipv6(domain: String<MAX_DOMAIN_LENGTH>) -> Self {
+        Self {
+            domain,
+            ip_network: Some(3),
+        }
+    }
+}
+
+impl CommandErrorHandler for ResolveDomainCommand {
+    type Error = DnsError;
+    const WOULD_BLOCK_ERROR: Self::Error = DnsError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        DnsError::ResolveFailed(error)
+    }
+}
+
+/// Configures the DNS servers
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPDNS", NoResponse, timeout_ms = 1_000)]
+pub struct DnsServersCommand {
+    /// 0: DNS servers provided by DHCP (or ESP-AT defaults) are used
+    /// 1: Manual DNS servers are used
+    #[at_arg(position = 0)]
+    enable: usize,
+
+    /// Primary DNS server
+    #[at_arg(position = 1)]
+    server1: Option<String<MAX_IP_LENGTH>>,
+
+    /// Secondary DNS server
+    #[at_arg(position = 2)]
+    server2: Option<String<MAX_IP_LENGTH>>,
+
+    /// Third DNS server
+    #[at_arg(position = 3)]
+    server3: Option<String<MAX_IP_LENGTH>>,
+}
+
+impl DnsServersCommand {
+    /// Uses the DNS servers provided by DHCP
+    pub fn dhcp() -> Self {
+        Self {
+            enable: 0,
+            server1: None,
+            server2: None,
+            server3: None,
+        }
+    }
+
+    /// Uses the given DNS servers. Servers exceeding [MAX_DNS_SERVERS] are ignored.
+    pub fn manual(servers: &[IpAddr]) -> Self {
+        let mut servers = servers.iter().take(MAX_DNS_SERVERS).map(ip_to_string);
+
+        Self {
+            enable: 1,
+            server1: servers.next(),
+            server2: servers.next(),
+            server3: servers.next(),
+        }
+    }
+}
+
+impl CommandErrorHandler for DnsServersCommand {
+    type Error = DnsError;
+    const WOULD_BLOCK_ERROR: Self::Error = DnsError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        DnsError::ConfigurationError(error)
+    }
+}
+
+/// Queries the DNS server configuration
+#[derive(Clone, Default)]
+pub struct DnsQueryCommand {}
+
+impl AtatCmd for DnsQueryCommand {
+    type Response = DnsConfig;
+
+    const MAX_LEN: usize = 12;
+    const MAX_TIMEOUT_MS: u32 = 1_000;
+
+    fn write(&self, buf: &mut [u8]) -> usize {
+        buf[..12].copy_from_slice(b"AT+CIPDNS?\r\n");
+        12
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, AtError> {
+        if resp.is_err() {
+            return Err(AtError::InvalidResponse);
+        }
+
+        DnsConfig::from_response(resp.unwrap()).ok_or(AtError::Parse)
+    }
+}
+
+impl CommandErrorHandler for DnsQueryCommand {
+    type Error = DnsError;
+    const WOULD_BLOCK_ERROR: Self::Error = DnsError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        DnsError::ConfigurationError(error)
+    }
+}
+
+/// Enables/Disables SNTP
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSNTPCFG", NoResponse, timeout_ms = 1_000)]
+pub struct SntpConfigCommand {
+    /// 0: Disables SNTP, 1: Enables SNTP
+    #[at_arg(position = 0)]
+    enable: usize,
+
+    /// Timezone, either in hours (-12 to 14) or as UTC offset [+|-][hh]mm
+    #[at_arg(position = 1)]
+    timezone: Option<i16>,
+
+    /// First SNTP server
+    #[at_arg(position = 2)]
+    server1: Option<String<MAX_SNTP_SERVER_LENGTH>>,
+
+    /// Second SNTP server
+    #[at_arg(position = 3)]
+    server2: Option<String<MAX_SNTP_SERVER_LENGTH>>,
+
+    /// Third SNTP server
+    #[at_arg(position = 4)]
+    server3: Option<String<MAX_SNTP_SERVER_LENGTH>>,
+}
+
+impl SntpConfigCommand {
+    /// Enables SNTP with the given encoded timezone and servers
+    pub fn enable(timezone: i16, servers: Vec<String<MAX_SNTP_SERVER_LENGTH>, MAX_SNTP_SERVERS>) -> Self {
+        let mut servers = servers.into_iter();
+
+        Self {
+            enable: 1,
+            timezone: Some(timezone),
+            server1: servers.next(),
+            server2: servers.next(),
+            server3: servers.next(),
+        }
+    }
+
+    /// Disables SNTP
+    pub fn disable() -> Self {
+        Self {
+            enable: 0,
+            timezone: None,
+            server1: None,
+            server2: None,
+            server3: None,
+        }
+    }
+}
+
+impl CommandErrorHandler for SntpConfigCommand {
+    type Error = SntpError;
+    const WOULD_BLOCK_ERROR: Self::Error = SntpError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        SntpError::ConfigurationError(error)
+    }
+}
+
+/// Sets the SNTP synchronisation interval
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSNTPINTV", NoResponse, timeout_ms = 1_000)]
+pub struct SntpIntervalCommand {
+    /// Interval in seconds
+    interval: u32,
+}
+
+impl SntpIntervalCommand {
+    pub fn new(interval: u32) -> Self {
+        Self { interval }
+    }
+}
+
+impl CommandErrorHandler for SntpIntervalCommand {
+    type Error = SntpError;
+    const WOULD_BLOCK_ERROR: Self::Error = SntpError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        SntpError::ConfigurationError(error)
+    }
+}
+
+/// Queries the local time
+#[derive(Clone, Default, AtatCmd)]
+#[at_cmd("+CIPSNTPTIME?", SntpTimeResponse, timeout_ms = 1_000)]
+pub struct SntpTimeCommand {}
+
+impl CommandErrorHandler for SntpTimeCommand {
+    type Error = SntpError;
+    const WOULD_BLOCK_ERROR: Self::Error = SntpError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        SntpError::TimeQueryFailed(error)
+    }
+}
+
+/// Pings a remote host
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+PING", PingResponse, timeout_ms = 10_000)]
+pub struct PingCommand {
+    /// IP address or hostname
+    host: String<MAX_DOMAIN_LENGTH>,
+}
+
+impl PingCommand {
+    pub fn new(host: String<MAX_DOMAIN_LENGTH>) -> Self {
+        Self { host }
+    }
+}
+
+impl CommandErrorHandler for PingCommand {
+    type Error = PingError;
+    const WOULD_BLOCK_ERROR: Self::Error = PingError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        PingError::PingFailed(error)
+    }
+}
+
+/// Initiates the transmission of data
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSEND", NoResponse, timeout_ms = 1_000)]
+pub struct TransmissionPrepareCommand {
+    /// Socket ID
+    link_id: usize,
+
+    /// Length of the data to transmit
+    length: usize,
+
+    /// Remote host, just relevant for UDP
+    remote_host: Option<String<MAX_IP_LENGTH>>,
+
+    /// Remote port, just relevant for UDP
+    remote_port: Option<u16>,
+}
+
+impl TransmissionPrepareCommand {
+    pub fn new(link_id: usize, length: usize) -> Self {
+        Self {
+            link_id,
+            length,
+            remote_host: None,
+            remote_port: None,
+        }
+    }
+
+    /// Transmission of a UDP datagram to the given remote
+    pub fn udp_remote(link_id: usize, length: usize, remote: SocketAddr) -> Self {
+        Self {
+            link_id,
+            length,
+            remote_host: Some(ip_to_string(&remote.ip())),
+            remote_port: Some(remote.port()),
+        }
+    }
+}
+
+impl CommandErrorHandler for TransmissionPrepareCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::TransmissionStartFailed(error)
+    }
+}
+
+/// The actual transmission of data. Max. data length: 256 bytes
+pub struct TransmissionCommand<'a, const MAX_LEN: usize> {
+    data: &'a [u8],
+}
+
+impl<'a, const MAX_LEN: usize> TransmissionCommand<'a, MAX_LEN> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+}
+
+impl<const MAX_LEN: usize> AtatCmd for TransmissionCommand<'_, MAX_LEN> {
+    type Response = NoResponse;
+    const MAX_LEN: usize = MAX_LEN;
+
+    const MAX_TIMEOUT_MS: u32 = 5000;
+    const EXPECTS_RESPONSE_CODE: bool = false;
+
+    fn write(&self, buf: &mut [u8]) -> usize {
+        buf[..self.data.len()].copy_from_slice(self.data);
+        self.data.len()
+    }
+
+    fn parse(&self, _resp: Result<&[u8], InternalError>) -> Result<Self::Response, AtError> {
+        Ok(NoResponse {})
+    }
+}
+
+impl<const MAX_LEN: usize> CommandErrorHandler for TransmissionCommand<'_, MAX_LEN> {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::SendFailed(error)
+    }
+}
+
+/// Command for receiving data
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPRECVDATA", NoResponse, timeout_ms = 1_000)]
+pub struct ReceiveDataCommand<const RESP_LEN: usize> {
+    /// Socket ID
+    link_id: usize,
+
+    /// Length in bytes to receive
+    length: usize,
+}
+
+impl<const RESP_LEN: usize> ReceiveDataCommand<RESP_LEN> {
+    pub fn new(link_id: usize, length: usize) -> Self {
+        Self { link_id, length }
+    }
 }
 
 impl<const RESP_LEN: usize> CommandErrorHandler for ReceiveDataCommand<RESP_LEN> {
@@ -370,6 +1399,45 @@ impl<const RESP_LEN: usize> CommandErrorHandler for ReceiveDataCommand<RESP_LEN>
     }
 }
 
+/// Queries the length of the data buffered by ESP-AT for all links. N = number of links.
+#[derive(Clone)]
+pub struct ReceiveLengthCommand<const N: usize> {}
+
+impl<const N: usize> ReceiveLengthCommand<N> {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<const N: usize> AtatCmd for ReceiveLengthCommand<N> {
+    type Response = ReceiveLengthResponse<N>;
+
+    const MAX_LEN: usize = 16;
+    const MAX_TIMEOUT_MS: u32 = 1_000;
+
+    fn write(&self, buf: &mut [u8]) -> usize {
+        buf[..16].copy_from_slice(b"AT+CIPRECVLEN?\r\n");
+        16
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, AtError> {
+        if resp.is_err() {
+            return Err(AtError::InvalidResponse);
+        }
+
+        ReceiveLengthResponse::from_response(resp.unwrap()).ok_or(AtError::Parse)
+    }
+}
+
+impl<const N: usize> CommandErrorHandler for ReceiveLengthCommand<N> {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::ReceiveLengthError(error)
+    }
+}
+
 /// Command for receiving data
 #[derive(Clone, AtatCmd)]
 #[at_cmd("+CIPCLOSE", NoResponse, timeout_ms = 1_000)]
@@ -393,6 +1461,145 @@ impl CommandErrorHandler for CloseSocketCommand {
     }
 }
 
+/// Sets the maximum number of connections allowed by the TCP server
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSERVERMAXCONN", NoResponse, timeout_ms = 1_000)]
+pub struct ServerMaxConnectionsCommand {
+    /// Maximum number of clients
+    max_connections: usize,
+}
+
+impl ServerMaxConnectionsCommand {
+    pub fn new(max_connections: usize) -> Self {
+        Self { max_connections }
+    }
+}
+
+impl CommandErrorHandler for ServerMaxConnectionsCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::ServerMaxConnectionsError(error)
+    }
+}
+
+/// Creates or deletes the TCP server
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSERVER", NoResponse, timeout_ms = 1_000)]
+pub struct ServerCommand {
+    /// 0: delete server, 1: create server
+    mode: usize,
+
+    /// Local port of the server, just relevant when creating the server
+    port: Option<u16>,
+}
+
+impl ServerCommand {
+    /// Creates a TCP server listening on the given port
+    pub fn create(port: u16) -> Self {
+        Self {
+            mode: 1,
+            port: Some(port),
+        }
+    }
+
+    /// Deletes the TCP server. Already accepted connections are kept.
+    pub fn delete() -> Self {
+        Self { mode: 0, port: None }
+    }
+}
+
+impl CommandErrorHandler for ServerCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        match self.mode {
+            0 => StackError::ServerStopError(error),
+            _ => StackError::ServerStartError(error),
+        }
+    }
+}
+
+/// Sets the timeout of connections accepted by the TCP server
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSTO", NoResponse, timeout_ms = 1_000)]
+pub struct ServerTimeoutCommand {
+    /// Timeout in seconds. Range: [0,7200], 0 = never timeout
+    timeout: u16,
+}
+
+impl ServerTimeoutCommand {
+    pub fn new(timeout: u16) -> Self {
+        Self { timeout }
+    }
+}
+
+impl CommandErrorHandler for ServerTimeoutCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::ServerTimeoutError(error)
+    }
+}
+
+/// Command for obtaining the state of all active connections. N = max. number of connections.
+/// Connections with link ids exceeding N are skipped.
+#[derive(Clone)]
+pub struct ConnectionStateCommand<const N: usize> {}
+
+impl<const N: usize> ConnectionStateCommand<N> {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<const N: usize> AtatCmd for ConnectionStateCommand<N> {
+    type Response = Vec<ConnectionStateResponse, N>;
+
+    const MAX_LEN: usize = 14;
+    const MAX_TIMEOUT_MS: u32 = 1_000;
+
+    fn write(&self, buf: &mut [u8]) -> usize {
+        buf[..14].copy_from_slice(b"AT+CIPSTATE?\r\n");
+        14
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, AtError> {
+        if resp.is_err() {
+            return Err(AtError::InvalidResponse);
+        }
+
+        let resp = core::str::from_utf8(resp.unwrap()).map_err(|_| AtError::Parse)?;
+        let mut connections = Vec::new();
+
+        // Response is empty if there are no active connections
+        for line in resp.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let connection =
+                atat::serde_at::from_slice::<ConnectionStateResponse>(line.as_bytes()).map_err(|_| AtError::Parse)?;
+
+            // Links exceeding the socket count of the adapter are skipped
+            if connection.link_id < N {
+                // Link ids are unique, so count is limited above
+                let _ = connections.push(connection);
+            }
+        }
+
+        Ok(connections)
+    }
+}
+
+impl<const N: usize> CommandErrorHandler for ConnectionStateCommand<N> {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::ConnectionStateError(error)
+    }
+}
+
 /// Restarts the module
 #[derive(Clone, Default, AtatCmd)]
 #[at_cmd("+RST", NoResponse, timeout_ms = 1_000)]
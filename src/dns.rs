@@ -0,0 +1,185 @@
+//! # DNS resolution
+//!
+//! This crate implements [Dns] of [embedded_nal] by the CIPDOMAIN command of ESP-AT.
+//! Only hostname lookups are supported, reverse lookups return [DnsError::Unsupported].
+//!
+//! By default, the DNS servers provided by DHCP are used. Up to [MAX_DNS_SERVERS] servers may be configured manually
+//! by [set_dns_servers()](Adapter::set_dns_servers), e.g. when using a static IP configuration.
+//!
+//! ## Example
+//!
+//! ````
+//! # use core::net::SocketAddr;
+//! # use embedded_nal::{AddrType, Dns, TcpClientStack};
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::wifi::Adapter;
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let urc_channel = AtClient::urc_channel();
+//! let client = AtClient::init(&urc_channel);
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 128, 8> = Adapter::new(client, urc_channel.subscriber().unwrap(), ExampleTimer::default());
+//!
+//! // Resolving the hostname
+//! let address = adapter.get_host_by_name("example.org", AddrType::IPv4).unwrap();
+//! assert_eq!("10.0.0.1", address.to_string());
+//!
+//! // Connecting to the resolved address
+//! let mut socket = adapter.socket().unwrap();
+//! adapter.connect(&mut socket, SocketAddr::new(address, 21)).unwrap();
+//! ````
+
+use crate::commands::{DnsQueryCommand, DnsServersCommand, ResolveDomainCommand};
+use crate::queue::ReceiveQueue;
+use crate::wifi::Adapter;
+use atat::blocking::AtatClient;
+use atat::{AtatResp, Error as AtError};
+use core::net::IpAddr;
+use core::str::FromStr;
+use embedded_nal::{AddrType, Dns};
+use fugit_timer::Timer;
+use heapless::{String, Vec};
+
+/// Maximum length of a hostname to resolve
+pub const MAX_DOMAIN_LENGTH: usize = 128;
+
+/// Maximum number of DNS servers supported by ESP-AT
+pub const MAX_DNS_SERVERS: usize = 3;
+
+/// Errors of DNS resolution
+#[derive(Clone, Debug, PartialEq)]
+pub enum DnsError {
+    /// CIPDOMAIN command failed, e.g. the hostname could not be resolved
+    ResolveFailed(AtError),
+
+    /// Hostname exceeds the maximum length of [MAX_DOMAIN_LENGTH]
+    HostnameTooLong,
+
+    /// ESP-AT responded with an invalid IP address
+    InvalidAddress,
+
+    /// Resolved address does not match the requested address type
+    AddressTypeMismatch,
+
+    /// Reverse lookups are not supported by ESP-AT
+    Unsupported,
+
+    /// CIPDNS command failed
+    ConfigurationError(AtError),
+
+    /// Either no or more then [MAX_DNS_SERVERS] DNS servers were given
+    InvalidServerCount,
+
+    /// Received an unexpected WouldBlock. The most common cause of errors is an incorrect mode of the client.
+    /// This must be either timeout or blocking.
+    UnexpectedWouldBlock,
+}
+
+/// DNS server configuration of ESP-AT
+#[derive(Clone, Debug, PartialEq)]
+pub struct DnsConfig {
+    /// True if the DNS servers were configured manually, false if provided by DHCP
+    pub manual: bool,
+
+    /// Active DNS servers, primary server first
+    pub servers: Vec<IpAddr, MAX_DNS_SERVERS>,
+}
+
+impl AtatResp for DnsConfig {}
+
+impl DnsConfig {
+    /// Parses the +CIPDNS line of the response, e.g. `+CIPDNS:1,"8.8.8.8","1.1.1.1"`
+    pub(crate) fn from_response(response: &[u8]) -> Option<Self> {
+        let response = core::str::from_utf8(response).ok()?;
+        let line = response.lines().find_map(|line| line.trim().strip_prefix("+CIPDNS:"))?;
+
+        let mut values = line.split(',');
+        let manual = match values.next()? {
+            "0" => false,
+            "1" => true,
+            _ => return None,
+        };
+
+        let mut servers = Vec::new();
+        for value in values {
+            let value = value.trim_matches('"');
+            servers.push(IpAddr::from_str(value).ok()?).ok()?;
+        }
+
+        Some(Self { manual, servers })
+    }
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > Adapter<'_, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    /// Configures the given DNS servers (1-3), primary server first. IPv6 servers require IPv6 support of the
+    /// ESP-AT firmware.
+    pub fn set_dns_servers(&mut self, servers: &[IpAddr]) -> Result<(), DnsError> {
+        if servers.is_empty() || servers.len() > MAX_DNS_SERVERS {
+            return Err(DnsError::InvalidServerCount);
+        }
+
+        self.send_command(DnsServersCommand::manual(servers))?;
+        Ok(())
+    }
+
+    /// Uses the DNS servers provided by DHCP. ESP-AT defaults are used if DHCP is disabled.
+    pub fn use_dhcp_dns_servers(&mut self) -> Result<(), DnsError> {
+        self.send_command(DnsServersCommand::dhcp())?;
+        Ok(())
+    }
+
+    /// Returns the active DNS server configuration
+    pub fn get_dns_config(&mut self) -> Result<DnsConfig, DnsError> {
+        self.send_command(DnsQueryCommand::default())
+    }
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > Dns for Adapter<'_, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    type Error = DnsError;
+
+    /// Resolves the given hostname. The address type is passed to ESP-AT, except for [AddrType::Either], which
+    /// uses the default behaviour of ESP-AT (IPv4 preferred) and is therefore supported by older firmwares too.
+    fn get_host_by_name(&mut self, hostname: &str, addr_type: AddrType) -> nb::Result<IpAddr, Self::Error> {
+        let domain = String::from_str(hostname).map_err(|_| DnsError::HostnameTooLong)?;
+
+        let command = match addr_type {
+            AddrType::IPv4 => ResolveDomainCommand::ipv4(domain),
+            AddrType::IPv6 => ResolveDomainCommand::ipv6(domain),
+            AddrType::Either => ResolveDomainCommand::new(domain),
+        };
+
+        let response = self.send_command(command)?;
+        let address = IpAddr::from_str(response.address.as_str()).map_err(|_| DnsError::InvalidAddress)?;
+
+        match (addr_type, address) {
+            (AddrType::IPv4, IpAddr::V6(_)) | (AddrType::IPv6, IpAddr::V4(_)) => {
+                nb::Result::Err(nb::Error::Other(DnsError::AddressTypeMismatch))
+            }
+            _ => nb::Result::Ok(address),
+        }
+    }
+
+    /// Reverse lookups are not supported by ESP-AT, so [DnsError::Unsupported] is always returned.
+    fn get_host_by_address(&mut self, _addr: IpAddr, _result: &mut [u8]) -> nb::Result<usize, Self::Error> {
+        nb::Result::Err(nb::Error::Other(DnsError::Unsupported))
+    }
+}
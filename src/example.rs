@@ -30,6 +30,13 @@ impl<'a> ExampleAtClient<'a> {
     }
 }
 
+#[cfg(feature = "async")]
+impl atat::asynch::AtatClient for ExampleAtClient<'_> {
+    async fn send<A: AtatCmd>(&mut self, cmd: &A) -> Result<A::Response, Error> {
+        AtatClient::send(self, cmd)
+    }
+}
+
 impl AtatClient for ExampleAtClient<'_> {
     fn send<A: AtatCmd>(&mut self, cmd: &A) -> Result<A::Response, Error> {
         let mut buffer = [0x0; 128];
@@ -41,6 +48,12 @@ impl AtatClient for ExampleAtClient<'_> {
                 self.publish_urc(b"WIFI GOT IP\r\n");
             }
             b"AT+CIPSTART=0,\"TCP\",\"10.0.0.1\",21\r\n" => self.publish_urc(b"0,CONNECT\r\n"),
+            b"AT+CIPSTART=0,\"SSL\",\"10.0.0.1\",443\r\n" => self.publish_urc(b"0,CONNECT\r\n"),
+            b"AT+CIPSTART=0,\"UDP\",\"10.0.0.1\",5000\r\n" => self.publish_urc(b"0,CONNECT\r\n"),
+            b"AT+CIPSTART=0,\"UDP\",\"0.0.0.0\",0,7000,2\r\n" => {
+                self.publish_urc(b"0,CONNECT\r\n");
+                self.publish_urc(b"+IPD,0,4,\"10.0.0.2\",6000\r\n");
+            }
             b"AT+CIPSEND=0,6\r\n" => {
                 self.publish_urc(b"SEND OK\r\n");
                 self.publish_urc(b"+IPD,0,16\r\n");
@@ -48,7 +61,43 @@ impl AtatClient for ExampleAtClient<'_> {
             b"AT+CIPRECVDATA=0,64\r\n" => {
                 self.publish_urc(b"+CIPRECVDATA,16:nice to see you!");
             }
+            b"AT+CIPRECVDATA=0,32\r\n" => {
+                self.publish_urc(b"+CIPRECVDATA:4,\"10.0.0.2\",6000,ping");
+            }
+            b"AT+CIPSEND=0,4,\"10.0.0.2\",6000\r\n" => self.publish_urc(b"SEND OK\r\n"),
             b"AT+CIPCLOSE=0\r\n" => self.publish_urc(b"0,CLOSED\r\n"),
+            b"AT+CIPSERVER=1,80\r\n" => self.publish_urc(b"0,CONNECT\r\n"),
+            b"AT+CIPSTATE?\r\n" => {
+                let response = cmd
+                    .parse(Ok(b"+CIPSTATE:0,\"TCP\",\"10.0.0.2\",51234,80,1\r\n"))
+                    .map_err(|_| Error::Error)?;
+                return Ok(response);
+            }
+            b"AT+CIPDOMAIN=\"example.org\",2\r\n" => {
+                let response = cmd.parse(Ok(b"+CIPDOMAIN:\"10.0.0.1\"\r\n")).map_err(|_| Error::Error)?;
+                return Ok(response);
+            }
+            b"AT+CWSAP=\"provisioning\",\"secret123\",1,3\r\n" => {
+                self.publish_urc(b"+STA_CONNECTED:\"18:31:bf:4b:8a:f2\"\r\n");
+                self.publish_urc(b"+DIST_STA_IP:\"18:31:bf:4b:8a:f2\",\"192.168.4.2\"\r\n");
+            }
+            b"AT+PING=\"example.org\"\r\n" => {
+                let response = cmd.parse(Ok(b"+PING:25\r\n")).map_err(|_| Error::Error)?;
+                return Ok(response);
+            }
+            b"AT+CIPSNTPCFG=1,1,\"pool.ntp.org\"\r\n" => self.publish_urc(b"+TIME_UPDATED\r\n"),
+            b"AT+CIPSNTPTIME?\r\n" => {
+                let response = cmd
+                    .parse(Ok(b"+CIPSNTPTIME:Thu Aug 04 14:48:05 2021\r\n"))
+                    .map_err(|_| Error::Error)?;
+                return Ok(response);
+            }
+            b"AT+CWLIF\r\n" => {
+                let response = cmd
+                    .parse(Ok(b"+CWLIF:192.168.4.2,18:31:bf:4b:8a:f2\r\n"))
+                    .map_err(|_| Error::Error)?;
+                return Ok(response);
+            }
             b"AT+CIFSR\r\n" => {
                 let response = cmd
                     .parse(Ok(
@@ -88,3 +137,15 @@ impl Timer<1_000_000> for ExampleTimer {
         nb::Result::Err(nb::Error::WouldBlock)
     }
 }
+
+/// Async delay mock
+#[cfg(feature = "async")]
+#[derive(Default)]
+pub struct ExampleDelay {}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::delay::DelayNs for ExampleDelay {
+    async fn delay_ns(&mut self, _ns: u32) {
+        core::future::pending().await
+    }
+}
@@ -5,7 +5,17 @@
 //!
 //! Currently this crates offers the following features
 //! * Joining an WIFI access point, s. [wifi module](crate::wifi)
+//! * Scanning for WIFI access points, s. [wifi module](crate::wifi)
+//! * SoftAP mode for running an access point, s. [softap module](crate::softap)
 //! * TCP client stack (multi socket), s. [stack module](crate::stack)
+//! * TCP server (single server, multiple clients), s. [stack module](crate::stack)
+//! * UDP client stack (multi socket), s. [udp module](crate::udp)
+//! * TLS client connections, s. [tls module](crate::tls)
+//! * DNS resolution and DNS server configuration, s. [dns module](crate::dns)
+//! * Network time by SNTP, s. [sntp module](crate::sntp)
+//! * Connectivity checks by ICMP ping, s. [ping module](crate::ping)
+//! * Async adapter implementing [embedded-nal-async](https://crates.io/crates/embedded-nal-async) (`async` feature)
+//! * Statically allocated receive queues of active receiving mode based on [bbqueue](https://crates.io/crates/bbqueue) (`bbqueue` feature), s. [queue module](crate::queue)
 //!
 //! ## Setup
 //! This crates is based on [ATAT](atat) and requires a AtClient instance.
@@ -44,10 +54,19 @@
 #[cfg(test)]
 extern crate alloc;
 
+#[cfg(feature = "async")]
+pub mod asynch;
 pub(crate) mod commands;
+pub mod dns;
 pub mod example;
+pub mod ping;
+pub mod queue;
 pub(crate) mod responses;
+pub mod sntp;
+pub mod softap;
 pub mod stack;
+pub mod tls;
+pub mod udp;
 pub mod urc;
 pub mod wifi;
 
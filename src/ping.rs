@@ -0,0 +1,88 @@
+//! # ICMP ping
+//!
+//! Connectivity may be checked by the PING command of ESP-AT. Both IP addresses and hostnames are supported as
+//! target, hostnames are resolved by ESP-AT.
+//!
+//! The response time is returned as [TimerDurationU32], using the tick rate of the adapter timer.
+//!
+//! ## Example
+//!
+//! ````
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::wifi::Adapter;
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let urc_channel = AtClient::urc_channel();
+//! let client = AtClient::init(&urc_channel);
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 128, 8> = Adapter::new(client, urc_channel.subscriber().unwrap(), ExampleTimer::default());
+//!
+//! // Pinging a remote host
+//! let duration = adapter.ping("example.org").unwrap();
+//! assert_eq!(25, duration.to_millis());
+//! ````
+
+use crate::commands::PingCommand;
+use crate::queue::ReceiveQueue;
+use crate::wifi::Adapter;
+use atat::blocking::AtatClient;
+use atat::Error as AtError;
+use core::str::FromStr;
+use fugit::TimerDurationU32;
+use fugit_timer::Timer;
+use heapless::String;
+
+/// Errors of ping requests
+#[derive(Clone, Debug, PartialEq)]
+pub enum PingError {
+    /// PING command failed, e.g. the hostname could not be resolved or the host is unreachable
+    PingFailed(AtError),
+
+    /// Remote host did not respond in time, reported by ESP-AT as `+PING:TIMEOUT` followed by ERROR
+    Timeout,
+
+    /// Host exceeds the maximum length of [MAX_DOMAIN_LENGTH](crate::dns::MAX_DOMAIN_LENGTH)
+    HostTooLong,
+
+    /// ESP-AT responded with an invalid response time
+    InvalidResponse,
+
+    /// Received an unexpected WouldBlock. The most common cause of errors is an incorrect mode of the client.
+    /// This must be either timeout or blocking.
+    UnexpectedWouldBlock,
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > Adapter<'_, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    /// Pings the given host (IP address or hostname) and returns the response time.
+    /// ESP-AT reports the time in milliseconds.
+    pub fn ping(&mut self, host: &str) -> Result<TimerDurationU32<TIMER_HZ>, PingError> {
+        let host = String::from_str(host).map_err(|_| PingError::HostTooLong)?;
+
+        // Discarding timeouts of previous requests
+        self.process_urc_messages();
+        self.session.ping_timeout = false;
+
+        let response = match self.send_command(PingCommand::new(host)) {
+            Ok(response) => response,
+            Err(error) => {
+                self.process_urc_messages();
+                return match core::mem::take(&mut self.session.ping_timeout) {
+                    true => Err(PingError::Timeout),
+                    false => Err(error),
+                };
+            }
+        };
+
+        let millis = u32::from_str(response.time.as_str()).map_err(|_| PingError::InvalidResponse)?;
+        Ok(TimerDurationU32::millis(millis))
+    }
+}
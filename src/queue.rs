@@ -0,0 +1,206 @@
+//! # Receive queues
+//!
+//! Data received in [active receiving mode](crate::stack::ReceiveMode::Active) is buffered per socket until it's
+//! read by `receive()`. The queue type is selected by the `Q` generic of the adapter:
+//!
+//! * [VecQueue]: [heapless::Vec] of N bytes, allocated as part of the adapter. `VecQueue<0>` is the default,
+//!   which allocates no buffer and so supports just passive receiving mode.
+//! * `BbQueue`: Statically allocated [bbqueue](https://crates.io/crates/bbqueue) buffer of N bytes, assigned by
+//!   `set_receive_queues()` of the adapter (`bbqueue` feature). Data gets read directly from the grants of the
+//!   queue, so consumed data does not need to be moved within the buffer.
+//!
+//! In both cases +IPD messages are parsed by ATAT first, so the payload of a single message is limited by `RX_SIZE`
+//! and copied once from the URC message to the queue.
+//!
+//! ## Example
+//!
+//! ````
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::queue::VecQueue;
+//! # use esp_at_nal::stack::ReceiveMode;
+//! # use esp_at_nal::wifi::Adapter;
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let urc_channel = AtClient::urc_channel();
+//! let client = AtClient::init(&urc_channel);
+//! // Adapter with a receive queue of 1024 bytes per socket
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 128, 8, 5, VecQueue<1024>> = Adapter::new(client, urc_channel.subscriber().unwrap(), ExampleTimer::default());
+//! adapter.set_receive_mode(ReceiveMode::Active).unwrap();
+//! ````
+#[cfg(feature = "bbqueue")]
+use bbqueue::{BBBuffer, Consumer, Producer};
+use heapless::Vec;
+
+/// FIFO byte queue buffering the received data of a single socket
+pub trait ReceiveQueue: Default {
+    /// Returns true if data can be buffered
+    fn is_enabled(&self) -> bool;
+
+    /// Returns the length of the buffered data
+    fn len(&self) -> usize;
+
+    /// Returns true if no data is buffered
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends as much of the given data as fits in the queue. Returns the appended length.
+    fn push(&mut self, data: &[u8]) -> usize;
+
+    /// Passes the next contiguous chunk of buffered data to the given function, which returns the consumed length.
+    /// Returns the consumed length, which is limited to the chunk length.
+    fn read_with<F: FnOnce(&[u8]) -> usize>(&mut self, f: F) -> usize;
+
+    /// Drops all buffered data
+    fn clear(&mut self);
+
+    /// Moves buffered data to the given buffer. Returns the copied length.
+    fn read(&mut self, buffer: &mut [u8]) -> usize {
+        let mut copied = 0;
+
+        while copied < buffer.len() && !self.is_empty() {
+            let length = self.read_with(|chunk| {
+                let length = chunk.len().min(buffer.len() - copied);
+                buffer[copied..copied + length].copy_from_slice(&chunk[..length]);
+                length
+            });
+
+            if length == 0 {
+                break;
+            }
+
+            copied += length;
+        }
+
+        copied
+    }
+}
+
+/// Receive queue backed by a [heapless::Vec] of N bytes
+#[derive(Default)]
+pub struct VecQueue<const N: usize> {
+    /// Buffered data
+    buffer: Vec<u8, N>,
+}
+
+impl<const N: usize> ReceiveQueue for VecQueue<N> {
+    /// Returns true if the capacity is greater than zero
+    fn is_enabled(&self) -> bool {
+        N > 0
+    }
+
+    fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn push(&mut self, data: &[u8]) -> usize {
+        let length = data.len().min(N - self.buffer.len());
+        // Length is limited above
+        let _ = self.buffer.extend_from_slice(&data[..length]);
+        length
+    }
+
+    /// Passes all buffered data, as it's always contiguous
+    fn read_with<F: FnOnce(&[u8]) -> usize>(&mut self, f: F) -> usize {
+        let length = f(&self.buffer).min(self.buffer.len());
+
+        self.buffer.copy_within(length.., 0);
+        self.buffer.truncate(self.buffer.len() - length);
+        length
+    }
+
+    fn clear(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+/// Receive queue backed by a statically allocated [BBBuffer] of N bytes, which is assigned by
+/// `set_receive_queues()` of the adapter. No data can be buffered until the storage is assigned.
+#[cfg(feature = "bbqueue")]
+#[derive(Default)]
+pub struct BbQueue<const N: usize> {
+    /// Assigned storage with its producer and consumer, None if no storage was assigned yet
+    queue: Option<(&'static BBBuffer<N>, Producer<'static, N>, Consumer<'static, N>)>,
+
+    /// Length of the buffered data, as not tracked by bbqueue
+    length: usize,
+}
+
+#[cfg(feature = "bbqueue")]
+impl<const N: usize> BbQueue<N> {
+    /// Assigns the given split storage. A previously assigned storage gets released, so it may be split again.
+    pub(crate) fn assign(
+        &mut self,
+        storage: &'static BBBuffer<N>,
+        producer: Producer<'static, N>,
+        consumer: Consumer<'static, N>,
+    ) {
+        if let Some((previous, previous_producer, previous_consumer)) = self.queue.take() {
+            // No grants are held between calls, so releasing can not fail
+            let _ = previous.try_release(previous_producer, previous_consumer);
+        }
+
+        self.queue = Some((storage, producer, consumer));
+        self.length = 0;
+    }
+}
+
+#[cfg(feature = "bbqueue")]
+impl<const N: usize> ReceiveQueue for BbQueue<N> {
+    /// Returns true if a storage is assigned
+    fn is_enabled(&self) -> bool {
+        self.queue.is_some()
+    }
+
+    fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Data may be written by two grants, if the free space wraps around the end of the storage
+    fn push(&mut self, data: &[u8]) -> usize {
+        let Some((_, producer, _)) = self.queue.as_mut() else {
+            return 0;
+        };
+
+        let mut written = 0;
+        while written < data.len() {
+            let Ok(mut grant) = producer.grant_max_remaining(data.len() - written) else {
+                break;
+            };
+
+            let length = grant.buf().len();
+            if length == 0 {
+                break;
+            }
+
+            grant.buf().copy_from_slice(&data[written..written + length]);
+            grant.commit(length);
+            written += length;
+        }
+
+        self.length += written;
+        written
+    }
+
+    /// Data wrapping around the end of the storage is passed by the next call
+    fn read_with<F: FnOnce(&[u8]) -> usize>(&mut self, f: F) -> usize {
+        let Some((_, _, consumer)) = self.queue.as_mut() else {
+            return 0;
+        };
+
+        let Ok(grant) = consumer.read() else {
+            return 0;
+        };
+
+        let length = f(grant.buf()).min(grant.buf().len());
+        grant.release(length);
+
+        self.length -= length;
+        length
+    }
+
+    fn clear(&mut self) {
+        while self.read_with(|chunk| chunk.len()) > 0 {}
+        self.length = 0;
+    }
+}
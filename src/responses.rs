@@ -1,6 +1,9 @@
 use atat::atat_derive::AtatResp;
-use atat::heapless::String;
+use atat::heapless::{String, Vec};
 use atat::heapless_bytes::Bytes;
+use atat::AtatResp;
+use core::net::{IpAddr, SocketAddr};
+use core::str::FromStr;
 
 /// Commands which gets just responded by OK
 #[derive(Clone, AtatResp)]
@@ -9,7 +12,7 @@ pub struct NoResponse;
 /// Single line response of CIFSR command
 #[derive(Clone, AtatResp, Debug)]
 pub struct LocalAddressResponse {
-    /// Address type
+    /// Address type, prefixed by the interface (STA: station, AP: SoftAP, ETH: Ethernet)
     /// * STAIP: Local IPv4 address
     /// * STAIP6LL: Link local IPv6 address
     /// * STAIP6GL: Global IPv6 address
@@ -19,3 +22,199 @@ pub struct LocalAddressResponse {
     /// String encoded address
     pub address: String<64>,
 }
+
+/// Single line response of CIPSTA query command
+#[derive(Clone, Debug)]
+pub struct IpConfigResponse {
+    /// Address type
+    /// * ip: Local IPv4 address
+    /// * gateway: IPv4 address of the gateway
+    /// * netmask: IPv4 netmask
+    /// * ip6ll: Link local IPv6 address
+    /// * ip6gl: Global IPv6 address
+    pub address_type: String<8>,
+
+    /// String encoded address
+    pub address: String<39>,
+}
+
+impl AtatResp for IpConfigResponse {}
+
+impl IpConfigResponse {
+    /// Parses all +CIPSTA lines, e.g. `+CIPSTA:gateway:"10.0.0.1"`
+    pub fn from_response<const N: usize>(response: &[u8]) -> Option<Vec<Self, N>> {
+        let mut addresses = Vec::new();
+
+        for line in core::str::from_utf8(response).ok()?.lines() {
+            let line = match line.trim().strip_prefix("+CIPSTA:") {
+                None => continue,
+                Some(line) => line,
+            };
+
+            let (address_type, address) = line.split_once(':')?;
+            let address = Self {
+                address_type: String::from_str(address_type).ok()?,
+                address: String::from_str(unquote(address)).ok()?,
+            };
+            addresses.push(address).ok()?;
+        }
+
+        Some(addresses)
+    }
+}
+
+/// Single line response of CIPSTATE command
+#[derive(Clone, AtatResp, Debug)]
+pub struct ConnectionStateResponse {
+    /// Link ID of the connection
+    pub link_id: usize,
+
+    /// Connection type, e.g. TCP, TCPv6, UDP, SSL, etc.
+    pub connection_type: String<6>,
+
+    /// Remote IPv4 or IPv6 address
+    pub remote_ip: String<39>,
+
+    /// Remote port
+    pub remote_port: u16,
+
+    /// Local port
+    pub local_port: u16,
+
+    /// 0: ESP-AT runs as client, 1: ESP-AT runs as server
+    pub tetype: usize,
+}
+
+impl ConnectionStateResponse {
+    /// Returns the remote address, or None for unspecified remotes (e.g. bound UDP sockets)
+    pub fn remote(&self) -> Option<SocketAddr> {
+        let ip = IpAddr::from_str(self.remote_ip.as_str()).ok()?;
+        if ip.is_unspecified() || self.remote_port == 0 {
+            return None;
+        }
+
+        Some(SocketAddr::new(ip, self.remote_port))
+    }
+}
+
+/// Response of CIPRECVLEN query command. N = number of links.
+#[derive(Clone, Debug)]
+pub struct ReceiveLengthResponse<const N: usize> {
+    /// Length of the buffered data, index = link id. Zero for unconnected links.
+    pub lengths: Vec<usize, N>,
+}
+
+impl<const N: usize> AtatResp for ReceiveLengthResponse<N> {}
+
+impl<const N: usize> ReceiveLengthResponse<N> {
+    /// Parses the response, e.g. `+CIPRECVLEN:100,,0,,`. Values of links exceeding N are skipped.
+    pub(crate) fn from_response(response: &[u8]) -> Option<Self> {
+        let response = core::str::from_utf8(response).ok()?;
+        let line = response.lines().find_map(|line| line.trim().strip_prefix("+CIPRECVLEN:"))?;
+
+        let mut lengths = Vec::new();
+        for value in line.split(',').take(N) {
+            // Unconnected links are reported either empty or as -1, depending on the firmware version
+            let length = match value {
+                "" | "-1" => 0,
+                _ => usize::from_str(value).ok()?,
+            };
+
+            // Count is limited above
+            let _ = lengths.push(length);
+        }
+
+        Some(Self { lengths })
+    }
+}
+
+/// Response of CIPDOMAIN command
+#[derive(Clone, AtatResp, Debug)]
+pub struct DomainResolveResponse {
+    /// Resolved IPv4 or IPv6 address
+    pub address: String<39>,
+}
+
+/// Response of CWMODE query command
+#[derive(Clone, AtatResp, Debug)]
+pub struct WifiModeResponse {
+    /// Current WIFI mode
+    pub mode: usize,
+}
+
+/// Response of CWSTATE query command
+#[derive(Clone, AtatResp, Debug)]
+pub struct StationStateResponse {
+    /// WIFI state of the station
+    pub state: u8,
+
+    /// SSID of the target access point
+    #[allow(unused)]
+    pub ssid: String<32>,
+}
+
+/// Response of CWHOSTNAME query command
+#[derive(Clone, AtatResp, Debug)]
+pub struct HostnameResponse {
+    /// Hostname of the station
+    pub hostname: String<32>,
+}
+
+/// Response of CIPSNTPTIME query command
+#[derive(Clone, AtatResp, Debug)]
+pub struct SntpTimeResponse {
+    /// Local time in asctime format, e.g. `Thu Aug 04 14:48:05 2021`
+    pub time: String<32>,
+}
+
+/// Response of PING command
+#[derive(Clone, AtatResp, Debug)]
+pub struct PingResponse {
+    /// Response time in milliseconds
+    pub time: String<8>,
+}
+
+/// Single line response of CWLIF command
+#[derive(Clone, Debug)]
+pub struct StationResponse {
+    /// IPv4 address of the station
+    pub ip: String<15>,
+
+    /// MAC address of the station
+    pub mac: String<17>,
+}
+
+impl AtatResp for StationResponse {}
+
+impl StationResponse {
+    /// Parses all +CWLIF lines. Depending on the firmware version, values are quoted or not.
+    /// Lines exceeding the capacity N are skipped.
+    pub fn from_response<const N: usize>(response: &[u8]) -> Option<Vec<Self, N>> {
+        let mut stations = Vec::new();
+
+        for line in core::str::from_utf8(response).ok()?.lines() {
+            let line = match line.trim().strip_prefix("+CWLIF:") {
+                None => continue,
+                Some(line) => line,
+            };
+
+            if stations.is_full() {
+                break;
+            }
+
+            let (ip, mac) = line.split_once(',')?;
+            let station = Self {
+                ip: String::from_str(unquote(ip)).ok()?,
+                mac: String::from_str(unquote(mac)).ok()?,
+            };
+            stations.push(station).ok()?;
+        }
+
+        Some(stations)
+    }
+}
+
+/// Removes the optional quotes of the given value
+pub(crate) fn unquote(value: &str) -> &str {
+    value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value)
+}
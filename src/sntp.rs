@@ -0,0 +1,231 @@
+//! # Network time (SNTP)
+//!
+//! ESP-AT is able to synchronize its clock by SNTP. The current time may be queried afterward, e.g. for validating
+//! TLS certificates or timestamping log messages.
+//!
+//! Newer firmware versions (>= 2.2) signal a successful synchronisation by a URC message, which is tracked by
+//! [is_time_synchronized()](Adapter::is_time_synchronized).
+//!
+//! ## Example
+//!
+//! ````
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::sntp::SntpConfig;
+//! # use esp_at_nal::wifi::Adapter;
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let urc_channel = AtClient::urc_channel();
+//! let client = AtClient::init(&urc_channel);
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 128, 8> = Adapter::new(client, urc_channel.subscriber().unwrap(), ExampleTimer::default());
+//!
+//! // Enabling SNTP with UTC+1
+//! let config = SntpConfig {
+//!     utc_offset_minutes: 60,
+//!     servers: &["pool.ntp.org"],
+//! };
+//! adapter.configure_sntp(&config).unwrap();
+//! assert!(adapter.is_time_synchronized());
+//!
+//! // Querying the current time
+//! let time = adapter.get_time().unwrap();
+//! assert_eq!(2021, time.year);
+//! assert_eq!(14, time.hour);
+//! ````
+
+use crate::commands::{SntpConfigCommand, SntpIntervalCommand, SntpTimeCommand};
+use crate::queue::ReceiveQueue;
+use crate::wifi::Adapter;
+use atat::blocking::AtatClient;
+use atat::Error as AtError;
+use core::str::FromStr;
+use fugit_timer::Timer;
+use heapless::{String, Vec};
+
+/// Max. number of SNTP servers supported by ESP-AT
+pub const MAX_SNTP_SERVERS: usize = 3;
+
+/// Max. length of a SNTP server domain
+pub const MAX_SNTP_SERVER_LENGTH: usize = 64;
+
+/// SNTP configuration
+#[derive(Clone, Debug, Default)]
+pub struct SntpConfig<'a> {
+    /// Offset of the local timezone to UTC in minutes, -720 (UTC-12:00) to 840 (UTC+14:00).
+    /// Offsets, which are not whole hours, require ESP-AT version >= 2.2.
+    pub utc_offset_minutes: i16,
+
+    /// Up to three SNTP servers. ESP-AT defaults are used if empty.
+    pub servers: &'a [&'a str],
+}
+
+/// Local date and time reported by ESP-AT
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DateTime {
+    /// Year, e.g. 2021
+    pub year: u16,
+
+    /// Month (1-12)
+    pub month: u8,
+
+    /// Day of the month (1-31)
+    pub day: u8,
+
+    /// Hour (0-23)
+    pub hour: u8,
+
+    /// Minute (0-59)
+    pub minute: u8,
+
+    /// Second (0-59)
+    pub second: u8,
+}
+
+/// Errors of SNTP configuration and time retrieval
+#[derive(Clone, Debug, PartialEq)]
+pub enum SntpError {
+    /// CIPSNTPCFG or CIPSNTPINTV command failed
+    ConfigurationError(AtError),
+
+    /// CIPSNTPTIME command failed
+    TimeQueryFailed(AtError),
+
+    /// More then [MAX_SNTP_SERVERS] servers were given
+    TooManyServers,
+
+    /// Server domain is longer then [MAX_SNTP_SERVER_LENGTH]
+    ServerTooLong,
+
+    /// UTC offset is out of range
+    InvalidUtcOffset,
+
+    /// Sync interval is out of range (15 - 4294967 seconds)
+    InvalidInterval,
+
+    /// ESP-AT responded with an invalid time
+    TimeParseError,
+
+    /// Received an unexpected WouldBlock. The most common cause of errors is an incorrect mode of the client.
+    /// This must be either timeout or blocking.
+    UnexpectedWouldBlock,
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > Adapter<'_, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    /// Enables SNTP with the given timezone and servers
+    pub fn configure_sntp(&mut self, config: &SntpConfig) -> Result<(), SntpError> {
+        if config.servers.len() > MAX_SNTP_SERVERS {
+            return Err(SntpError::TooManyServers);
+        }
+
+        let mut servers = Vec::new();
+        for server in config.servers {
+            // Count is checked above
+            let _ = servers.push(String::from_str(server).map_err(|_| SntpError::ServerTooLong)?);
+        }
+
+        let timezone = encode_utc_offset(config.utc_offset_minutes)?;
+        self.send_command(SntpConfigCommand::enable(timezone, servers))?;
+        Ok(())
+    }
+
+    /// Disables SNTP
+    pub fn disable_sntp(&mut self) -> Result<(), SntpError> {
+        self.send_command(SntpConfigCommand::disable())?;
+        Ok(())
+    }
+
+    /// Sets the interval of SNTP synchronisation in seconds (15 - 4294967). ESP-AT default is one hour.
+    pub fn set_sntp_interval(&mut self, seconds: u32) -> Result<(), SntpError> {
+        if !(15..=4_294_967).contains(&seconds) {
+            return Err(SntpError::InvalidInterval);
+        }
+
+        self.send_command(SntpIntervalCommand::new(seconds))?;
+        Ok(())
+    }
+
+    /// Returns the local time of ESP-AT. Unless synchronized, the time is counted from 1970-01-01.
+    pub fn get_time(&mut self) -> Result<DateTime, SntpError> {
+        let response = self.send_command(SntpTimeCommand::default())?;
+        DateTime::from_str(response.time.as_str()).map_err(|_| SntpError::TimeParseError)
+    }
+
+    /// Returns true if the time was synchronized by SNTP, as signaled by the +TIME_UPDATED URC message.
+    /// Always false for ESP-AT versions < 2.2.
+    pub fn is_time_synchronized(&mut self) -> bool {
+        self.process_urc_messages();
+        self.session.time_synchronized
+    }
+}
+
+/// Encodes the UTC offset as expected by CIPSNTPCFG. Whole hours are encoded as hours (-12 to 14) for compatibility
+/// with older firmware versions, other offsets as [+|-][hh]mm.
+fn encode_utc_offset(minutes: i16) -> Result<i16, SntpError> {
+    if !(-720..=840).contains(&minutes) {
+        return Err(SntpError::InvalidUtcOffset);
+    }
+
+    if minutes % 60 == 0 {
+        return Ok(minutes / 60);
+    }
+
+    Ok(minutes / 60 * 100 + minutes % 60)
+}
+
+impl FromStr for DateTime {
+    type Err = ();
+
+    /// Parses the asctime format of CIPSNTPTIME, e.g. `Thu Aug 04 14:48:05 2021`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut parts = value.split_whitespace();
+        let _weekday = parts.next().ok_or(())?;
+
+        let month = match parts.next().ok_or(())? {
+            "Jan" => 1,
+            "Feb" => 2,
+            "Mar" => 3,
+            "Apr" => 4,
+            "May" => 5,
+            "Jun" => 6,
+            "Jul" => 7,
+            "Aug" => 8,
+            "Sep" => 9,
+            "Oct" => 10,
+            "Nov" => 11,
+            "Dec" => 12,
+            _ => return Err(()),
+        };
+
+        let day = parts.next().ok_or(())?.parse().map_err(|_| ())?;
+
+        let mut time = parts.next().ok_or(())?.split(':');
+        let mut next_time = || -> Result<u8, ()> { time.next().ok_or(())?.parse().map_err(|_| ()) };
+        let hour = next_time()?;
+        let minute = next_time()?;
+        let second = next_time()?;
+
+        let year = parts.next().ok_or(())?.parse().map_err(|_| ())?;
+
+        if parts.next().is_some() || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+            return Err(());
+        }
+
+        Ok(Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        })
+    }
+}
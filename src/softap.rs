@@ -0,0 +1,224 @@
+//! # SoftAP mode
+//!
+//! ESP-AT may run as an access point, e.g. for provisioning a device. The access point is either started
+//! exclusively (SoftAP mode) or in addition to the station (SoftAP+Station mode), so that a network may be joined
+//! in parallel.
+//!
+//! Stations joining or leaving the access point are reported by [StationEvent]s.
+//!
+//! ## Example
+//!
+//! ````
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::softap::{SoftApConfig, StationEvent};
+//! # use esp_at_nal::wifi::{Adapter, AuthMode};
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let urc_channel = AtClient::urc_channel();
+//! let client = AtClient::init(&urc_channel);
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 128, 8> = Adapter::new(client, urc_channel.subscriber().unwrap(), ExampleTimer::default());
+//!
+//! // Starting an access point
+//! let config = SoftApConfig {
+//!     ssid: "provisioning",
+//!     password: "secret123",
+//!     encryption: AuthMode::Wpa2Psk,
+//!     ..Default::default()
+//! };
+//! adapter.start_access_point(&config).unwrap();
+//!
+//! // Waiting for the first station
+//! let event = adapter.next_station_event().unwrap();
+//! assert!(matches!(event, StationEvent::Connected(_)));
+//!
+//! // Listing the connected stations
+//! let stations = adapter.get_stations::<4>().unwrap();
+//! assert_eq!("192.168.4.2", stations[0].ip.to_string());
+//! ````
+
+use crate::commands::{DisconnectStationCommand, ListStationsCommand, SoftApConfigurationCommand};
+use crate::queue::ReceiveQueue;
+use crate::responses::StationResponse;
+use crate::wifi::{Adapter, AuthMode, CommandError, MacAddress, WifiAdapter, WifiMode};
+use atat::blocking::AtatClient;
+use atat::Error as AtError;
+use core::net::Ipv4Addr;
+use core::str::FromStr;
+use fugit_timer::Timer;
+use heapless::{String, Vec};
+
+/// Max. number of unhandled station events. If exceeded, the oldest event is dropped.
+pub(crate) const MAX_STATION_EVENTS: usize = 8;
+
+/// Configuration of the access point
+#[derive(Clone, Debug)]
+pub struct SoftApConfig<'a> {
+    /// SSID of the access point, at most 32 chars
+    pub ssid: &'a str,
+
+    /// Password of the access point, 8 to 63 chars. Ignored for open access points.
+    pub password: &'a str,
+
+    /// WIFI channel
+    pub channel: u8,
+
+    /// Encryption. Supported: Open, WpaPsk, Wpa2Psk and WpaWpa2Psk
+    pub encryption: AuthMode,
+
+    /// Max. number of connected stations (1-10). Uses ESP-AT default if None.
+    pub max_connections: Option<u8>,
+
+    /// Hides the SSID if true
+    pub hidden: bool,
+
+    /// Keeps the station enabled (SoftAP+Station mode), so that a network may be joined in parallel
+    pub station_enabled: bool,
+}
+
+impl Default for SoftApConfig<'_> {
+    fn default() -> Self {
+        Self {
+            ssid: "",
+            password: "",
+            channel: 1,
+            encryption: AuthMode::Open,
+            max_connections: None,
+            hidden: false,
+            station_enabled: false,
+        }
+    }
+}
+
+/// Station connected to the access point
+#[derive(Clone, Debug, PartialEq)]
+pub struct Station {
+    /// IP address assigned to the station
+    pub ip: Ipv4Addr,
+
+    /// MAC address of the station
+    pub mac: MacAddress,
+}
+
+/// Station state change, reported by URC messages
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StationEvent {
+    /// Station with the given MAC address joined the access point
+    Connected(MacAddress),
+
+    /// Station with the given MAC address left the access point
+    Disconnected(MacAddress),
+
+    /// An IP was assigned to the station with the given MAC address
+    IpAssigned(MacAddress, Ipv4Addr),
+}
+
+/// Errors of SoftAP mode
+#[derive(Clone, Debug, PartialEq)]
+pub enum SoftApError {
+    /// Error while switching the WIFI mode
+    ModeError(AtError),
+
+    /// CWSAP command failed
+    ConfigurationError(AtError),
+
+    /// CWLIF command failed
+    ListStationsError(AtError),
+
+    /// CWQIF command failed
+    DisconnectStationError(AtError),
+
+    /// Given SSID is longer then the max. size of 32 chars
+    InvalidSsidLength,
+
+    /// Given password is shorter then 8 or longer then 63 chars
+    InvalidPasswordLength,
+
+    /// Encryption is not supported by SoftAP mode
+    UnsupportedEncryption,
+
+    /// ESP-AT responded with an invalid station address
+    AddressParseError,
+
+    /// Received an unexpected WouldBlock. The most common cause of errors is an incorrect mode of the client.
+    /// This must be either timeout or blocking.
+    UnexpectedWouldBlock,
+}
+
+impl SoftApError {
+    /// Maps the error of switching the WIFI mode
+    pub(crate) fn from_mode_error(error: CommandError) -> Self {
+        match error {
+            CommandError::CommandFailed(error) => Self::ModeError(error),
+            _ => Self::UnexpectedWouldBlock,
+        }
+    }
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > Adapter<'_, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    /// Switches to SoftAP (or SoftAP+Station) mode and starts the access point
+    pub fn start_access_point(&mut self, config: &SoftApConfig) -> Result<(), SoftApError> {
+        let ssid = String::from_str(config.ssid).map_err(|_| SoftApError::InvalidSsidLength)?;
+        let password = String::from_str(config.password).map_err(|_| SoftApError::InvalidPasswordLength)?;
+
+        if config.encryption != AuthMode::Open && config.password.len() < 8 {
+            return Err(SoftApError::InvalidPasswordLength);
+        }
+
+        let command = SoftApConfigurationCommand::new(ssid, password, config)?;
+
+        let mode = match config.station_enabled {
+            true => WifiMode::SoftApStation,
+            false => WifiMode::SoftAp,
+        };
+        self.set_mode(mode, None).map_err(SoftApError::from_mode_error)?;
+
+        self.send_command(command)?;
+        Ok(())
+    }
+
+    /// Returns up to N stations connected to the access point
+    pub fn get_stations<const N: usize>(&mut self) -> Result<Vec<Station, N>, SoftApError> {
+        let responses = self.send_command(ListStationsCommand::<N>::new())?;
+
+        let mut stations = Vec::new();
+        for response in responses {
+            // Capacity of both vectors is equal
+            let _ = stations.push(Station::try_from(response)?);
+        }
+
+        Ok(stations)
+    }
+
+    /// Disconnects the station with the given MAC address, or all stations if None
+    pub fn disconnect_station(&mut self, mac: Option<&MacAddress>) -> Result<(), SoftApError> {
+        self.send_command(DisconnectStationCommand::new(mac))?;
+        Ok(())
+    }
+
+    /// Returns the oldest unhandled station event
+    pub fn next_station_event(&mut self) -> Option<StationEvent> {
+        self.process_urc_messages();
+        self.session.station_events.pop_front()
+    }
+}
+
+impl TryFrom<StationResponse> for Station {
+    type Error = SoftApError;
+
+    fn try_from(response: StationResponse) -> Result<Self, Self::Error> {
+        Ok(Self {
+            ip: Ipv4Addr::from_str(response.ip.as_str()).map_err(|_| SoftApError::AddressParseError)?,
+            mac: MacAddress::from_str(response.mac.as_str()).map_err(|_| SoftApError::AddressParseError)?,
+        })
+    }
+}
@@ -1,6 +1,6 @@
-//! # TCP client stack
+//! # TCP stack
 //!
-//! This crate fully implements [TcpClientStack] of [embedded_nal].
+//! This crate fully implements [TcpClientStack] and [TcpFullStack] of [embedded_nal].
 //!
 //! Block/chunk size is defined a const generics, s. [Adapter] for more details.
 //!
@@ -35,16 +35,64 @@
 //! // Closing socket
 //! adapter.close(socket).unwrap();
 //! ````
+//!
+//! ## TCP server
+//!
+//! ESP-AT supports a single TCP server. Connections of remote clients are assigned to the link ids
+//! by ESP-AT, so the server socket itself does not occupy a link id after `bind()`.
+//!
+//! ````
+//! # use embedded_nal::{TcpClientStack, TcpFullStack};
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::wifi::{Adapter, WifiAdapter};
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let urc_channel = AtClient::urc_channel();
+//! let client = AtClient::init(&urc_channel);
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 128, 8> = Adapter::new(client, urc_channel.subscriber().unwrap(), ExampleTimer::default());
+//!
+//! // Creating a TCP server on port 80
+//! let mut server = adapter.socket().unwrap();
+//! adapter.bind(&mut server, 80).unwrap();
+//! adapter.listen(&mut server).unwrap();
+//!
+//! // Accepting a remote client
+//! let (mut socket, remote) = adapter.accept(&mut server).unwrap();
+//! assert_eq!("10.0.0.2:51234", remote.to_string());
+//! adapter.send(&mut socket, b"hallo!").unwrap();
+//!
+//! // Closing both client connection and server
+//! adapter.close(socket).unwrap();
+//! adapter.close(server).unwrap();
+//! ````
+//!
+//! ## Receiving mode
+//!
+//! By default, ESP-AT buffers received data until it's requested by `receive()` (passive mode). For latency-sensitive
+//! connections ESP-AT may send received data instantly (active mode), s. [set_receive_mode()](Adapter::set_receive_mode).
+//!
+//! In active mode, data is buffered per socket and may be read without copying by
+//! [receive_with()](Adapter::receive_with). The receive queue type is selected by the `Q` generic of the adapter,
+//! s. [queue module](crate::queue).
 
 use crate::commands::{
-    CloseSocketCommand, ConnectCommand, ReceiveDataCommand, SetMultipleConnectionsCommand,
-    SetSocketReceivingModeCommand, TransmissionCommand, TransmissionPrepareCommand,
+    CloseSocketCommand, ConnectCommand, ConnectionStateCommand, ReceiveDataCommand, ReceiveLengthCommand,
+    ServerCommand, ServerMaxConnectionsCommand, ServerTimeoutCommand, SetMultipleConnectionsCommand,
+    SetRemoteInfoCommand, SetSocketReceivingModeCommand, TransmissionCommand, TransmissionPrepareCommand,
 };
+#[cfg(feature = "bbqueue")]
+use crate::queue::BbQueue;
+use crate::queue::ReceiveQueue;
+use crate::responses::ConnectionStateResponse;
+use crate::udp::ReceivedDatagram;
 use crate::wifi::{Adapter, Session};
 use atat::blocking::AtatClient;
 use atat::Error as AtError;
+#[cfg(feature = "bbqueue")]
+use bbqueue::BBBuffer;
+use core::mem;
 use core::net::SocketAddr;
-use embedded_nal::{TcpClientStack, TcpError, TcpErrorKind};
+use embedded_nal::{TcpClientStack, TcpError, TcpErrorKind, TcpFullStack};
 use fugit_timer::Timer;
 use heapless::Vec;
 
@@ -54,11 +102,14 @@ pub struct Socket {
     /// Unique link id of AT
     #[allow(unused)]
     pub(crate) link_id: usize,
+
+    /// True if socket is bound as TCP server. Server sockets do not occupy a link id.
+    pub(crate) server: bool,
 }
 
 impl Socket {
     pub(crate) fn new(link_id: usize) -> Self {
-        Self { link_id }
+        Self { link_id, server: false }
     }
 }
 
@@ -68,8 +119,87 @@ pub(crate) struct SocketState {
     /// Connection state
     pub(crate) state: ConnectionState,
 
-    /// Data length in bytes available to receive which is buffered by ESP-AT
+    /// Data length in bytes available to receive, which is either buffered by ESP-AT (passive receiving mode)
+    /// or by the session (active receiving mode)
     pub(crate) data_available: usize,
+
+    /// Remote address of the last data received in active receiving mode, if CIPDINFO is enabled
+    pub(crate) data_remote: Option<SocketAddr>,
+
+    /// True if data received in active receiving mode was dropped, as the receive buffer was full
+    pub(crate) rx_overflow: bool,
+
+    /// Remote address of the connection, set once the socket is connected
+    pub(crate) remote: Option<SocketAddr>,
+
+    /// Local port of the connection, set by UDP bind or reported by CIPSTATE
+    pub(crate) local_port: Option<u16>,
+
+    /// Link type of the connection, set on connect or reported by CIPSTATE
+    pub(crate) link_type: Option<LinkType>,
+}
+
+impl SocketState {
+    /// State of a connection established by a remote client of the TCP server
+    pub(crate) fn incoming() -> Self {
+        Self {
+            state: ConnectionState::Incoming,
+            ..Self::default()
+        }
+    }
+}
+
+/// Link type of a connection
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LinkType {
+    /// TCP connection (IPv4 or IPv6)
+    Tcp,
+    /// UDP transmission (IPv4 or IPv6)
+    Udp,
+    /// TLS encrypted TCP connection (IPv4 or IPv6)
+    Ssl,
+}
+
+impl LinkType {
+    /// Parses the connection type reported by ESP-AT, e.g. `TCP` or `SSLv6`
+    pub(crate) fn from_connection_type(connection_type: &str) -> Option<Self> {
+        match connection_type.strip_suffix("v6").unwrap_or(connection_type) {
+            "TCP" => Some(Self::Tcp),
+            "UDP" => Some(Self::Udp),
+            "SSL" => Some(Self::Ssl),
+            _ => None,
+        }
+    }
+}
+
+/// Details of a connected socket, s. [socket_info()](Adapter::socket_info)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct SocketInfo {
+    /// Link type of the connection. None if not supported by this crate.
+    pub link_type: Option<LinkType>,
+
+    /// Local port of the connection. Just known for bound UDP sockets, or once reported by
+    /// [sync_sockets()](Adapter::sync_sockets).
+    pub local_port: Option<u16>,
+
+    /// Remote address of the connection. None for UDP sockets accepting datagrams of any remote.
+    pub remote: Option<SocketAddr>,
+}
+
+/// Socket receiving mode of ESP-AT
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum ReceiveMode {
+    /// ESP-AT buffers received data, which gets requested by CIPRECVDATA command on `receive()`
+    #[default]
+    Passive,
+
+    /// ESP-AT sends received data instantly by +IPD messages, which gets buffered per socket by this crate.
+    ///
+    /// Requires a receive queue per socket, which is selected by the `Q` generic of the adapter, s. [crate::queue].
+    /// Saves the roundtrip of requesting the data, but offers no flow control. Data exceeding the buffer
+    /// size of the receive queue of a socket gets dropped, which is signaled by [Error::ReceiveBufferOverflow].
+    /// The same applies to +IPD messages exceeding `RX_SIZE`, which must also fit in the ingress buffer of ATAT.
+    Active,
 }
 
 /// Internal connection state
@@ -79,10 +209,14 @@ pub(crate) enum ConnectionState {
     Closed,
     /// Socket was returned by socket() but is not connected yet
     Open,
+    /// Connect command was sent, but the connection is not confirmed yet
+    Connecting,
     /// Connection is fully open
     Connected,
     /// Socket was closed by URC message, but Socket object still exists and needs to be fully closed by calling 'close()'
     Closing,
+    /// Connection was established by a remote client of the TCP server, but not accepted yet
+    Incoming,
 }
 
 impl Default for ConnectionState {
@@ -97,8 +231,11 @@ pub enum Error {
     /// Error while sending CIPMUX command for enabling multiple connections
     EnablingMultiConnectionsFailed(AtError),
 
-    /// Error while sending CIPRECVMODE command for enabling passive socket receiving mode
-    EnablingPassiveSocketModeFailed(AtError),
+    /// Error while sending CIPRECVMODE command for setting the socket receiving mode
+    ConfiguringReceiveModeFailed(AtError),
+
+    /// Error while sending CIPDINFO command for enabling remote address information of received data
+    EnablingRemoteInfoFailed(AtError),
 
     /// TCP connect command failed
     ConnectError(AtError),
@@ -137,12 +274,68 @@ pub enum Error {
     /// This indicates either a bug in this crate or in AT-ESP firmware.
     ReceiveOverflow,
 
+    /// Received data was dropped in active receiving mode, as the receive buffer of the socket was full or
+    /// a single +IPD message exceeded `RX_SIZE`. Data buffered before is still available.
+    ReceiveBufferOverflow,
+
+    /// Active receiving mode requires a receive queue, s. `Q` generic of the adapter.
+    /// A [BbQueue](crate::queue) needs to be assigned by `set_receive_queues()` of the adapter first.
+    ReceiveBufferDisabled,
+
+    /// Storage of a [BbQueue](crate::queue) is already in use, e.g. by another adapter
+    ReceiveQueueInUse,
+
+    /// Operation is only supported in active receiving mode, s. [ReceiveMode::Active]
+    ActiveReceiveModeRequired,
+
     /// Received an unexpected WouldBlock. The most common cause of errors is an incorrect mode of the client.
     /// This must be either timeout or blocking.
     UnexpectedWouldBlock,
 
     /// Upstream timer error
     TimerError,
+
+    /// Error while sending CIPSERVERMAXCONN command for setting the max. server connections
+    ServerMaxConnectionsError(AtError),
+
+    /// Error while sending CIPSERVER command for creating the TCP server
+    ServerStartError(AtError),
+
+    /// Error while sending CIPSERVER command for deleting the TCP server
+    ServerStopError(AtError),
+
+    /// Error while sending CIPSTO command for setting the server connection timeout
+    ServerTimeoutError(AtError),
+
+    /// Error while sending CIPSTATE command for obtaining connection states
+    ConnectionStateError(AtError),
+
+    /// Error while sending CIPRECVLEN command for obtaining the length of buffered data
+    ReceiveLengthError(AtError),
+
+    /// ESP-AT supports just a single TCP server, which is already bound by another socket
+    ServerAlreadyBound,
+
+    /// Socket needs to be bound by `bind()` first
+    SocketNotBound,
+
+    /// Server sockets may just be used for `listen()`, `accept()` and `close()`
+    ServerSocket,
+
+    /// SSL connect command failed, e.g. due to a failed TLS handshake or certificate verification
+    TlsConnectError(AtError),
+
+    /// Error while sending CIPSSLCCONF command for configuring the certificate verification
+    TlsConfigurationError(AtError),
+
+    /// Error while sending CIPSSLCSNI command for setting the server name indication
+    TlsServerNameError(AtError),
+
+    /// Error while sending CIPSSLCALPN command for setting the application layer protocols
+    TlsAlpnError(AtError),
+
+    /// Server name or ALPN protocols exceed the supported length or count
+    InvalidTlsConfig,
 }
 
 impl TcpError for Error {
@@ -161,13 +354,15 @@ impl<
         const TX_SIZE: usize,
         const RX_SIZE: usize,
         const URC_CAPACITY: usize,
-    > TcpClientStack for Adapter<'_, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, URC_CAPACITY>
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > TcpClientStack for Adapter<'_, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
 {
     type TcpSocket = Socket;
     type Error = Error;
 
     /// Opens and returns a new socket
-    /// Currently only five parallel sockets are supported. If not socket is available [Error::NoSocketAvailable] is returned.
+    /// Up to `MAX_SOCKETS` parallel sockets are supported. If not socket is available [Error::NoSocketAvailable] is returned.
     ///
     /// On first call ESP-AT is configured to support multiple connections.
     fn socket(&mut self) -> Result<Self::TcpSocket, Self::Error> {
@@ -178,37 +373,15 @@ impl<
     /// Opens a new TCP connection. Both IPv4 and IPv6 are supported.
     /// Returns [Error::AlreadyConnected] if socket is already connected.
     ///
-    /// On first call ESP-AT is configured for passive socket receiving mode. So receiving data
-    /// is buffered on ESP-AT to a maximum size of around 8192 bytes.
+    /// On first call ESP-AT is configured for the socket receiving mode, s. [set_receive_mode()](Adapter::set_receive_mode).
+    /// In passive mode (default) receiving data is buffered on ESP-AT to a maximum size of around 8192 bytes.
     fn connect(&mut self, socket: &mut Socket, remote: SocketAddr) -> nb::Result<(), Self::Error> {
-        self.process_urc_messages();
-
-        if self.session.is_socket_connected(socket) {
-            return nb::Result::Err(nb::Error::Other(Error::AlreadyConnected));
-        }
-
-        self.enable_passive_receiving_mode()?;
-        self.session.already_connected = false;
-
         let command = match remote {
             SocketAddr::V4(address) => ConnectCommand::tcp_v4(socket.link_id, address),
             SocketAddr::V6(address) => ConnectCommand::tcp_v6(socket.link_id, address),
         };
-        let result = self.send_command(command);
-        self.process_urc_messages();
 
-        // ESP-AT returned that given socket is already connected. This indicates that a URC Connect message was missed.
-        if self.session.already_connected {
-            self.session.sockets[socket.link_id].state = ConnectionState::Connected;
-            return nb::Result::Ok(());
-        }
-        result?;
-
-        if !self.session.is_socket_connected(socket) {
-            return nb::Result::Err(nb::Error::Other(Error::UnconfirmedSocketState));
-        }
-
-        self.session.reset_available_data(socket);
+        self.open_connection(socket, Some(remote), command)?;
         nb::Result::Ok(())
     }
 
@@ -230,14 +403,295 @@ impl<
     ///
     /// The data is read internally in blocks. The block size is defined by the generic constant RX_SIZE.
     /// In any case, data is read until the buffer is completely filled or no further data is available.
+    ///
+    /// Returns [nb::Error::WouldBlock] if no data is available. If enabled by
+    /// [set_receive_refresh_interval()](Adapter::set_receive_refresh_interval), the available data is
+    /// queried from ESP-AT once the socket was idle for the given number of calls.
     fn receive(&mut self, socket: &mut Self::TcpSocket, buffer: &mut [u8]) -> nb::Result<usize, Self::Error> {
+        self.receive_data(socket, buffer, false)
+    }
+
+    /// Closes a socket
+    ///
+    /// If the socket has already been closed by the remote side or is not connected, no command
+    /// is sent to the ESP-AT but only the internal status is set.
+    /// In case of an error (which is returned) the socket is internally set to closed so that it is not lost and can be reused.
+    fn close(&mut self, socket: Self::TcpSocket) -> Result<(), Self::Error> {
+        if socket.server {
+            return self.stop_server();
+        }
+
+        self.close_socket(&socket)
+    }
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > TcpFullStack for Adapter<'_, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    /// Binds the socket to the given local port. ESP-AT supports just a single TCP server, so
+    /// [Error::ServerAlreadyBound] is returned if another socket is already bound.
+    ///
+    /// The socket releases its link id, as ESP-AT assigns link ids to incoming connections itself.
+    /// No command is sent until `listen()` is called.
+    fn bind(&mut self, socket: &mut Self::TcpSocket, local_port: u16) -> Result<(), Self::Error> {
         self.process_urc_messages();
 
-        if !self.session.is_data_available(socket) {
+        if self.session.server_port.is_some() {
+            return Err(Error::ServerAlreadyBound);
+        }
+
+        if socket.server || !self.session.is_socket_open(socket) {
+            return Err(Error::AlreadyConnected);
+        }
+
+        self.session.sockets[socket.link_id].state = ConnectionState::Closed;
+        self.session.server_port = Some(local_port);
+        socket.server = true;
+        Ok(())
+    }
+
+    /// Creates the TCP server on the bound port.
+    ///
+    /// Max. connections and connection timeout are configured beforehand, if set by
+    /// [set_server_max_connections()](Adapter::set_server_max_connections) resp.
+    /// [set_server_timeout_s()](Adapter::set_server_timeout_s).
+    /// On first call ESP-AT is configured for the socket receiving mode, s. [set_receive_mode()](Adapter::set_receive_mode).
+    fn listen(&mut self, socket: &mut Self::TcpSocket) -> Result<(), Self::Error> {
+        let port = match self.session.server_port {
+            Some(port) if socket.server => port,
+            _ => return Err(Error::SocketNotBound),
+        };
+
+        self.configure_receiving_mode()?;
+
+        if let Some(max_connections) = self.server_max_connections {
+            self.send_command(ServerMaxConnectionsCommand::new(max_connections))?;
+        }
+
+        self.send_command(ServerCommand::create(port))?;
+        self.session.server_listening = true;
+
+        if let Some(timeout) = self.server_timeout {
+            self.send_command(ServerTimeoutCommand::new(timeout))?;
+        }
+
+        Ok(())
+    }
+
+    /// Accepts the next pending connection of a remote client. Returns [nb::Error::WouldBlock] if
+    /// no connection is pending.
+    ///
+    /// The remote address is obtained by CIPSTATE command, s. [sync_sockets()](Adapter::sync_sockets).
+    fn accept(&mut self, socket: &mut Self::TcpSocket) -> nb::Result<(Self::TcpSocket, SocketAddr), Self::Error> {
+        if !socket.server || !self.session.server_listening {
+            return nb::Result::Err(nb::Error::Other(Error::SocketNotBound));
+        }
+
+        self.process_urc_messages();
+
+        let link_id = match self.session.get_next_incoming() {
+            None => return nb::Result::Err(nb::Error::WouldBlock),
+            Some(link_id) => link_id,
+        };
+
+        self.sync_sockets()?;
+        let remote = match self.session.sockets[link_id] {
+            SocketState {
+                state: ConnectionState::Incoming,
+                remote: Some(remote),
+                ..
+            } => remote,
+            _ => return nb::Result::Err(nb::Error::Other(Error::ConnectionStateError(AtError::InvalidResponse))),
+        };
+
+        self.session.sockets[link_id].state = ConnectionState::Connected;
+        nb::Result::Ok((Socket::new(link_id), remote))
+    }
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > Adapter<'_, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    /// Returns true if the socket is currently connected. Connection aborts by the remote side are also taken into account.
+    /// The current implementation never returns a Error.
+    pub fn is_connected(&mut self, socket: &Socket) -> Result<bool, Error> {
+        self.process_urc_messages();
+        Ok(!socket.server && self.session.is_socket_connected(socket))
+    }
+
+    /// Returns the details of the given socket, or None if the socket is not connected
+    pub fn socket_info(&mut self, socket: &Socket) -> Option<SocketInfo> {
+        if !self.is_connected(socket).ok()? {
+            return None;
+        }
+
+        let state = &self.session.sockets[socket.link_id];
+        Some(SocketInfo {
+            link_type: state.link_type,
+            local_port: state.local_port,
+            remote: state.remote,
+        })
+    }
+
+    /// Queries the state of all connections by CIPSTATE command and updates the internal socket states.
+    ///
+    /// Socket states are usually tracked by URC messages. If a message got lost, e.g. by an overflow of the URC
+    /// channel, the internal state diverges from ESP-AT. Connections no longer reported by ESP-AT need to be closed
+    /// by `close()`, pending connects get confirmed if reported.
+    ///
+    /// Gets called automatically if a connect was not confirmed by URC message.
+    pub fn sync_sockets(&mut self) -> Result<(), Error> {
+        self.process_urc_messages();
+        let connections = self.send_command(ConnectionStateCommand::<MAX_SOCKETS>::new())?;
+        self.session.sync_sockets(&connections);
+        Ok(())
+    }
+
+    /// Establishes a connection of the given socket using the given CIPSTART command.
+    /// Shared by TCP and UDP stack. Remote is None for UDP sockets accepting datagrams of any remote.
+    pub(crate) fn open_connection(
+        &mut self,
+        socket: &Socket,
+        remote: Option<SocketAddr>,
+        command: ConnectCommand,
+    ) -> Result<(), Error> {
+        self.process_urc_messages();
+        self.assert_socket_connectable(socket)?;
+
+        self.configure_receiving_mode()?;
+        self.session.already_connected = false;
+
+        // Resetting before connecting, so that data received right after connecting is not lost
+        self.session.reset_available_data(socket);
+        self.receive_idle_calls[socket.link_id] = 0;
+        let state = &mut self.session.sockets[socket.link_id];
+        state.state = ConnectionState::Connecting;
+        state.link_type = LinkType::from_connection_type(command.connection_type());
+        state.local_port = command.local_port();
+
+        let result = self.await_connection(socket, command);
+
+        // Link id is still assigned to the socket, so it may be used for another connect attempt
+        if result.is_err() && self.session.sockets[socket.link_id].state == ConnectionState::Connecting {
+            self.session.sockets[socket.link_id].state = ConnectionState::Open;
+        }
+        result?;
+
+        self.session.sockets[socket.link_id].remote = remote;
+        Ok(())
+    }
+
+    /// Sends the connect command and awaits the confirmation of the connection
+    fn await_connection(&mut self, socket: &Socket, command: ConnectCommand) -> Result<(), Error> {
+        let result = self.send_command(command);
+        self.process_urc_messages();
+
+        // ESP-AT returned that given socket is already connected. This indicates that a URC Connect message was missed.
+        if self.session.already_connected {
+            self.session.sockets[socket.link_id].state = ConnectionState::Connected;
+            return Ok(());
+        }
+        result?;
+
+        // URC message may got lost, so the actual state is queried
+        if !self.session.is_socket_connected(socket)
+            && (self.sync_sockets().is_err() || !self.session.is_socket_connected(socket))
+        {
+            return Err(Error::UnconfirmedSocketState);
+        }
+
+        Ok(())
+    }
+
+    /// Asserts that the given socket is neither a server socket nor already connected
+    pub(crate) fn assert_socket_connectable(&self, socket: &Socket) -> Result<(), Error> {
+        if socket.server {
+            return Err(Error::ServerSocket);
+        }
+
+        // Link id was assigned to a remote client of the TCP server in the meantime
+        if self.session.is_socket_connected(socket) || self.session.is_socket_incoming(socket) {
+            return Err(Error::AlreadyConnected);
+        }
+
+        Ok(())
+    }
+
+    /// Sends the given buffer as single message (e.g. UDP datagram) using just one CIPSEND command.
+    /// The data is still written in blocks of TX_SIZE, but transmission is confirmed just once.
+    ///
+    /// If remote is given, the datagram is sent to the given remote instead of the connected one (UDP only).
+    pub(crate) fn send_datagram(
+        &mut self,
+        socket: &Socket,
+        remote: Option<SocketAddr>,
+        buffer: &[u8],
+    ) -> nb::Result<(), Error> {
+        self.process_urc_messages();
+        self.assert_socket_connected(socket)?;
+
+        let command = match remote {
+            None => TransmissionPrepareCommand::new(socket.link_id, buffer.len()),
+            Some(remote) => TransmissionPrepareCommand::udp_remote(socket.link_id, buffer.len(), remote),
+        };
+        self.send_command(command)?;
+
+        self.session.send_confirmed = None;
+        self.session.recv_byte_count = None;
+
+        for chunk in buffer.chunks(TX_SIZE) {
+            self.send_command::<TransmissionCommand<'_, TX_SIZE>>(TransmissionCommand::new(chunk))?;
+        }
+
+        self.await_send_confirmation(buffer.len())?;
+        nb::Result::Ok(())
+    }
+
+    /// Receives data (if available) of the given socket and writes it to the given buffer.
+    /// Shared by TCP and UDP stack. If single_chunk is true, just a single CIPRECVDATA response is read.
+    pub(crate) fn receive_data(
+        &mut self,
+        socket: &Socket,
+        buffer: &mut [u8],
+        single_chunk: bool,
+    ) -> nb::Result<usize, Error> {
+        self.process_urc_messages();
+
+        if socket.server {
+            return nb::Result::Err(nb::Error::Other(Error::ServerSocket));
+        }
+
+        // Data may be dropped completely, so the overflow is reported even if no data is available
+        self.session.take_receive_overflow(socket)?;
+
+        if !self.session.is_data_available(socket) && !self.refresh_idle_socket(socket)? {
             return nb::Result::Err(nb::Error::WouldBlock);
         }
 
+        self.receive_idle_calls[socket.link_id] = 0;
+
+        if self.session.receive_mode == ReceiveMode::Active {
+            return nb::Result::Ok(self.session.read_received_data(socket, buffer)?);
+        }
+
         let mut buffer: Buffer<RX_SIZE> = Buffer::new(buffer);
+        self.session.data_remote = None;
 
         while self.session.is_data_available(socket) && !buffer.is_full() {
             let command = ReceiveDataCommand::<RX_SIZE>::new(socket.link_id, buffer.get_next_length());
@@ -251,60 +705,47 @@ impl<
             let data = self.session.data.take().unwrap();
             self.session.reduce_available_data(socket, data.len());
             buffer.append(data)?;
+
+            if single_chunk {
+                break;
+            }
         }
 
         nb::Result::Ok(buffer.len())
     }
 
-    /// Closes a socket
+    /// Closes the given socket. Shared by TCP and UDP stack.
     ///
     /// If the socket has already been closed by the remote side or is not connected, no command
     /// is sent to the ESP-AT but only the internal status is set.
     /// In case of an error (which is returned) the socket is internally set to closed so that it is not lost and can be reused.
-    fn close(&mut self, socket: Self::TcpSocket) -> Result<(), Self::Error> {
+    pub(crate) fn close_socket(&mut self, socket: &Socket) -> Result<(), Error> {
         self.process_urc_messages();
 
-        // Socket already closed during restart
-        if self.session.is_socket_closed(&socket) {
+        // Socket already closed during restart or link id was assigned to a pending server connection meanwhile
+        if self.session.is_socket_closed(socket) || self.session.is_socket_incoming(socket) {
             return Ok(());
         }
 
         // Socket is not connected yet or was already closed remotely
-        if self.session.is_socket_closing(&socket) || self.session.is_socket_open(&socket) {
-            self.session.sockets[socket.link_id].state = ConnectionState::Closed;
+        if self.session.is_socket_closing(socket) || self.session.is_socket_open(socket) {
+            self.session.sockets[socket.link_id] = SocketState::default();
             return Ok(());
         }
 
         let mut result = self.send_command(CloseSocketCommand::new(socket.link_id));
         self.process_urc_messages();
 
-        if !self.session.is_socket_closing(&socket) && result.is_ok() {
+        if !self.session.is_socket_closing(socket) && result.is_ok() {
             result = Err(Error::UnconfirmedSocketState);
         }
 
         // Setting to Closed even on error. Otherwise socket can not be reused in future, as its consumed.
-        self.session.sockets[socket.link_id].state = ConnectionState::Closed;
+        self.session.sockets[socket.link_id] = SocketState::default();
 
         result?;
         Ok(())
     }
-}
-
-impl<
-        A: AtatClient,
-        T: Timer<TIMER_HZ>,
-        const TIMER_HZ: u32,
-        const TX_SIZE: usize,
-        const RX_SIZE: usize,
-        const URC_CAPACITY: usize,
-    > Adapter<'_, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, URC_CAPACITY>
-{
-    /// Returns true if the socket is currently connected. Connection aborts by the remote side are also taken into account.
-    /// The current implementation never returns a Error.
-    pub fn is_connected(&mut self, socket: &Socket) -> Result<bool, Error> {
-        self.process_urc_messages();
-        Ok(self.session.is_socket_connected(socket))
-    }
 
     /// Sends a chunk of max. 256 bytes
     fn send_chunk(&mut self, data: &[u8]) -> Result<(), Error> {
@@ -312,6 +753,11 @@ impl<
         self.session.recv_byte_count = None;
 
         self.send_command::<TransmissionCommand<'_, TX_SIZE>>(TransmissionCommand::new(data))?;
+        self.await_send_confirmation(data.len())
+    }
+
+    /// Blocks until the transmission of the given data length is confirmed by URC message or the send timeout is reached
+    fn await_send_confirmation(&mut self, length: usize) -> Result<(), Error> {
         self.timer.start(self.send_timeout).map_err(|_| Error::TimerError)?;
 
         while self.session.send_confirmed.is_none() {
@@ -324,7 +770,7 @@ impl<
                 }
 
                 // Byte count does not match
-                if self.session.is_received_byte_count_incorrect(data.len()) {
+                if self.session.is_received_byte_count_incorrect(length) {
                     return Err(Error::PartialSend);
                 }
 
@@ -345,9 +791,94 @@ impl<
         Ok(())
     }
 
+    /// Deletes the TCP server and releases the bound port. Already accepted connections are kept open.
+    fn stop_server(&mut self) -> Result<(), Error> {
+        let listening = self.session.server_listening;
+
+        self.session.server_port = None;
+        self.session.server_listening = false;
+
+        if listening {
+            self.send_command(ServerCommand::delete())?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the length of the data buffered by ESP-AT for the given socket, as reported by URC messages or
+    /// [refresh_available_data()](Adapter::refresh_available_data). In active receiving mode the length of the
+    /// data buffered by this crate is returned.
+    pub fn available(&mut self, socket: &Socket) -> usize {
+        self.process_urc_messages();
+
+        match socket.server {
+            true => 0,
+            false => self.session.sockets[socket.link_id].data_available,
+        }
+    }
+
+    /// Queries the length of the buffered data of all sockets by CIPRECVLEN command.
+    ///
+    /// The length is usually reported by +IPD URC messages. If a message got lost, e.g. by an overflow of the URC
+    /// channel, data remains buffered by ESP-AT until further data arrives.
+    ///
+    /// Has no effect in active receiving mode, as ESP-AT does not buffer any data.
+    pub fn refresh_available_data(&mut self) -> Result<(), Error> {
+        self.process_urc_messages();
+
+        if self.session.receive_mode == ReceiveMode::Active {
+            return Ok(());
+        }
+
+        let response = self.send_command(ReceiveLengthCommand::<MAX_SOCKETS>::new())?;
+        self.session.update_available_data(&response.lengths);
+        Ok(())
+    }
+
+    /// Sets the number of consecutive `receive()` calls without data, after which `receive()` refreshes the
+    /// available data by [refresh_available_data()](Adapter::refresh_available_data). So for polling every
+    /// 10 ms, an interval of 100 refreshes an idle socket about once per second. Disabled if None, which is the default.
+    ///
+    /// Idle periods are counted in calls instead of time, as the timer of the adapter is restarted by other
+    /// operations and therefore does not provide a continuous time base.
+    pub fn set_receive_refresh_interval(&mut self, idle_calls: Option<u32>) {
+        self.receive_refresh_interval = idle_calls;
+    }
+
+    /// Refreshes the available data, if the given socket was idle for the configured number of `receive()` calls.
+    /// Returns true if data is available afterward.
+    fn refresh_idle_socket(&mut self, socket: &Socket) -> Result<bool, Error> {
+        let interval = match self.receive_refresh_interval {
+            Some(interval) if self.session.receive_mode == ReceiveMode::Passive => interval,
+            _ => return Ok(false),
+        };
+
+        let idle_calls = &mut self.receive_idle_calls[socket.link_id];
+        *idle_calls = idle_calls.saturating_add(1);
+        if *idle_calls < interval {
+            return Ok(false);
+        }
+
+        *idle_calls = 0;
+        self.refresh_available_data()?;
+        Ok(self.session.is_data_available(socket))
+    }
+
+    /// Sets the max. number of connections accepted by the TCP server. Gets applied by next `listen()` call.
+    /// Value is limited by the number of link ids (`MAX_SOCKETS`).
+    pub fn set_server_max_connections(&mut self, max_connections: usize) {
+        self.server_max_connections = Some(max_connections);
+    }
+
+    /// Sets the timeout in seconds, after which idle connections accepted by the TCP server are closed.
+    /// Gets applied by next `listen()` call. Range: 0-7200, 0 = never timeout
+    pub fn set_server_timeout_s(&mut self, timeout: u16) {
+        self.server_timeout = Some(timeout);
+    }
+
     /// Enables multiple connections.
     /// Stores internal state, so command is just sent once for saving bandwidth
-    fn enable_multiple_connections(&mut self) -> Result<(), Error> {
+    pub(crate) fn enable_multiple_connections(&mut self) -> Result<(), Error> {
         if self.session.multi_connections_enabled {
             return Ok(());
         }
@@ -357,20 +888,65 @@ impl<
         Ok(())
     }
 
-    /// Enables the passive socket receiving mode
+    /// Sets the socket receiving mode, which is passive by default. Gets applied by next `connect()` or `listen()`
+    /// call, so it should be set before opening the first connection. See [ReceiveMode] for details.
+    ///
+    /// Returns [Error::ReceiveBufferDisabled] when enabling active mode without receive queue (`Q` generic).
+    pub fn set_receive_mode(&mut self, mode: ReceiveMode) -> Result<(), Error> {
+        self.session.set_receive_mode(mode)
+    }
+
+    /// Passes data received in [active receiving mode](ReceiveMode::Active) to the given function without copying
+    /// it to a separate buffer. The function returns the consumed length, unconsumed data stays buffered.
+    ///
+    /// Just a single contiguous chunk is passed. With [BbQueue](crate::queue), data wrapping around the end of the
+    /// queue is passed by the next call. Returns [Error::ActiveReceiveModeRequired] in passive receiving mode.
+    pub fn receive_with<F: FnOnce(&[u8]) -> usize>(&mut self, socket: &Socket, f: F) -> nb::Result<usize, Error> {
+        self.process_urc_messages();
+
+        if socket.server {
+            return nb::Result::Err(nb::Error::Other(Error::ServerSocket));
+        }
+
+        if self.session.receive_mode != ReceiveMode::Active {
+            return nb::Result::Err(nb::Error::Other(Error::ActiveReceiveModeRequired));
+        }
+
+        self.session.take_receive_overflow(socket)?;
+
+        if !self.session.is_data_available(socket) {
+            return nb::Result::Err(nb::Error::WouldBlock);
+        }
+
+        nb::Result::Ok(self.session.read_received_data_with(socket, f)?)
+    }
+
+    /// Configures the socket receiving mode
     /// Stores internal state, so command is just sent once for saving bandwidth
-    fn enable_passive_receiving_mode(&mut self) -> Result<(), Error> {
-        if self.session.passive_mode_enabled {
+    fn configure_receiving_mode(&mut self) -> Result<(), Error> {
+        if self.session.receive_mode_configured {
             return Ok(());
         }
 
-        self.send_command(SetSocketReceivingModeCommand::passive_mode())?;
-        self.session.passive_mode_enabled = true;
+        self.send_command(SetSocketReceivingModeCommand::new(self.session.receive_mode))?;
+        self.session.receive_mode_configured = true;
+        Ok(())
+    }
+
+    /// Enables remote address information of received data (CIPDINFO)
+    /// Stores internal state, so command is just sent once for saving bandwidth
+    pub(crate) fn enable_remote_info(&mut self) -> Result<(), Error> {
+        if self.session.remote_info_enabled {
+            return Ok(());
+        }
+
+        self.send_command(SetRemoteInfoCommand::enabled())?;
+        self.session.remote_info_enabled = true;
         Ok(())
     }
 
     /// Assigns a free link_id. Returns an error in case no more free sockets are available
-    fn open_socket(&mut self) -> Result<Socket, Error> {
+    pub(crate) fn open_socket(&mut self) -> Result<Socket, Error> {
         if let Some(link_id) = self.session.get_next_open() {
             self.session.sockets[link_id].state = ConnectionState::Open;
             return Ok(Socket::new(link_id));
@@ -380,7 +956,11 @@ impl<
     }
 
     /// Asserts that the given socket is connected and returns otherwise the appropriate error
-    fn assert_socket_connected(&self, socket: &Socket) -> nb::Result<(), Error> {
+    pub(crate) fn assert_socket_connected(&self, socket: &Socket) -> nb::Result<(), Error> {
+        if socket.server {
+            return nb::Result::Err(nb::Error::Other(Error::ServerSocket));
+        }
+
         if self.session.is_socket_closing(socket) {
             return nb::Result::Err(nb::Error::Other(Error::ClosingSocket));
         }
@@ -393,19 +973,93 @@ impl<
     }
 }
 
-impl<const RX_SIZE: usize> Session<RX_SIZE> {
+#[cfg(feature = "bbqueue")]
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        const N: usize,
+    > Adapter<'_, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, BbQueue<N>>
+{
+    /// Assigns the storage of the [BbQueue] of each socket, which is required for enabling
+    /// [active receiving mode](ReceiveMode::Active). Array index = link id.
+    ///
+    /// Returns [Error::ReceiveQueueInUse] if any storage is already split, e.g. by another adapter. In this case no
+    /// storage is assigned. Otherwise storages of a previous call get released, so they may be assigned again.
+    pub fn set_receive_queues(&mut self, storages: [&'static BBBuffer<N>; MAX_SOCKETS]) -> Result<(), Error> {
+        self.session.set_receive_queues(storages)
+    }
+}
+
+impl<const RX_SIZE: usize, const MAX_SOCKETS: usize, Q: ReceiveQueue> Session<RX_SIZE, MAX_SOCKETS, Q> {
     /// Fetches the next open socket ID and returns None in case no socket is available
-    fn get_next_open(&self) -> Option<usize> {
+    pub(crate) fn get_next_open(&self) -> Option<usize> {
         self.sockets.iter().position(|state| state.state == ConnectionState::Closed)
     }
 
+    /// Reconciles the socket states with the connections reported by CIPSTATE command
+    pub(crate) fn sync_sockets(&mut self, connections: &[ConnectionStateResponse]) {
+        for (link_id, socket) in self.sockets.iter_mut().enumerate() {
+            let connection = match connections.iter().find(|connection| connection.link_id == link_id) {
+                Some(connection) => connection,
+                None => {
+                    // Closed without noticing, e.g. as the CLOSED URC message got lost
+                    match socket.state {
+                        ConnectionState::Connected => socket.state = ConnectionState::Closing,
+                        ConnectionState::Incoming => *socket = SocketState::default(),
+                        _ => {}
+                    }
+                    continue;
+                }
+            };
+
+            match socket.state {
+                ConnectionState::Connecting => socket.state = ConnectionState::Connected,
+                ConnectionState::Connected | ConnectionState::Incoming => {}
+                // ESP-AT may assign any link not connected on its side to remote clients of the TCP server
+                _ if connection.tetype == 1 => {
+                    *socket = SocketState::incoming();
+                    self.rx_buffers[link_id].clear();
+                }
+                // Unknown client connection, e.g. opened before a restart of the MCU, which can not be adopted
+                _ => continue,
+            }
+
+            let link_type = LinkType::from_connection_type(connection.connection_type.as_str());
+
+            // Remote of UDP sockets may change by received datagrams, so the address of connect() is kept
+            if link_type != Some(LinkType::Udp) {
+                socket.remote = connection.remote();
+            }
+
+            socket.local_port = Some(connection.local_port);
+            socket.link_type = link_type;
+        }
+    }
+
+    /// Sets the available data of all sockets to the lengths reported by CIPRECVLEN command
+    pub(crate) fn update_available_data(&mut self, lengths: &[usize]) {
+        for (socket, length) in self.sockets.iter_mut().zip(lengths) {
+            socket.data_available = *length;
+        }
+    }
+
+    /// Fetches the link ID of the next connection established by a remote client
+    fn get_next_incoming(&self) -> Option<usize> {
+        self.sockets.iter().position(|state| state.state == ConnectionState::Incoming)
+    }
+
     /// Returns true if data is available for the given socket
-    fn is_data_available(&self, socket: &Socket) -> bool {
+    pub(crate) fn is_data_available(&self, socket: &Socket) -> bool {
         self.sockets[socket.link_id].data_available > 0
     }
 
     /// Reduces the available data length mark by the given length of the given socket ID
-    fn reduce_available_data(&mut self, socket: &Socket, length: usize) {
+    pub(crate) fn reduce_available_data(&mut self, socket: &Socket, length: usize) {
         if self.sockets[socket.link_id].data_available < length {
             self.sockets[socket.link_id].data_available = 0;
             return;
@@ -416,32 +1070,159 @@ impl<const RX_SIZE: usize> Session<RX_SIZE> {
 
     /// Returns true if the reported received byte length does NOT match the actual data length
     /// Returns false if received byte count was not reported by ESP-AT (older firmware version)
-    fn is_received_byte_count_incorrect(&self, actual_data_length: usize) -> bool {
+    pub(crate) fn is_received_byte_count_incorrect(&self, actual_data_length: usize) -> bool {
         self.recv_byte_count.is_some() && *self.recv_byte_count.as_ref().unwrap() != actual_data_length
     }
 
-    /// Sets the available data of the given socket to zero
-    fn reset_available_data(&mut self, socket: &Socket) {
+    /// Sets the available data of the given socket to zero and drops data received in active receiving mode
+    pub(crate) fn reset_available_data(&mut self, socket: &Socket) {
         self.sockets[socket.link_id].data_available = 0;
+        self.sockets[socket.link_id].rx_overflow = false;
+        self.rx_buffers[socket.link_id].clear();
+        self.rx_datagrams[socket.link_id].clear();
+    }
+
+    /// Changes the socket receiving mode, which gets configured by the next connect or listen call
+    pub(crate) fn set_receive_mode(&mut self, mode: ReceiveMode) -> Result<(), Error> {
+        if mode == ReceiveMode::Active && !self.rx_buffers.iter().all(ReceiveQueue::is_enabled) {
+            return Err(Error::ReceiveBufferDisabled);
+        }
+
+        if self.receive_mode != mode {
+            self.receive_mode = mode;
+            self.receive_mode_configured = false;
+        }
+
+        Ok(())
+    }
+
+    /// Appends data received in active receiving mode to the receive buffer of the given link id.
+    /// Data exceeding the buffer capacity is dropped, as well as the truncated part of the URC message.
+    /// Datagrams of UDP sockets are dropped completely, if MAX_RX_DATAGRAMS are already buffered.
+    pub(crate) fn buffer_received_data(
+        &mut self,
+        link_id: usize,
+        data: &[u8],
+        remote: Option<SocketAddr>,
+        truncated: bool,
+    ) {
+        let (Some(socket), Some(rx_buffer)) = (self.sockets.get_mut(link_id), self.rx_buffers.get_mut(link_id)) else {
+            return;
+        };
+
+        let datagrams = &mut self.rx_datagrams[link_id];
+        let udp = socket.link_type == Some(LinkType::Udp);
+
+        if udp && datagrams.is_full() {
+            socket.rx_overflow = true;
+            return;
+        }
+
+        let length = rx_buffer.push(data);
+
+        if truncated || length < data.len() {
+            socket.rx_overflow = true;
+        }
+
+        if udp && length > 0 {
+            // Capacity is checked above
+            let _ = datagrams.push_back(ReceivedDatagram { length, remote });
+        }
+
+        socket.data_available = rx_buffer.len();
+        socket.data_remote = remote;
+    }
+
+    /// Returns [Error::ReceiveBufferOverflow] once, if data received in active receiving mode was dropped
+    pub(crate) fn take_receive_overflow(&mut self, socket: &Socket) -> Result<(), Error> {
+        if mem::take(&mut self.sockets[socket.link_id].rx_overflow) {
+            return Err(Error::ReceiveBufferOverflow);
+        }
+
+        Ok(())
+    }
+
+    /// Moves data received in active receiving mode to the given buffer. Returns the copied length.
+    /// Just the next datagram is read for UDP sockets.
+    pub(crate) fn read_received_data(&mut self, socket: &Socket, buffer: &mut [u8]) -> Result<usize, Error> {
+        let length = self.next_datagram_length(socket).unwrap_or(buffer.len()).min(buffer.len());
+        self.consume_received_data(socket, |rx_buffer| rx_buffer.read(&mut buffer[..length]))
+    }
+
+    /// Passes the next chunk of data received in active receiving mode to the given function, which returns the
+    /// consumed length. The chunk is limited to the next datagram for UDP sockets.
+    pub(crate) fn read_received_data_with<F: FnOnce(&[u8]) -> usize>(
+        &mut self,
+        socket: &Socket,
+        f: F,
+    ) -> Result<usize, Error> {
+        let limit = self.next_datagram_length(socket);
+
+        self.consume_received_data(socket, |rx_buffer| {
+            rx_buffer.read_with(|chunk| {
+                let chunk = &chunk[..limit.unwrap_or(chunk.len()).min(chunk.len())];
+                f(chunk).min(chunk.len())
+            })
+        })
+    }
+
+    /// Returns the unread length of the next buffered datagram, None if the socket is not a UDP socket
+    fn next_datagram_length(&self, socket: &Socket) -> Option<usize> {
+        if self.sockets[socket.link_id].link_type != Some(LinkType::Udp) {
+            return None;
+        }
+
+        Some(self.rx_datagrams[socket.link_id].front().map_or(0, |datagram| datagram.length))
+    }
+
+    /// Consumes data of the receive buffer by the given function and updates the available data length.
+    /// The remote of the consumed data is taken from the next datagram for UDP sockets.
+    fn consume_received_data<F: FnOnce(&mut Q) -> usize>(&mut self, socket: &Socket, f: F) -> Result<usize, Error> {
+        let state = &mut self.sockets[socket.link_id];
+        let rx_buffer = &mut self.rx_buffers[socket.link_id];
+        let length = f(rx_buffer);
+
+        state.data_available = rx_buffer.len();
+        self.data_remote = state.data_remote;
+
+        if state.link_type == Some(LinkType::Udp) {
+            let datagrams = &mut self.rx_datagrams[socket.link_id];
+
+            if let Some(datagram) = datagrams.front_mut() {
+                datagram.length -= length;
+                self.data_remote = datagram.remote;
+
+                if datagram.length == 0 {
+                    datagrams.pop_front();
+                }
+            }
+        }
+
+        Ok(length)
     }
 
     /// Returns true if the given socket is in OPEN state
-    fn is_socket_open(&self, socket: &Socket) -> bool {
+    pub(crate) fn is_socket_open(&self, socket: &Socket) -> bool {
         self.sockets[socket.link_id].state == ConnectionState::Open
     }
 
     /// Returns true if the given socket is in CLOSED state
-    fn is_socket_closed(&self, socket: &Socket) -> bool {
+    pub(crate) fn is_socket_closed(&self, socket: &Socket) -> bool {
         self.sockets[socket.link_id].state == ConnectionState::Closed
     }
 
     /// Returns true if the given socket is in CLOSING state
-    fn is_socket_closing(&self, socket: &Socket) -> bool {
+    pub(crate) fn is_socket_closing(&self, socket: &Socket) -> bool {
         self.sockets[socket.link_id].state == ConnectionState::Closing
     }
 
+    /// Returns true if the link id of the given socket is assigned to a pending server connection
+    pub(crate) fn is_socket_incoming(&self, socket: &Socket) -> bool {
+        self.sockets[socket.link_id].state == ConnectionState::Incoming
+    }
+
     /// Returns true if the given socket is in CONNECTED state
-    fn is_socket_connected(&self, socket: &Socket) -> bool {
+    pub(crate) fn is_socket_connected(&self, socket: &Socket) -> bool {
         self.sockets[socket.link_id].state == ConnectionState::Connected
     }
 }
@@ -502,3 +1283,30 @@ impl<'a, const CHUNK_SIZE: usize> Buffer<'a, CHUNK_SIZE> {
         self.position
     }
 }
+
+#[cfg(feature = "bbqueue")]
+impl<const RX_SIZE: usize, const MAX_SOCKETS: usize, const N: usize> Session<RX_SIZE, MAX_SOCKETS, BbQueue<N>> {
+    /// Assigns the storage of the receive queue of each link id. Either all or no storages get assigned.
+    pub(crate) fn set_receive_queues(&mut self, storages: [&'static BBBuffer<N>; MAX_SOCKETS]) -> Result<(), Error> {
+        let mut queues = storages.map(|storage| storage.try_split().ok());
+
+        if queues.iter().any(Option::is_none) {
+            // Releasing the storages split above, so that they are not lost
+            for (storage, queue) in storages.iter().zip(queues.iter_mut()) {
+                if let Some((producer, consumer)) = queue.take() {
+                    let _ = storage.try_release(producer, consumer);
+                }
+            }
+
+            return Err(Error::ReceiveQueueInUse);
+        }
+
+        for ((rx_buffer, storage), queue) in self.rx_buffers.iter_mut().zip(storages).zip(queues) {
+            if let Some((producer, consumer)) = queue {
+                rx_buffer.assign(storage, producer, consumer);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,9 +1,10 @@
 use crate::tests::mock::{MockAtatClient, MockTimer, MockedCommand};
 use crate::urc::URCMessages;
 use crate::wifi::WifiAdapter;
-use crate::wifi::{Adapter, AddressErrors};
+use crate::wifi::{Adapter, AddressErrors, CommandError, InterfaceAddress, MacAddress};
 use alloc::string::ToString;
 use atat::Error as AtError;
+use core::str::FromStr;
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_sync::pubsub::PubSubChannel;
 
@@ -23,7 +24,7 @@ fn test_all_addresses() {
     });
 
     let address = adapter.get_address().unwrap();
-    assert_eq!("10:fe:ed:05:ba:50", address.mac.unwrap().as_str());
+    assert_eq!("10:fe:ed:05:ba:50", address.mac.unwrap().to_string());
     assert_eq!("10.0.0.181", address.ipv4.unwrap().to_string());
     assert_eq!("fe80::e6ee:e64e:84c:a745", address.ipv6_link_local.unwrap().to_string());
     assert_eq!(
@@ -32,6 +33,78 @@ fn test_all_addresses() {
     );
 }
 
+#[test]
+fn test_soft_ap_station_addresses() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    adapter.client.add_response(MockedCommand {
+        command: Some(b"AT+CIFSR\r\n"),
+        response: b"+CIFSR:APIP,\"192.168.4.1\"\r\n+CIFSR:APIP6LL,\"fe80::12fe:edff:fe05:ba51\"\r\n+CIFSR:APIP6GL,\"2a02:810d:1340:2df5::1\"\r\n+CIFSR:APMAC,\"12:fe:ed:05:ba:51\"\r\n+CIFSR:STAIP,\"10.0.0.181\"\r\n+CIFSR:STAIP6LL,\"fe80::e6ee:e64e:84c:a745\"\r\n+CIFSR:STAIP6GL,\"2a02:810d:1340:2df5:68e1:704d:4a72:656a\"\r\n+CIFSR:STAMAC,\"10:fe:ed:05:ba:50\"\r\n",
+        urc_messages: None,
+    });
+
+    let address = adapter.get_address().unwrap();
+    assert_eq!("10.0.0.181", address.ipv4.unwrap().to_string());
+    assert_eq!("10:fe:ed:05:ba:50", address.mac.unwrap().to_string());
+    assert_eq!("fe80::e6ee:e64e:84c:a745", address.ipv6_link_local.unwrap().to_string());
+    assert_eq!(
+        "2a02:810d:1340:2df5:68e1:704d:4a72:656a",
+        address.ipv6_global.unwrap().to_string()
+    );
+
+    assert_eq!("192.168.4.1", address.soft_ap.ipv4.unwrap().to_string());
+    assert_eq!("12:fe:ed:05:ba:51", address.soft_ap.mac.unwrap().to_string());
+    assert_eq!(
+        "fe80::12fe:edff:fe05:ba51",
+        address.soft_ap.ipv6_link_local.unwrap().to_string()
+    );
+    assert_eq!(
+        "2a02:810d:1340:2df5::1",
+        address.soft_ap.ipv6_global.unwrap().to_string()
+    );
+    assert_eq!(InterfaceAddress::default(), address.ethernet);
+}
+
+#[test]
+fn test_ethernet_addresses() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    adapter.client.add_response(MockedCommand {
+        command: Some(b"AT+CIFSR\r\n"),
+        response: b"+CIFSR:ETHIP,\"10.0.0.182\"\r\n+CIFSR:ETHMAC,\"10:fe:ed:05:ba:52\"\r\n",
+        urc_messages: None,
+    });
+
+    let address = adapter.get_address().unwrap();
+    assert_eq!("10.0.0.182", address.ethernet.ipv4.unwrap().to_string());
+    assert_eq!("10:fe:ed:05:ba:52", address.ethernet.mac.unwrap().to_string());
+    assert!(address.ipv4.is_none());
+    assert!(address.mac.is_none());
+    assert_eq!(InterfaceAddress::default(), address.soft_ap);
+}
+
+#[test]
+fn test_soft_ap_mac_parse_error() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    adapter.client.add_response(MockedCommand {
+        command: Some(b"AT+CIFSR\r\n"),
+        response: b"+CIFSR:APMAC,\"12:fe:ed:05:ba\"\r\n",
+        urc_messages: None,
+    });
+
+    assert_eq!(AddressErrors::AddressParseError, adapter.get_address().unwrap_err());
+}
+
 #[test]
 fn test_ipv6_missing() {
     let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
@@ -46,7 +119,7 @@ fn test_ipv6_missing() {
     });
 
     let address = adapter.get_address().unwrap();
-    assert_eq!("10:fe:ed:05:ba:50", address.mac.unwrap().as_str());
+    assert_eq!("10:fe:ed:05:ba:50", address.mac.unwrap().to_string());
     assert_eq!("10.0.0.181", address.ipv4.unwrap().to_string());
     assert!(address.ipv6_global.is_none());
     assert!(address.ipv6_link_local.is_none());
@@ -66,7 +139,7 @@ fn test_ipv4_missing() {
     });
 
     let address = adapter.get_address().unwrap();
-    assert_eq!("10:fe:ed:05:ba:50", address.mac.unwrap().as_str());
+    assert_eq!("10:fe:ed:05:ba:50", address.mac.unwrap().to_string());
     assert!(address.ipv4.is_none());
     assert_eq!("fe80::e6ee:e64e:84c:a745", address.ipv6_link_local.unwrap().to_string());
     assert_eq!(
@@ -182,6 +255,22 @@ fn test_mac_to_long() {
     assert_eq!(AddressErrors::AddressParseError, adapter.get_address().unwrap_err());
 }
 
+#[test]
+fn test_mac_invalid_octet() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    adapter.client.add_response(MockedCommand {
+        command: Some(b"AT+CIFSR\r\n"),
+        response: b"+CIFSR:STAMAC,\"10:fe:ed:05:zz:50\"\r\n",
+        urc_messages: None,
+    });
+
+    assert_eq!(AddressErrors::AddressParseError, adapter.get_address().unwrap_err());
+}
+
 #[test]
 fn test_command_error() {
     let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
@@ -196,3 +285,73 @@ fn test_command_error() {
         adapter.get_address().unwrap_err()
     );
 }
+
+#[test]
+fn test_mac_address_from_str() {
+    let mac = MacAddress::from_str("10:FE:ed:05:ba:50").unwrap();
+    assert_eq!([0x10, 0xfe, 0xed, 0x05, 0xba, 0x50], mac.octets());
+    assert!(mac.is_unicast());
+
+    assert!(MacAddress::from_str("10:fe:ed:05:ba").is_err());
+    assert!(MacAddress::from_str("10:fe:ed:05:ba:50:01").is_err());
+    assert!(MacAddress::from_str("10:fe:ed:05:ba:5").is_err());
+    assert!(MacAddress::from_str("10-fe-ed-05-ba-50").is_err());
+    assert!(MacAddress::from_str("10:fe:ed:05:ba:+5").is_err());
+}
+
+#[test]
+fn test_mac_address_to_string() {
+    let mac = MacAddress::from([0x01, 0xab, 0x00, 0x05, 0xba, 0xff]);
+    assert_eq!("01:ab:00:05:ba:ff", mac.to_string());
+    assert!(!mac.is_unicast());
+}
+
+#[test]
+fn test_set_station_mac() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+CIPSTAMAC=\"02:1a:2b:00:00:01\"\r\n"), None));
+
+    adapter
+        .set_station_mac(MacAddress([0x02, 0x1a, 0x2b, 0x00, 0x00, 0x01]))
+        .unwrap();
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_set_ap_mac() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+CIPAPMAC=\"02:1a:2b:00:00:02\"\r\n"), None));
+
+    adapter.set_ap_mac(MacAddress([0x02, 0x1a, 0x2b, 0x00, 0x00, 0x02])).unwrap();
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_set_mac_command_error() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    adapter.client.add_response(MockedCommand::error(
+        Some(b"AT+CIPSTAMAC=\"01:1a:2b:00:00:01\"\r\n"),
+        None,
+    ));
+
+    let error = adapter
+        .set_station_mac(MacAddress([0x01, 0x1a, 0x2b, 0x00, 0x00, 0x01]))
+        .unwrap_err();
+    assert_eq!(CommandError::CommandFailed(AtError::Parse), error);
+}
@@ -0,0 +1,778 @@
+use crate::asynch::stack::TcpConnection;
+use crate::asynch::wifi::WifiAdapter;
+use crate::asynch::Adapter;
+use crate::queue::VecQueue;
+use crate::stack::{Error, ReceiveMode};
+use crate::tests::mock::{MockAtatClient, MockDelay, MockedCommand};
+use crate::urc::URCMessages;
+use crate::wifi::{CommandError, DhcpInterface, JoinError, JoinFailureReason, ScanOptions, StationState, WifiMode};
+use atat::Error as AtError;
+use core::net::{Ipv4Addr, SocketAddr};
+use core::str::FromStr;
+use embassy_futures::block_on;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::pubsub::PubSubChannel;
+use embedded_io_async::{Read, Write};
+use embedded_nal_async::TcpConnect;
+
+type AdapterType<'a> = Adapter<'a, MockAtatClient<'a>, MockDelay, 32, 16, 16, 5, VecQueue<16>>;
+type ConnectionType<'a, 'b> = TcpConnection<'a, 'b, MockAtatClient<'b>, MockDelay, 32, 16, 16, 5, VecQueue<16>>;
+
+#[test]
+fn test_join_correct_commands() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CWJAP=\"test_wifi\",\"secret\"\r\n"),
+        Some(&[b"WIFI CONNECTED\r\n", b"WIFI GOT IP\r\n"]),
+    ));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let state = block_on(adapter.join("test_wifi", "secret")).unwrap();
+
+    assert!(state.connected);
+    assert!(state.ip_assigned);
+    adapter.client.try_lock().unwrap().client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_join_mode_error() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::error(Some(b"AT+CWMODE=1\r\n"), None));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let error = block_on(adapter.join("test_wifi", "secret")).unwrap_err();
+
+    assert_eq!(JoinError::ModeError(AtError::Parse), error);
+}
+
+#[test]
+fn test_join_invalid_ssid_length() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let error = block_on(adapter.join(&"a".repeat(33), "secret")).unwrap_err();
+
+    assert_eq!(JoinError::InvalidSSDLength, error);
+}
+
+#[test]
+fn test_get_join_status_urc_messages() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+
+    adapter.client.try_lock().unwrap().client.add_urc_wifi_connected();
+    let state = adapter.get_join_status();
+
+    assert!(state.connected);
+    assert!(!state.ip_assigned);
+}
+
+#[test]
+fn test_get_address_correct_command() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CIFSR\r\n"),
+        b"+CIFSR:STAIP,\"10.0.0.181\"\r\n",
+        None,
+    ));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let address = block_on(adapter.get_address()).unwrap();
+
+    assert_eq!("10.0.0.181", address.ipv4.unwrap().to_string());
+}
+
+#[test]
+fn test_set_auto_connect_correct_command() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWAUTOCONN=1\r\n"), None));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    block_on(adapter.set_auto_connect(true)).unwrap();
+
+    adapter.client.try_lock().unwrap().client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_restart_ready() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+RST\r\n"), Some(&[b"ready\r\n"])));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    block_on(adapter.restart()).unwrap();
+
+    adapter.client.try_lock().unwrap().client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_restart_ready_timeout() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+RST\r\n"), None));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let error = block_on(adapter.restart()).unwrap_err();
+
+    assert_eq!(CommandError::ReadyTimeout, error);
+}
+
+#[test]
+fn test_connect_correct_commands() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"TCP\",\"127.0.0.1\",5000\r\n"),
+        Some(&[b"0,CONNECT\r\n"]),
+    ));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=1,\"TCPv6\",\"2001:0db8:0:0:0:0:0:0001\",8080\r\n"),
+        Some(&[b"1,CONNECT\r\n"]),
+    ));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+
+    let first = block_on(adapter.connect(SocketAddr::from_str("127.0.0.1:5000").unwrap())).unwrap();
+    let second = block_on(adapter.connect(SocketAddr::from_str("[2001:db8::1]:8080").unwrap())).unwrap();
+
+    assert!(first.is_connected());
+    assert!(second.is_connected());
+    adapter.client.try_lock().unwrap().client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_connect_error_releases_link_id() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::error(
+        Some(b"AT+CIPSTART=0,\"TCP\",\"127.0.0.1\",5000\r\n"),
+        None,
+    ));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"TCP\",\"127.0.0.1\",5000\r\n"),
+        Some(&[b"0,CONNECT\r\n"]),
+    ));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+
+    let result = block_on(adapter.connect(SocketAddr::from_str("127.0.0.1:5000").unwrap()));
+    assert_eq!(Error::ConnectError(AtError::Parse), result.err().unwrap());
+
+    block_on(adapter.connect(SocketAddr::from_str("127.0.0.1:5000").unwrap())).unwrap();
+    adapter.client.try_lock().unwrap().client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_connect_unconfirmed() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"TCP\",\"127.0.0.1\",5000\r\n"),
+        None,
+    ));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSTATE?\r\n"), None));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let result = block_on(adapter.connect(SocketAddr::from_str("127.0.0.1:5000").unwrap()));
+
+    assert_eq!(Error::UnconfirmedSocketState, result.err().unwrap());
+}
+
+#[test]
+fn test_connect_unconfirmed_synced_by_connection_state() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"TCP\",\"127.0.0.1\",5000\r\n"),
+        None,
+    ));
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CIPSTATE?\r\n"),
+        b"+CIPSTATE:0,\"TCP\",\"127.0.0.1\",5000,41000,0\r\n",
+        None,
+    ));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let connection = block_on(adapter.connect(SocketAddr::from_str("127.0.0.1:5000").unwrap())).unwrap();
+
+    assert!(connection.is_connected());
+}
+
+#[test]
+fn test_sync_sockets_missed_closed_message() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let mut connection = connect(&adapter, 0);
+
+    add_response(&adapter, MockedCommand::ok(Some(b"AT+CIPSTATE?\r\n"), None));
+    block_on(adapter.sync_sockets()).unwrap();
+
+    assert!(!connection.is_connected());
+    assert_eq!(Error::ClosingSocket, block_on(connection.write(b"test")).unwrap_err());
+}
+
+#[test]
+fn test_connect_already_connected_urc() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"TCP\",\"127.0.0.1\",5000\r\n"),
+        Some(&[b"ALREADY CONNECTED\r\n"]),
+    ));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let connection = block_on(adapter.connect(SocketAddr::from_str("127.0.0.1:5000").unwrap())).unwrap();
+
+    assert!(connection.is_connected());
+}
+
+#[test]
+fn test_connect_no_socket_available() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+
+    let _connections: [ConnectionType; 5] = core::array::from_fn(|link_id| connect(&adapter, link_id));
+
+    let result = block_on(adapter.connect(SocketAddr::from_str("127.0.0.1:5000").unwrap()));
+    assert_eq!(Error::NoSocketAvailable, result.err().unwrap());
+}
+
+#[test]
+fn test_connect_custom_socket_count() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"TCP\",\"127.0.0.1\",5000\r\n"),
+        Some(&[b"0,CONNECT\r\n"]),
+    ));
+
+    let adapter: Adapter<_, _, 32, 16, 16, 1> =
+        Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let _connection = block_on(adapter.connect(SocketAddr::from_str("127.0.0.1:5000").unwrap())).unwrap();
+
+    let result = block_on(adapter.connect(SocketAddr::from_str("127.0.0.1:5000").unwrap()));
+    assert_eq!(Error::NoSocketAvailable, result.err().unwrap());
+}
+
+#[test]
+fn test_write_correct_commands() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let mut connection = connect(&adapter, 0);
+
+    add_response(
+        &adapter,
+        MockedCommand::ok(Some(b"AT+CIPSEND=0,4\r\n"), Some(&[b"Recv 4 bytes\r\n"])),
+    );
+    add_response(&adapter, MockedCommand::ok(Some(b"test"), Some(&[b"SEND OK\r\n"])));
+
+    let length = block_on(connection.write(b"test")).unwrap();
+
+    assert_eq!(4, length);
+    adapter.client.try_lock().unwrap().client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_write_send_fail() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let mut connection = connect(&adapter, 0);
+
+    add_response(&adapter, MockedCommand::ok(Some(b"AT+CIPSEND=0,4\r\n"), None));
+    add_response(&adapter, MockedCommand::ok(Some(b"test"), Some(&[b"SEND FAIL\r\n"])));
+
+    let error = block_on(connection.write(b"test")).unwrap_err();
+    assert_eq!(Error::SendFailed(AtError::Error), error);
+}
+
+#[test]
+fn test_write_confirmation_timeout() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let mut connection = connect(&adapter, 0);
+
+    add_response(&adapter, MockedCommand::ok(Some(b"AT+CIPSEND=0,4\r\n"), None));
+    add_response(&adapter, MockedCommand::ok(Some(b"test"), None));
+
+    let error = block_on(connection.write(b"test")).unwrap_err();
+    assert_eq!(Error::SendFailed(AtError::Timeout), error);
+}
+
+#[test]
+fn test_write_byte_count_not_matching() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let mut connection = connect(&adapter, 0);
+
+    add_response(
+        &adapter,
+        MockedCommand::ok(Some(b"AT+CIPSEND=0,4\r\n"), Some(&[b"Recv 3 bytes\r\n"])),
+    );
+    add_response(&adapter, MockedCommand::ok(Some(b"test"), Some(&[b"SEND OK\r\n"])));
+
+    let error = block_on(connection.write(b"test")).unwrap_err();
+    assert_eq!(Error::PartialSend, error);
+}
+
+#[test]
+fn test_write_closed_remotely() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let mut connection = connect(&adapter, 0);
+
+    adapter.client.try_lock().unwrap().client.add_urc_first_socket_closed();
+
+    let error = block_on(connection.write(b"test")).unwrap_err();
+    assert_eq!(Error::ClosingSocket, error);
+}
+
+#[test]
+fn test_read_available_data() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let mut connection = connect(&adapter, 0);
+
+    adapter.client.try_lock().unwrap().client.add_urc_message(b"+IPD,0,4\r\n");
+    add_response(
+        &adapter,
+        MockedCommand::ok(Some(b"AT+CIPRECVDATA=0,16\r\n"), Some(&[b"+CIPRECVDATA:4,aaaa"])),
+    );
+
+    let mut buffer = [b' '; 16];
+    let length = block_on(connection.read(&mut buffer)).unwrap();
+
+    assert_eq!(4, length);
+    assert_eq!(b"aaaa", &buffer[..4]);
+    adapter.client.try_lock().unwrap().client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_read_active_mode() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    adapter.set_receive_mode(ReceiveMode::Active).unwrap();
+    let mut connection = connect(&adapter, 0);
+
+    adapter.client.try_lock().unwrap().client.add_urc_message(b"+IPD,0,4:aaaa");
+    adapter.client.try_lock().unwrap().client.add_urc_message(b"+IPD,0,2:bb");
+
+    let mut buffer = [b' '; 4];
+    assert_eq!(4, block_on(connection.read(&mut buffer)).unwrap());
+    assert_eq!(b"aaaa", &buffer);
+
+    assert_eq!(2, block_on(connection.read(&mut buffer)).unwrap());
+    assert_eq!(b"bb", &buffer[..2]);
+    adapter.client.try_lock().unwrap().client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_read_active_mode_buffer_overflow() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    adapter.set_receive_mode(ReceiveMode::Active).unwrap();
+    let mut connection = connect(&adapter, 0);
+
+    adapter
+        .client
+        .try_lock()
+        .unwrap()
+        .client
+        .add_urc_message(b"+IPD,0,10:aaaaaaaaaa");
+    adapter
+        .client
+        .try_lock()
+        .unwrap()
+        .client
+        .add_urc_message(b"+IPD,0,10:bbbbbbbbbb");
+
+    let mut buffer = [b' '; 32];
+    let error = block_on(connection.read(&mut buffer)).unwrap_err();
+    assert_eq!(Error::ReceiveBufferOverflow, error);
+
+    assert_eq!(16, block_on(connection.read(&mut buffer)).unwrap());
+    assert_eq!(b"aaaaaaaaaabbbbbb", &buffer[..16]);
+}
+
+#[test]
+fn test_read_active_mode_data_dropped_completely() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    adapter.set_receive_mode(ReceiveMode::Active).unwrap();
+    let mut connection = connect(&adapter, 0);
+
+    // No data of the dropped +IPD message is buffered
+    adapter.session.borrow_mut().sockets[0].rx_overflow = true;
+
+    let mut buffer = [b' '; 8];
+    let error = block_on(connection.read(&mut buffer)).unwrap_err();
+    assert_eq!(Error::ReceiveBufferOverflow, error);
+}
+
+#[test]
+fn test_read_missing_data_response() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let mut connection = connect(&adapter, 0);
+
+    adapter.client.try_lock().unwrap().client.add_urc_message(b"+IPD,0,4\r\n");
+    add_response(&adapter, MockedCommand::ok(Some(b"AT+CIPRECVDATA=0,16\r\n"), None));
+
+    let mut buffer = [b' '; 16];
+    let error = block_on(connection.read(&mut buffer)).unwrap_err();
+
+    assert_eq!(Error::ReceiveFailed(AtError::InvalidResponse), error);
+}
+
+#[test]
+fn test_read_closed_remotely() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let mut connection = connect(&adapter, 0);
+
+    adapter.client.try_lock().unwrap().client.add_urc_first_socket_closed();
+
+    let mut buffer = [b' '; 16];
+    let length = block_on(connection.read(&mut buffer)).unwrap();
+
+    assert_eq!(0, length);
+}
+
+#[test]
+fn test_close_correct_command() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let connection = connect(&adapter, 0);
+
+    add_response(
+        &adapter,
+        MockedCommand::ok(Some(b"AT+CIPCLOSE=0\r\n"), Some(&[b"0,CLOSED\r\n"])),
+    );
+
+    block_on(connection.close()).unwrap();
+
+    // Link id is reused by next connection
+    connect(&adapter, 0);
+    adapter.client.try_lock().unwrap().client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_close_unconfirmed() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let connection = connect(&adapter, 0);
+
+    add_response(&adapter, MockedCommand::ok(Some(b"AT+CIPCLOSE=0\r\n"), None));
+
+    let error = block_on(connection.close()).unwrap_err();
+    assert_eq!(Error::UnconfirmedSocketState, error);
+
+    // Link id is released anyway
+    connect(&adapter, 0);
+}
+
+#[test]
+fn test_close_already_closed_remotely() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let connection = connect(&adapter, 0);
+
+    adapter.client.try_lock().unwrap().client.add_urc_first_socket_closed();
+
+    block_on(connection.close()).unwrap();
+    adapter.client.try_lock().unwrap().client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_dropped_connection_closed_by_next_connect() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+
+    drop(connect(&adapter, 0));
+
+    add_response(
+        &adapter,
+        MockedCommand::ok(Some(b"AT+CIPCLOSE=0\r\n"), Some(&[b"0,CLOSED\r\n"])),
+    );
+    connect(&adapter, 0);
+    adapter.client.try_lock().unwrap().client.assert_all_cmds_sent();
+}
+
+/// Adds a mocked command to the client of the adapter
+fn add_response(adapter: &AdapterType, command: MockedCommand) {
+    adapter.client.try_lock().unwrap().client.add_response(command);
+}
+
+/// Opens a connection, which is expected to get the given link id
+fn connect<'a, 'b>(adapter: &'a AdapterType<'b>, link_id: usize) -> ConnectionType<'a, 'b> {
+    let session = adapter.session.borrow();
+    let multi_connections_enabled = session.multi_connections_enabled;
+    let receive_mode_configured = session.receive_mode_configured;
+    let receive_mode = session.receive_mode;
+    drop(session);
+
+    if !multi_connections_enabled {
+        add_response(adapter, MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    }
+
+    if !receive_mode_configured {
+        let command: &'static [u8] = match receive_mode {
+            ReceiveMode::Passive => b"AT+CIPRECVMODE=1\r\n",
+            ReceiveMode::Active => b"AT+CIPRECVMODE=0\r\n",
+        };
+        add_response(adapter, MockedCommand::ok(Some(command), None));
+    }
+
+    let urc_messages: &'static [&'static [u8]] = match link_id {
+        0 => &[b"0,CONNECT\r\n"],
+        1 => &[b"1,CONNECT\r\n"],
+        2 => &[b"2,CONNECT\r\n"],
+        3 => &[b"3,CONNECT\r\n"],
+        _ => &[b"4,CONNECT\r\n"],
+    };
+    add_response(adapter, MockedCommand::ok(None, Some(urc_messages)));
+
+    block_on(adapter.connect(SocketAddr::from_str("127.0.0.1:5000").unwrap())).unwrap()
+}
+
+#[test]
+fn test_scan_with_options() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWLAPOPT=0,2\r\n"), None));
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CWLAP\r\n"),
+        b"+CWLAP:(\"test_wifi\")\r\n",
+        None,
+    ));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    block_on(adapter.set_scan_options(ScanOptions {
+        fields: ScanOptions::FIELD_SSID,
+        ..Default::default()
+    }))
+    .unwrap();
+    let access_points = block_on(adapter.scan::<4>()).unwrap();
+
+    assert_eq!("test_wifi", access_points[0].ssid.as_ref().unwrap().as_str());
+    assert_eq!(None, access_points[0].auth_mode);
+    adapter.client.try_lock().unwrap().client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_join_soft_ap_mode_active() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(Some(b"AT+CWMODE?\r\n"), b"+CWMODE:2\r\n", None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CWMODE=3\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CWJAP=\"test_wifi\",\"secret\"\r\n"), None));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    assert_eq!(WifiMode::SoftAp, block_on(adapter.get_mode()).unwrap());
+    block_on(adapter.join("test_wifi", "secret")).unwrap();
+
+    adapter.client.try_lock().unwrap().client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_set_mode_correct_command() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWMODE=1,1\r\n"), None));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    block_on(adapter.set_mode(WifiMode::Station, Some(true))).unwrap();
+
+    adapter.client.try_lock().unwrap().client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_join_failure_reason() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWMODE=1\r\n"), None));
+    client.add_response(MockedCommand::error(
+        Some(b"AT+CWJAP=\"test_wifi\",\"secret\"\r\n"),
+        Some(&[b"+CWJAP:3\r\n"]),
+    ));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+    let error = block_on(adapter.join("test_wifi", "secret")).unwrap_err();
+
+    assert_eq!(JoinError::JoinFailed(JoinFailureReason::AccessPointNotFound), error);
+}
+
+#[test]
+fn test_leave_connection_closing() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+
+    let mut connection = connect(&adapter, 0);
+    add_response(
+        &adapter,
+        MockedCommand::ok(Some(b"AT+CWQAP\r\n"), Some(&[b"WIFI DISCONNECT\r\n"])),
+    );
+    block_on(adapter.leave()).unwrap();
+
+    assert!(!connection.is_connected());
+    assert!(!adapter.get_join_status().connected);
+
+    let mut buffer = [0x0; 16];
+    assert_eq!(0, block_on(connection.read(&mut buffer)).unwrap());
+}
+
+#[test]
+fn test_leave_disconnect_timeout() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+
+    adapter.client.try_lock().unwrap().client.add_urc_wifi_connected();
+    add_response(&adapter, MockedCommand::ok(Some(b"AT+CWQAP\r\n"), None));
+
+    let error = block_on(adapter.leave()).unwrap_err();
+
+    assert_eq!(CommandError::DisconnectTimeout, error);
+}
+
+#[test]
+fn test_get_access_point_info() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CWJAP?\r\n"),
+        b"+CWJAP:\"test_wifi\",\"ca:d7:19:d8:a6:44\",6,-52,0,1,3,0,1\r\n",
+        None,
+    ));
+    client.add_response(MockedCommand::new(Some(b"AT+CWJAP?\r\n"), b"No AP\r\n", None));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+
+    let info = block_on(adapter.get_access_point_info()).unwrap().unwrap();
+    assert_eq!("test_wifi", info.ssid.as_str());
+    assert_eq!("ca:d7:19:d8:a6:44", info.bssid.to_string());
+    assert_eq!(-52, info.rssi);
+
+    assert_eq!(None, block_on(adapter.get_access_point_info()).unwrap());
+}
+
+#[test]
+fn test_get_station_state() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CWSTATE?\r\n"),
+        b"+CWSTATE:2,\"test_wifi\"\r\n",
+        None,
+    ));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+
+    assert_eq!(StationState::IpAssigned, block_on(adapter.get_station_state()).unwrap());
+    assert!(adapter.get_join_status().connected);
+    assert!(adapter.get_join_status().ip_assigned);
+}
+
+#[test]
+fn test_static_ip_config() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWDHCP=0,1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTA=\"10.0.0.100\",\"10.0.0.1\",\"255.255.255.0\"\r\n"),
+        None,
+    ));
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CIPSTA?\r\n"),
+        b"+CIPSTA:ip:\"10.0.0.100\"\r\n+CIPSTA:gateway:\"10.0.0.1\"\r\n+CIPSTA:netmask:\"255.255.255.0\"\r\n",
+        None,
+    ));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+
+    block_on(adapter.set_dhcp(DhcpInterface::Station, false)).unwrap();
+    block_on(adapter.set_static_ip(
+        Ipv4Addr::new(10, 0, 0, 100),
+        Ipv4Addr::new(10, 0, 0, 1),
+        Ipv4Addr::new(255, 255, 255, 0),
+    ))
+    .unwrap();
+
+    let address = block_on(adapter.get_ip_config()).unwrap();
+    assert_eq!(Some(Ipv4Addr::new(10, 0, 0, 100)), address.ipv4);
+    assert_eq!(Some(Ipv4Addr::new(10, 0, 0, 1)), address.gateway);
+    assert_eq!(Some(Ipv4Addr::new(255, 255, 255, 0)), address.netmask);
+}
+
+#[test]
+fn test_hostname() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWHOSTNAME=\"sensor-01\"\r\n"), None));
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CWHOSTNAME?\r\n"),
+        b"+CWHOSTNAME:sensor-01\r\n",
+        None,
+    ));
+
+    let adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), MockDelay::default());
+
+    block_on(adapter.set_hostname("sensor-01")).unwrap();
+    assert_eq!("sensor-01", block_on(adapter.get_hostname()).unwrap().as_str());
+}
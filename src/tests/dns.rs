@@ -0,0 +1,255 @@
+use crate::dns::DnsError;
+use crate::tests::mock::{MockAtatClient, MockTimer, MockedCommand};
+use crate::urc::URCMessages;
+use crate::wifi::Adapter;
+use alloc::string::ToString;
+use atat::Error as AtError;
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use core::str::FromStr;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::pubsub::PubSubChannel;
+use embedded_nal::{AddrType, Dns};
+
+type AdapterType<'a> = Adapter<'a, MockAtatClient<'a>, MockTimer, 1_000_000, 32, 16, 16>;
+
+#[test]
+fn test_get_host_by_name_ipv4() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CIPDOMAIN=\"example.org\",2\r\n"),
+        b"+CIPDOMAIN:\"93.184.216.34\"\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let address = adapter.get_host_by_name("example.org", AddrType::IPv4).unwrap();
+
+    assert_eq!(IpAddr::V4(Ipv4Addr::new(93, 184, 216, 34)), address);
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_get_host_by_name_ipv6() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CIPDOMAIN=\"example.org\",3\r\n"),
+        b"+CIPDOMAIN:\"2606:2800:220:1:248:1893:25c8:1946\"\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let address = adapter.get_host_by_name("example.org", AddrType::IPv6).unwrap();
+
+    assert_eq!(IpAddr::from_str("2606:2800:220:1:248:1893:25c8:1946").unwrap(), address);
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_get_host_by_name_either_unquoted_response() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CIPDOMAIN=\"example.org\"\r\n"),
+        b"+CIPDOMAIN:93.184.216.34\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let address = adapter.get_host_by_name("example.org", AddrType::Either).unwrap();
+
+    assert_eq!("93.184.216.34", address.to_string());
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_get_host_by_name_command_error() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::error(Some(b"AT+CIPDOMAIN=\"unknown.org\"\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let error = adapter.get_host_by_name("unknown.org", AddrType::Either).unwrap_err();
+
+    assert_eq!(nb::Error::Other(DnsError::ResolveFailed(AtError::Parse)), error);
+}
+
+#[test]
+fn test_get_host_by_name_invalid_address() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(None, b"+CIPDOMAIN:\"93.184.216\"\r\n", None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let error = adapter.get_host_by_name("example.org", AddrType::Either).unwrap_err();
+
+    assert_eq!(nb::Error::Other(DnsError::InvalidAddress), error);
+}
+
+#[test]
+fn test_get_host_by_name_address_type_mismatch() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(None, b"+CIPDOMAIN:\"93.184.216.34\"\r\n", None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let error = adapter.get_host_by_name("example.org", AddrType::IPv6).unwrap_err();
+
+    assert_eq!(nb::Error::Other(DnsError::AddressTypeMismatch), error);
+}
+
+#[test]
+fn test_get_host_by_name_hostname_too_long() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let error = adapter.get_host_by_name(&"a".repeat(129), AddrType::Either).unwrap_err();
+
+    assert_eq!(nb::Error::Other(DnsError::HostnameTooLong), error);
+}
+
+#[test]
+fn test_get_host_by_address_unsupported() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut buffer = [0x0; 64];
+    let error = adapter
+        .get_host_by_address(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), &mut buffer)
+        .unwrap_err();
+
+    assert_eq!(nb::Error::Other(DnsError::Unsupported), error);
+}
+
+#[test]
+fn test_set_dns_servers() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPDNS=1,\"8.8.8.8\"\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPDNS=1,\"8.8.8.8\",\"2001:4860:4860:0:0:0:0:8888\",\"1.1.1.1\"\r\n"),
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.set_dns_servers(&[IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8))]).unwrap();
+    adapter
+        .set_dns_servers(&[
+            IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8)),
+            IpAddr::V6(Ipv6Addr::from_str("2001:4860:4860::8888").unwrap()),
+            IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+        ])
+        .unwrap();
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_set_dns_servers_invalid_count() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    assert_eq!(DnsError::InvalidServerCount, adapter.set_dns_servers(&[]).unwrap_err());
+
+    let server = IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8));
+    assert_eq!(
+        DnsError::InvalidServerCount,
+        adapter.set_dns_servers(&[server; 4]).unwrap_err()
+    );
+}
+
+#[test]
+fn test_set_dns_servers_command_error() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::error(Some(b"AT+CIPDNS=1,\"8.8.8.8\"\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let error = adapter.set_dns_servers(&[IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8))]).unwrap_err();
+
+    assert_eq!(DnsError::ConfigurationError(AtError::Parse), error);
+}
+
+#[test]
+fn test_use_dhcp_dns_servers() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPDNS=0\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.use_dhcp_dns_servers().unwrap();
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_get_dns_config() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CIPDNS?\r\n"),
+        b"+CIPDNS:1,\"8.8.8.8\",\"2001:4860:4860::8888\"\r\n",
+        None,
+    ));
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CIPDNS?\r\n"),
+        b"+CIPDNS:0,\"208.67.222.222\"\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let config = adapter.get_dns_config().unwrap();
+    assert!(config.manual);
+    assert_eq!(2, config.servers.len());
+    assert_eq!(IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8)), config.servers[0]);
+    assert_eq!("2001:4860:4860::8888", config.servers[1].to_string());
+
+    let config = adapter.get_dns_config().unwrap();
+    assert!(!config.manual);
+    assert_eq!(IpAddr::V4(Ipv4Addr::new(208, 67, 222, 222)), config.servers[0]);
+}
+
+#[test]
+fn test_get_dns_config_invalid_response() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CIPDNS?\r\n"),
+        b"+CIPDNS:1,\"8.8.8\"\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(
+        DnsError::ConfigurationError(AtError::Parse),
+        adapter.get_dns_config().unwrap_err()
+    );
+}
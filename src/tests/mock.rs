@@ -78,20 +78,26 @@ impl AtatClient for MockAtatClient<'_> {
             );
         }
 
-        let response = cmd.parse(Ok(behaviour.response)).map_err(|_| Error::Parse)?;
-
-        if let Some(messages) = behaviour.urc_messages {
-            for message in messages {
-                if let Some(message) = URCMessages::parse(message) {
-                    self.urc_publisher.try_publish(message).unwrap()
-                };
-            }
+        // Error responses are not parsed, as responses with string fields would accept them
+        if behaviour.response == b"ERROR\r\n" {
+            self.publish_urc_messages(&behaviour);
+            return Err(Error::Parse);
         }
 
+        let response = cmd.parse(Ok(behaviour.response)).map_err(|_| Error::Parse)?;
+        self.publish_urc_messages(&behaviour);
+
         Ok(response)
     }
 }
 
+#[cfg(feature = "async")]
+impl atat::asynch::AtatClient for MockAtatClient<'_> {
+    async fn send<A: AtatCmd>(&mut self, cmd: &A) -> Result<A::Response, Error> {
+        AtatClient::send(self, cmd)
+    }
+}
+
 impl<'a> MockAtatClient<'a> {
     pub fn new(channel: &'a PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1>) -> Self {
         Self {
@@ -105,6 +111,17 @@ impl<'a> MockAtatClient<'a> {
         self.responses.push_back(response);
     }
 
+    /// Publishes the URC messages of the given command
+    fn publish_urc_messages(&mut self, behaviour: &MockedCommand) {
+        if let Some(messages) = behaviour.urc_messages {
+            for message in messages {
+                if let Some(message) = URCMessages::parse(message) {
+                    self.urc_publisher.try_publish(message).unwrap()
+                };
+            }
+        }
+    }
+
     /// Publishes a URC message
     pub fn add_urc_message(&mut self, message: &'static [u8]) {
         let message = URCMessages::parse(message).unwrap();
@@ -168,3 +185,13 @@ impl MockTimer {
         TimerDurationU32::millis(duration)
     }
 }
+
+/// Delay mock, which completes instantly. So timeouts are reached if the awaited URC message is not already queued.
+#[cfg(feature = "async")]
+#[derive(Default)]
+pub struct MockDelay {}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::delay::DelayNs for MockDelay {
+    async fn delay_ns(&mut self, _ns: u32) {}
+}
@@ -1,6 +1,16 @@
 mod address;
+#[cfg(feature = "async")]
+mod asynch;
 mod buffer;
+mod dns;
 mod mock;
+mod ping;
+#[cfg(feature = "bbqueue")]
+mod queue;
+mod sntp;
+mod softap;
 mod stack;
+mod tls;
+mod udp;
 mod urc;
 mod wifi;
@@ -0,0 +1,126 @@
+use crate::ping::PingError;
+use crate::tests::mock::{MockAtatClient, MockTimer, MockedCommand};
+use crate::urc::URCMessages;
+use crate::wifi::Adapter;
+use atat::Error as AtError;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::pubsub::PubSubChannel;
+
+type AdapterType<'a> = Adapter<'a, MockAtatClient<'a>, MockTimer, 1_000_000, 32, 16, 16>;
+
+#[test]
+fn test_ping_hostname() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+PING=\"example.org\"\r\n"),
+        b"+PING:37\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let duration = adapter.ping("example.org").unwrap();
+
+    assert_eq!(37, duration.to_millis());
+    assert_eq!(37_000, duration.ticks());
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_ping_ip_address() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+PING=\"10.0.0.1\"\r\n"),
+        b"+PING:2\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(2, adapter.ping("10.0.0.1").unwrap().to_millis());
+}
+
+#[test]
+fn test_ping_timeout() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::error(
+        Some(b"AT+PING=\"10.0.0.1\"\r\n"),
+        Some(&[b"+PING:TIMEOUT\r\n"]),
+    ));
+    client.add_response(MockedCommand::error(Some(b"AT+PING=\"10.0.0.1\"\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(PingError::Timeout, adapter.ping("10.0.0.1").unwrap_err());
+    assert!(!adapter.session.ping_timeout);
+
+    // Timeout is not reported for subsequent failures
+    assert_eq!(
+        PingError::PingFailed(AtError::Parse),
+        adapter.ping("10.0.0.1").unwrap_err()
+    );
+}
+
+#[test]
+fn test_ping_previous_timeout_discarded() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_urc_message(b"+PING:TIMEOUT\r\n");
+    client.add_response(MockedCommand::error(Some(b"AT+PING=\"10.0.0.1\"\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(
+        PingError::PingFailed(AtError::Parse),
+        adapter.ping("10.0.0.1").unwrap_err()
+    );
+}
+
+#[test]
+fn test_ping_invalid_response() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+PING=\"10.0.0.1\"\r\n"),
+        b"+PING:-1\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(PingError::InvalidResponse, adapter.ping("10.0.0.1").unwrap_err());
+}
+
+#[test]
+fn test_ping_command_error() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::error(Some(b"AT+PING=\"unknown.org\"\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(
+        PingError::PingFailed(AtError::Parse),
+        adapter.ping("unknown.org").unwrap_err()
+    );
+}
+
+#[test]
+fn test_ping_host_too_long() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let host = "a".repeat(129);
+    assert_eq!(PingError::HostTooLong, adapter.ping(&host).unwrap_err());
+}
@@ -0,0 +1,172 @@
+use crate::queue::BbQueue;
+use crate::stack::{Error, ReceiveMode, Socket};
+use crate::tests::mock::{MockAtatClient, MockTimer, MockedCommand};
+use crate::urc::URCMessages;
+use crate::wifi::{Adapter, WifiAdapter};
+use alloc::boxed::Box;
+use bbqueue::BBBuffer;
+use core::net::SocketAddr;
+use core::str::FromStr;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::pubsub::PubSubChannel;
+use embedded_nal::TcpClientStack;
+
+type AdapterType<'a> = Adapter<'a, MockAtatClient<'a>, MockTimer, 1_000_000, 32, 16, 16, 2, BbQueue<16>>;
+
+#[test]
+fn test_set_receive_mode_without_receive_queues() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    assert_eq!(
+        Error::ReceiveBufferDisabled,
+        adapter.set_receive_mode(ReceiveMode::Active).unwrap_err()
+    );
+}
+
+#[test]
+fn test_receive_active_mode() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.set_receive_queues(storages()).unwrap();
+    let mut socket = connect_socket_active_mode(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,12:aaaaaaaaaaaa");
+    let mut buffer = [b' '; 16];
+    assert_eq!(12, adapter.receive(&mut socket, &mut buffer).unwrap());
+    assert_eq!(b"aaaaaaaaaaaa", &buffer[..12]);
+
+    // Data wraps around the end of the storage
+    adapter.client.add_urc_message(b"+IPD,0,8:bbbbcccc");
+    assert_eq!(8, adapter.available(&socket));
+    assert_eq!(8, adapter.receive(&mut socket, &mut buffer).unwrap());
+    assert_eq!(b"bbbbcccc", &buffer[..8]);
+
+    assert_eq!(
+        nb::Error::WouldBlock,
+        adapter.receive(&mut socket, &mut buffer).unwrap_err()
+    );
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_receive_active_mode_buffer_overflow() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.set_receive_queues(storages()).unwrap();
+    let mut socket = connect_socket_active_mode(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,12:aaaaaaaaaaaa");
+    adapter.client.add_urc_message(b"+IPD,0,8:bbbbbbbb");
+
+    let mut buffer = [b' '; 32];
+    assert_eq!(
+        nb::Error::Other(Error::ReceiveBufferOverflow),
+        adapter.receive(&mut socket, &mut buffer).unwrap_err()
+    );
+
+    // Data buffered before the overflow is kept
+    assert_eq!(16, adapter.receive(&mut socket, &mut buffer).unwrap());
+    assert_eq!(b"aaaaaaaaaaaabbbb", &buffer[..16]);
+}
+
+#[test]
+fn test_set_receive_queues_storage_in_use() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let [first, second] = storages();
+    let split = second.try_split().unwrap();
+
+    assert_eq!(
+        Error::ReceiveQueueInUse,
+        adapter.set_receive_queues([first, second]).unwrap_err()
+    );
+    assert_eq!(
+        Error::ReceiveBufferDisabled,
+        adapter.set_receive_mode(ReceiveMode::Active).unwrap_err()
+    );
+
+    // First storage was released again, so both storages may be assigned once the second one is released
+    assert!(second.try_release(split.0, split.1).is_ok());
+    adapter.set_receive_queues([first, second]).unwrap();
+    adapter.set_receive_mode(ReceiveMode::Active).unwrap();
+}
+
+#[test]
+fn test_set_receive_queues_releases_previous_storages() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let previous = storages();
+    adapter.set_receive_queues(previous).unwrap();
+    adapter.set_receive_queues(storages()).unwrap();
+
+    assert!(previous[0].try_split().is_ok());
+    assert!(previous[1].try_split().is_ok());
+}
+
+#[test]
+fn test_receive_active_mode_after_restart() {
+    let mut timer = MockTimer::new();
+    timer.expect_start().returning(|_| Ok(()));
+    timer.expect_wait().returning(|| nb::Result::Err(nb::Error::WouldBlock));
+
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.set_receive_queues(storages()).unwrap();
+    connect_socket_active_mode(&mut adapter);
+    adapter.client.add_urc_message(b"+IPD,0,4:aaaa");
+
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+RST\r\n"), Some(&[b"ready\r\n"])));
+    adapter.restart().unwrap();
+
+    // Receive queues are kept, but data buffered before the restart is dropped
+    let mut socket = connect_socket_active_mode(&mut adapter);
+    assert_eq!(0, adapter.available(&socket));
+
+    adapter.client.add_urc_message(b"+IPD,0,4:bbbb");
+    let mut buffer = [b' '; 16];
+    assert_eq!(4, adapter.receive(&mut socket, &mut buffer).unwrap());
+    assert_eq!(b"bbbb", &buffer[..4]);
+    adapter.client.assert_all_cmds_sent();
+}
+
+/// Returns a storage per link id. Storages are leaked, as they need to be static.
+fn storages<const N: usize, const M: usize>() -> [&'static BBBuffer<N>; M] {
+    core::array::from_fn(|_| &*Box::leak(Box::new(BBBuffer::new())))
+}
+
+/// Helper for opening & connecting a socket in active receiving mode
+fn connect_socket_active_mode(adapter: &mut AdapterType) -> Socket {
+    adapter.set_receive_mode(ReceiveMode::Active).unwrap();
+
+    adapter.client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=0\r\n"), None));
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"TCP\",\"127.0.0.1\",5000\r\n"),
+        Some(&[b"0,CONNECT\r\n"]),
+    ));
+
+    let mut socket = adapter.socket().unwrap();
+    adapter
+        .connect(&mut socket, SocketAddr::from_str("127.0.0.1:5000").unwrap())
+        .unwrap();
+
+    socket
+}
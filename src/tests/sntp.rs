@@ -0,0 +1,205 @@
+use crate::sntp::{DateTime, SntpConfig, SntpError};
+use crate::tests::mock::{MockAtatClient, MockTimer, MockedCommand};
+use crate::urc::URCMessages;
+use crate::wifi::Adapter;
+use atat::Error as AtError;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::pubsub::PubSubChannel;
+
+type AdapterType<'a> = Adapter<'a, MockAtatClient<'a>, MockTimer, 1_000_000, 32, 16, 16>;
+
+#[test]
+fn test_configure_sntp_correct_commands() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSNTPCFG=1,0\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSNTPCFG=1,-5,\"pool.ntp.org\",\"time.google.com\",\"ntp1.aliyun.com\"\r\n"),
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.configure_sntp(&SntpConfig::default()).unwrap();
+
+    let config = SntpConfig {
+        utc_offset_minutes: -300,
+        servers: &["pool.ntp.org", "time.google.com", "ntp1.aliyun.com"],
+    };
+    adapter.configure_sntp(&config).unwrap();
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_configure_sntp_utc_offset_minutes() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSNTPCFG=1,530\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSNTPCFG=1,-930\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSNTPCFG=1,14\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    for utc_offset_minutes in [330, -570, 840] {
+        let config = SntpConfig {
+            utc_offset_minutes,
+            servers: &[],
+        };
+        adapter.configure_sntp(&config).unwrap();
+    }
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_configure_sntp_invalid_config() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let config = SntpConfig {
+        utc_offset_minutes: 0,
+        servers: &["a", "b", "c", "d"],
+    };
+    assert_eq!(SntpError::TooManyServers, adapter.configure_sntp(&config).unwrap_err());
+
+    let config = SntpConfig {
+        utc_offset_minutes: 0,
+        servers: &["very-long-domain-name-exceeding-the-limit-of-sixty-four-chars.org"],
+    };
+    assert_eq!(SntpError::ServerTooLong, adapter.configure_sntp(&config).unwrap_err());
+
+    let config = SntpConfig {
+        utc_offset_minutes: 900,
+        servers: &[],
+    };
+    assert_eq!(
+        SntpError::InvalidUtcOffset,
+        adapter.configure_sntp(&config).unwrap_err()
+    );
+}
+
+#[test]
+fn test_configure_sntp_command_error() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::error(Some(b"AT+CIPSNTPCFG=1,0\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(
+        SntpError::ConfigurationError(AtError::Parse),
+        adapter.configure_sntp(&SntpConfig::default()).unwrap_err()
+    );
+}
+
+#[test]
+fn test_disable_sntp() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSNTPCFG=0\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.disable_sntp().unwrap();
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_set_sntp_interval() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSNTPINTV=600\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.set_sntp_interval(600).unwrap();
+    adapter.client.assert_all_cmds_sent();
+
+    assert_eq!(SntpError::InvalidInterval, adapter.set_sntp_interval(14).unwrap_err());
+    assert_eq!(
+        SntpError::InvalidInterval,
+        adapter.set_sntp_interval(4_294_968).unwrap_err()
+    );
+}
+
+#[test]
+fn test_get_time() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CIPSNTPTIME?\r\n"),
+        b"+CIPSNTPTIME:Thu Aug  4 14:48:05 2021\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(
+        DateTime {
+            year: 2021,
+            month: 8,
+            day: 4,
+            hour: 14,
+            minute: 48,
+            second: 5,
+        },
+        adapter.get_time().unwrap()
+    );
+}
+
+#[test]
+fn test_get_time_invalid_response() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CIPSNTPTIME?\r\n"),
+        b"+CIPSNTPTIME:Thu Abc 04 14:48:05 2021\r\n",
+        None,
+    ));
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CIPSNTPTIME?\r\n"),
+        b"+CIPSNTPTIME:Thu Aug 04 24:48:05 2021\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(SntpError::TimeParseError, adapter.get_time().unwrap_err());
+    assert_eq!(SntpError::TimeParseError, adapter.get_time().unwrap_err());
+}
+
+#[test]
+fn test_get_time_command_error() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::error(Some(b"AT+CIPSNTPTIME?\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(
+        SntpError::TimeQueryFailed(AtError::Parse),
+        adapter.get_time().unwrap_err()
+    );
+}
+
+#[test]
+fn test_is_time_synchronized() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    assert!(!adapter.is_time_synchronized());
+
+    adapter.client.add_urc_message(b"+TIME_UPDATED\r\n");
+    assert!(adapter.is_time_synchronized());
+}
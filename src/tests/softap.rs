@@ -0,0 +1,331 @@
+use crate::softap::{SoftApConfig, SoftApError, Station, StationEvent};
+use crate::tests::mock::{MockAtatClient, MockTimer, MockedCommand};
+use crate::urc::URCMessages;
+use crate::wifi::{Adapter, AuthMode, MacAddress};
+use atat::Error as AtError;
+use core::net::Ipv4Addr;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::pubsub::PubSubChannel;
+
+type AdapterType<'a> = Adapter<'a, MockAtatClient<'a>, MockTimer, 1_000_000, 32, 16, 16>;
+
+#[test]
+fn test_start_access_point_correct_commands() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWMODE=2\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CWSAP=\"test_ap\",\"secret123\",6,3\r\n"),
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter
+        .start_access_point(&SoftApConfig {
+            ssid: "test_ap",
+            password: "secret123",
+            channel: 6,
+            encryption: AuthMode::Wpa2Psk,
+            ..Default::default()
+        })
+        .unwrap();
+
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_start_access_point_station_enabled_hidden() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWMODE=3\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CWSAP=\"test_ap\",\"\",1,0,10,1\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter
+        .start_access_point(&SoftApConfig {
+            ssid: "test_ap",
+            hidden: true,
+            station_enabled: true,
+            ..Default::default()
+        })
+        .unwrap();
+
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_start_access_point_max_connections() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWMODE=2\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CWSAP=\"test_ap\",\"secret123\",1,4,2\r\n"),
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter
+        .start_access_point(&SoftApConfig {
+            ssid: "test_ap",
+            password: "secret123",
+            encryption: AuthMode::WpaWpa2Psk,
+            max_connections: Some(2),
+            ..Default::default()
+        })
+        .unwrap();
+
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_start_access_point_mode_error() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::error(Some(b"AT+CWMODE=2\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let error = adapter
+        .start_access_point(&SoftApConfig {
+            ssid: "test_ap",
+            ..Default::default()
+        })
+        .unwrap_err();
+
+    assert_eq!(SoftApError::ModeError(AtError::Parse), error);
+}
+
+#[test]
+fn test_start_access_point_configuration_error() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWMODE=2\r\n"), None));
+    client.add_response(MockedCommand::error(Some(b"AT+CWSAP=\"test_ap\",\"\",1,0\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let error = adapter
+        .start_access_point(&SoftApConfig {
+            ssid: "test_ap",
+            ..Default::default()
+        })
+        .unwrap_err();
+
+    assert_eq!(SoftApError::ConfigurationError(AtError::Parse), error);
+}
+
+#[test]
+fn test_start_access_point_invalid_config() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let long_ssid = "a".repeat(33);
+    let config = SoftApConfig {
+        ssid: &long_ssid,
+        ..Default::default()
+    };
+    assert_eq!(
+        SoftApError::InvalidSsidLength,
+        adapter.start_access_point(&config).unwrap_err()
+    );
+
+    let config = SoftApConfig {
+        ssid: "test_ap",
+        password: "short",
+        encryption: AuthMode::Wpa2Psk,
+        ..Default::default()
+    };
+    assert_eq!(
+        SoftApError::InvalidPasswordLength,
+        adapter.start_access_point(&config).unwrap_err()
+    );
+
+    let config = SoftApConfig {
+        ssid: "test_ap",
+        password: "secret123",
+        encryption: AuthMode::Wep,
+        ..Default::default()
+    };
+    assert_eq!(
+        SoftApError::UnsupportedEncryption,
+        adapter.start_access_point(&config).unwrap_err()
+    );
+}
+
+#[test]
+fn test_get_stations() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CWLIF\r\n"),
+        b"+CWLIF:192.168.4.2,18:31:bf:4b:8a:f2\r\n+CWLIF:\"192.168.4.3\",\"18:31:bf:4b:8a:f3\"\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let stations = adapter.get_stations::<4>().unwrap();
+
+    assert_eq!(2, stations.len());
+    assert_eq!(
+        Station {
+            ip: Ipv4Addr::new(192, 168, 4, 2),
+            mac: MacAddress([0x18, 0x31, 0xbf, 0x4b, 0x8a, 0xf2]),
+        },
+        stations[0]
+    );
+    assert_eq!(Ipv4Addr::new(192, 168, 4, 3), stations[1].ip);
+    assert_eq!("18:31:bf:4b:8a:f3", stations[1].mac.to_string());
+}
+
+#[test]
+fn test_get_stations_more_than_capacity() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CWLIF\r\n"),
+        b"+CWLIF:192.168.4.2,18:31:bf:4b:8a:f2\r\n+CWLIF:192.168.4.3,18:31:bf:4b:8a:f3\r\n+CWLIF:192.168.4.4,18:31:bf:4b:8a:f4\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let stations = adapter.get_stations::<2>().unwrap();
+
+    assert_eq!(2, stations.len());
+    assert_eq!(Ipv4Addr::new(192, 168, 4, 2), stations[0].ip);
+    assert_eq!(Ipv4Addr::new(192, 168, 4, 3), stations[1].ip);
+}
+
+#[test]
+fn test_get_stations_invalid_address() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::new(
+        None,
+        b"+CWLIF:192.168.4,18:31:bf:4b:8a:f2\r\n",
+        None,
+    ));
+    client.add_response(MockedCommand::new(None, b"+CWLIF:192.168.4.2,18:31:bf:4b:8a\r\n", None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(SoftApError::AddressParseError, adapter.get_stations::<4>().unwrap_err());
+    assert_eq!(SoftApError::AddressParseError, adapter.get_stations::<4>().unwrap_err());
+}
+
+#[test]
+fn test_get_stations_command_error() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::error(Some(b"AT+CWLIF\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let error = adapter.get_stations::<4>().unwrap_err();
+
+    assert_eq!(SoftApError::ListStationsError(AtError::Parse), error);
+}
+
+#[test]
+fn test_disconnect_station_correct_commands() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWQIF=\"18:31:bf:4b:8a:f2\"\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CWQIF\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter
+        .disconnect_station(Some(&MacAddress([0x18, 0x31, 0xbf, 0x4b, 0x8a, 0xf2])))
+        .unwrap();
+    adapter.disconnect_station(None).unwrap();
+
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_disconnect_station_errors() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::error(Some(b"AT+CWQIF\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(
+        SoftApError::DisconnectStationError(AtError::Parse),
+        adapter.disconnect_station(None).unwrap_err()
+    );
+}
+
+#[test]
+fn test_station_events() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CWMODE=2\r\n"),
+        Some(&[
+            b"+STA_CONNECTED:\"18:31:bf:4b:8a:f2\"\r\n",
+            b"+DIST_STA_IP:\"18:31:bf:4b:8a:f2\",\"192.168.4.2\"\r\n",
+            b"+STA_DISCONNECTED:\"18:31:bf:4b:8a:f2\"\r\n",
+        ]),
+    ));
+    client.add_response(MockedCommand::ok(Some(b"AT+CWSAP=\"test_ap\",\"\",1,0\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(None, adapter.next_station_event());
+
+    adapter
+        .start_access_point(&SoftApConfig {
+            ssid: "test_ap",
+            ..Default::default()
+        })
+        .unwrap();
+
+    let mac = MacAddress([0x18, 0x31, 0xbf, 0x4b, 0x8a, 0xf2]);
+    assert_eq!(Some(StationEvent::Connected(mac)), adapter.next_station_event());
+    assert_eq!(
+        Some(StationEvent::IpAssigned(mac, Ipv4Addr::new(192, 168, 4, 2))),
+        adapter.next_station_event()
+    );
+    assert_eq!(Some(StationEvent::Disconnected(mac)), adapter.next_station_event());
+    assert_eq!(None, adapter.next_station_event());
+}
+
+#[test]
+fn test_station_events_oldest_dropped() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    for i in 0..10 {
+        let mac = MacAddress([0x18, 0x31, 0xbf, 0x4b, 0x8a, i]);
+        adapter.session.handle_urc(URCMessages::StationConnected(mac));
+    }
+
+    assert_eq!(
+        Some(StationEvent::Connected(MacAddress([
+            0x18, 0x31, 0xbf, 0x4b, 0x8a, 0x02
+        ]))),
+        adapter.next_station_event()
+    );
+}
@@ -1,16 +1,18 @@
-use crate::stack::{Error, Socket};
+use crate::queue::VecQueue;
+use crate::stack::{Error, LinkType, ReceiveMode, Socket};
 use crate::tests::mock::{MockAtatClient, MockTimer, MockedCommand};
 use crate::urc::URCMessages;
 use crate::wifi::{Adapter, WifiAdapter};
+use alloc::string::ToString;
 use alloc::vec;
 use atat::Error as AtError;
 use core::net::SocketAddr;
 use core::str::FromStr;
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_sync::pubsub::PubSubChannel;
-use embedded_nal::TcpClientStack;
+use embedded_nal::{TcpClientStack, TcpFullStack};
 
-type AdapterType<'a> = Adapter<'a, MockAtatClient<'a>, MockTimer, 1_000_000, 32, 16, 16>;
+type AdapterType<'a> = Adapter<'a, MockAtatClient<'a>, MockTimer, 1_000_000, 32, 16, 16, 5, VecQueue<16>>;
 
 #[test]
 fn test_socket_multi_conn_error() {
@@ -69,6 +71,67 @@ fn test_socket_not_available() {
     assert_eq!(Error::NoSocketAvailable, result);
 }
 
+#[test]
+fn test_socket_custom_socket_count() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+
+    let mut adapter: Adapter<_, _, 1_000_000, 32, 16, 16, 2> =
+        Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(0, adapter.socket().unwrap().link_id);
+    assert_eq!(1, adapter.socket().unwrap().link_id);
+    assert_eq!(Error::NoSocketAvailable, adapter.socket().unwrap_err());
+}
+
+#[test]
+fn test_connect_multi_digit_link_id() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=11,\"TCP\",\"127.0.0.1\",5000\r\n"),
+        Some(&[b"11,CONNECT\r\n"]),
+    ));
+
+    let mut adapter: Adapter<_, _, 1_000_000, 32, 16, 16, 12> =
+        Adapter::new(client, channel.subscriber().unwrap(), timer);
+    for _ in 0..11 {
+        adapter.socket().unwrap();
+    }
+
+    let mut socket = adapter.socket().unwrap();
+    assert_eq!(11, socket.link_id);
+
+    adapter
+        .connect(&mut socket, SocketAddr::from_str("127.0.0.1:5000").unwrap())
+        .unwrap();
+    assert!(adapter.is_connected(&socket).unwrap());
+
+    adapter.client.add_urc_message(b"11,CLOSED\r\n");
+    assert!(!adapter.is_connected(&socket).unwrap());
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_urc_link_id_out_of_range_ignored() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let socket = connect_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"5,CONNECT\r\n");
+    adapter.client.add_urc_message(b"7,CLOSED\r\n");
+    adapter.client.add_urc_message(b"+IPD,9,4\r\n");
+
+    assert!(adapter.is_connected(&socket).unwrap());
+    adapter.client.assert_all_cmds_sent();
+}
+
 #[test]
 fn test_connect_already_connected_by_urc() {
     let timer = MockTimer::new();
@@ -238,7 +301,7 @@ fn test_connect_receive_mode_error() {
         .unwrap_err();
 
     assert_eq!(
-        nb::Error::Other(Error::EnablingPassiveSocketModeFailed(AtError::Parse)),
+        nb::Error::Other(Error::ConfiguringReceiveModeFailed(AtError::Parse)),
         error
     );
 }
@@ -365,6 +428,7 @@ fn test_connect_unconfirmed() {
         Some(b"AT+CIPSTART=0,\"TCP\",\"127.0.0.1\",6000\r\n"),
         None,
     ));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSTATE?\r\n"), None));
 
     let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
 
@@ -376,6 +440,182 @@ fn test_connect_unconfirmed() {
     assert_eq!(nb::Error::Other(Error::UnconfirmedSocketState), error);
 }
 
+#[test]
+fn test_connect_unconfirmed_synced_by_connection_state() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"TCP\",\"127.0.0.1\",6000\r\n"),
+        None,
+    ));
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CIPSTATE?\r\n"),
+        b"+CIPSTATE:0,\"TCP\",\"127.0.0.1\",6000,41000,0\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut socket = adapter.socket().unwrap();
+    adapter
+        .connect(&mut socket, SocketAddr::from_str("127.0.0.1:6000").unwrap())
+        .unwrap();
+
+    assert!(adapter.is_connected(&socket).unwrap());
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_sync_sockets_missed_closed_message() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_response(MockedCommand::ok(Some(b"AT+CIPSTATE?\r\n"), None));
+    adapter.sync_sockets().unwrap();
+
+    assert!(!adapter.is_connected(&socket).unwrap());
+    assert_eq!(
+        nb::Error::Other(Error::ClosingSocket),
+        adapter.send(&mut socket, b"test").unwrap_err()
+    );
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_sync_sockets_link_ids_exceeding_socket_count_skipped() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let socket = connect_socket(&mut adapter);
+
+    adapter.client.add_response(MockedCommand::new(
+        Some(b"AT+CIPSTATE?\r\n"),
+        b"+CIPSTATE:0,\"TCP\",\"127.0.0.1\",5000,41000,0\r\n+CIPSTATE:7,\"TCP\",\"127.0.0.1\",6000,41001,0\r\n",
+        None,
+    ));
+    adapter.sync_sockets().unwrap();
+
+    assert!(adapter.is_connected(&socket).unwrap());
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_socket_info() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let socket = connect_socket(&mut adapter);
+
+    let info = adapter.socket_info(&socket).unwrap();
+    assert_eq!(Some(LinkType::Tcp), info.link_type);
+    assert_eq!(None, info.local_port);
+    assert_eq!("127.0.0.1:5000", info.remote.unwrap().to_string());
+
+    // Local port is reported by CIPSTATE
+    adapter.client.add_response(MockedCommand::new(
+        Some(b"AT+CIPSTATE?\r\n"),
+        b"+CIPSTATE:0,\"TCP\",\"127.0.0.1\",5000,41000,0\r\n",
+        None,
+    ));
+    adapter.sync_sockets().unwrap();
+
+    let info = adapter.socket_info(&socket).unwrap();
+    assert_eq!(Some(LinkType::Tcp), info.link_type);
+    assert_eq!(Some(41000), info.local_port);
+    assert_eq!("127.0.0.1:5000", info.remote.unwrap().to_string());
+}
+
+#[test]
+fn test_socket_info_unconnected_socket() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    adapter.client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    let socket = adapter.socket().unwrap();
+
+    assert_eq!(None, adapter.socket_info(&socket));
+}
+
+#[test]
+fn test_sync_sockets_incoming_connection() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut server = listen_server(&mut adapter);
+
+    // CONNECT message of the remote client got lost
+    adapter.client.add_response(MockedCommand::new(
+        Some(b"AT+CIPSTATE?\r\n"),
+        b"+CIPSTATE:0,\"TCP\",\"10.0.0.2\",51234,80,1\r\n",
+        None,
+    ));
+    adapter.client.add_response(MockedCommand::new(
+        Some(b"AT+CIPSTATE?\r\n"),
+        b"+CIPSTATE:0,\"TCP\",\"10.0.0.2\",51234,80,1\r\n",
+        None,
+    ));
+
+    adapter.sync_sockets().unwrap();
+    let (socket, remote) = adapter.accept(&mut server).unwrap();
+
+    assert_eq!(0, socket.link_id);
+    assert_eq!("10.0.0.2:51234", remote.to_string());
+    assert!(adapter.is_connected(&socket).unwrap());
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_sync_sockets_server_connection_on_unconnected_socket() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    adapter.client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    let socket = adapter.socket().unwrap();
+
+    adapter.client.add_response(MockedCommand::new(
+        Some(b"AT+CIPSTATE?\r\n"),
+        b"+CIPSTATE:0,\"TCP\",\"10.0.0.2\",51234,80,1\r\n",
+        None,
+    ));
+    adapter.sync_sockets().unwrap();
+
+    assert!(!adapter.is_connected(&socket).unwrap());
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_sync_sockets_command_error() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let socket = connect_socket(&mut adapter);
+
+    adapter
+        .client
+        .add_response(MockedCommand::error(Some(b"AT+CIPSTATE?\r\n"), None));
+
+    assert_eq!(
+        Error::ConnectionStateError(AtError::Parse),
+        adapter.sync_sockets().unwrap_err()
+    );
+    assert!(adapter.is_connected(&socket).unwrap());
+}
+
 #[test]
 fn test_connect_available_data_reset() {
     let timer = MockTimer::new();
@@ -441,6 +681,23 @@ fn test_send_not_connected() {
     assert_eq!(nb::Error::Other(Error::SocketUnconnected), error);
 }
 
+#[test]
+fn test_send_after_leave() {
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(1).returning(|_| Ok(()));
+
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_response(MockedCommand::ok(Some(b"AT+CWQAP\r\n"), None));
+    adapter.leave().unwrap();
+
+    let error = adapter.send(&mut socket, b"test data").unwrap_err();
+    assert_eq!(nb::Error::Other(Error::ClosingSocket), error);
+}
+
 #[test]
 fn test_send_tx_prepare_error() {
     let timer = MockTimer::new();
@@ -825,110 +1082,503 @@ fn test_receive_correct_command() {
 }
 
 #[test]
-/// Out-of-spec response covering bug in older ESP-AT firmware versions
-/// See https://github.com/atlas-aero/rt-esp-at-nal/issues/23
-fn test_receive_correct_command_out_of_spec() {
+fn test_receive_refresh_after_idle_interval() {
     let timer = MockTimer::new();
     let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
     let client = MockAtatClient::new(&channel);
     let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.set_receive_refresh_interval(Some(3));
     let mut socket = connect_socket(&mut adapter);
 
-    adapter.client.add_urc_message(b"+IPD,0,4\r\n");
+    // +IPD message got lost
+    adapter.client.add_response(MockedCommand::new(
+        Some(b"AT+CIPRECVLEN?\r\n"),
+        b"+CIPRECVLEN:4,,,,\r\n",
+        None,
+    ));
     adapter.client.add_response(MockedCommand::ok(
         Some(b"AT+CIPRECVDATA=0,16\r\n"),
-        Some(&[b"+CIPRECVDATA,4:aaaa"]),
+        Some(&[b"+CIPRECVDATA:4,aaaa"]),
     ));
 
     let mut buffer = [b' '; 16];
-    let length = adapter.receive(&mut socket, &mut buffer).unwrap();
+    assert_eq!(
+        nb::Error::WouldBlock,
+        adapter.receive(&mut socket, &mut buffer).unwrap_err()
+    );
+    assert_eq!(
+        nb::Error::WouldBlock,
+        adapter.receive(&mut socket, &mut buffer).unwrap_err()
+    );
 
+    let length = adapter.receive(&mut socket, &mut buffer).unwrap();
     assert_eq!(4, length);
     assert_eq!(b"aaaa", &buffer[..4]);
     adapter.client.assert_all_cmds_sent();
 }
 
 #[test]
-fn test_receive_data_received_buffer_bigger_then_block_size() {
+fn test_receive_refresh_no_data_available() {
     let timer = MockTimer::new();
     let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
     let client = MockAtatClient::new(&channel);
     let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.set_receive_refresh_interval(Some(2));
     let mut socket = connect_socket(&mut adapter);
 
-    adapter.client.add_urc_message(b"+IPD,0,24\r\n");
-    adapter.client.add_response(MockedCommand::ok(
-        Some(b"AT+CIPRECVDATA=0,16\r\n"),
-        Some(&[b"+CIPRECVDATA:16,aaaaaaaaaaaaaaaa"]),
-    ));
-    adapter.client.add_response(MockedCommand::ok(
-        Some(b"AT+CIPRECVDATA=0,16\r\n"),
-        Some(&[b"+CIPRECVDATA:8,bbbbbbbb"]),
+    adapter.client.add_response(MockedCommand::new(
+        Some(b"AT+CIPRECVLEN?\r\n"),
+        b"+CIPRECVLEN:0,,,,\r\n",
+        None,
     ));
 
-    let mut buffer = [b' '; 64];
-    let length = adapter.receive(&mut socket, &mut buffer).unwrap();
-
-    assert_eq!(24, length);
-    assert_eq!(b"aaaaaaaaaaaaaaaabbbbbbbb", &buffer[..length]);
+    // Idle count starts again after refreshing, so third call does not refresh
+    let mut buffer = [b' '; 16];
+    for _ in 0..3 {
+        assert_eq!(
+            nb::Error::WouldBlock,
+            adapter.receive(&mut socket, &mut buffer).unwrap_err()
+        );
+    }
     adapter.client.assert_all_cmds_sent();
 }
 
 #[test]
-fn test_receive_data_received_buffer_smaller_then_block_size() {
+fn test_refresh_available_data() {
     let timer = MockTimer::new();
     let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
     let client = MockAtatClient::new(&channel);
     let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
-    let mut socket = connect_socket(&mut adapter);
-
-    // Signal data is available
-    adapter.client.add_urc_message(b"+IPD,0,5\r\n");
+    let socket = connect_socket(&mut adapter);
 
-    adapter.client.add_response(MockedCommand::ok(
-        Some(b"AT+CIPRECVDATA=0,2\r\n"),
-        Some(&[b"+CIPRECVDATA:2,aa"]),
-    ));
-    let mut buffer = [b' '; 2];
-    let length = adapter.receive(&mut socket, &mut buffer).unwrap();
-    assert_eq!(2, length);
-    assert_eq!(b"aa", &buffer);
+    adapter.client.add_urc_message(b"+IPD,0,4\r\n");
+    assert_eq!(4, adapter.available(&socket));
 
-    adapter.client.add_response(MockedCommand::ok(
-        Some(b"AT+CIPRECVDATA=0,2\r\n"),
-        Some(&[b"+CIPRECVDATA:2,bb"]),
+    adapter.client.add_response(MockedCommand::new(
+        Some(b"AT+CIPRECVLEN?\r\n"),
+        b"+CIPRECVLEN:100,-1,,,,,,,\r\n",
+        None,
     ));
-    let mut buffer = [b' '; 2];
-    let length = adapter.receive(&mut socket, &mut buffer).unwrap();
-    assert_eq!(2, length);
-    assert_eq!(b"bb", &buffer);
+    adapter.refresh_available_data().unwrap();
 
-    adapter.client.add_response(MockedCommand::ok(
-        Some(b"AT+CIPRECVDATA=0,2\r\n"),
-        Some(&[b"+CIPRECVDATA:1,c"]),
-    ));
-    let mut buffer = [b' '; 2];
-    let length = adapter.receive(&mut socket, &mut buffer).unwrap();
-    assert_eq!(1, length);
-    assert_eq!(b"c ", &buffer);
+    assert_eq!(100, adapter.available(&socket));
     adapter.client.assert_all_cmds_sent();
 }
 
 #[test]
-fn test_receive_data_received_less_data_received_then_requested() {
+fn test_refresh_available_data_errors() {
     let timer = MockTimer::new();
     let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
     let client = MockAtatClient::new(&channel);
     let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
-    let mut socket = connect_socket(&mut adapter);
 
-    adapter.client.add_urc_message(b"+IPD,0,10\r\n");
-    adapter.client.add_response(MockedCommand::ok(
-        Some(b"AT+CIPRECVDATA=0,16\r\n"),
-        Some(&[b"+CIPRECVDATA:4,aaaa"]),
+    adapter
+        .client
+        .add_response(MockedCommand::error(Some(b"AT+CIPRECVLEN?\r\n"), None));
+    adapter.client.add_response(MockedCommand::new(
+        Some(b"AT+CIPRECVLEN?\r\n"),
+        b"+CIPRECVLEN:A,,,,\r\n",
+        None,
     ));
-    adapter.client.add_response(MockedCommand::ok(
-        Some(b"AT+CIPRECVDATA=0,16\r\n"),
+
+    assert_eq!(
+        Error::ReceiveLengthError(AtError::Parse),
+        adapter.refresh_available_data().unwrap_err()
+    );
+    assert_eq!(
+        Error::ReceiveLengthError(AtError::Parse),
+        adapter.refresh_available_data().unwrap_err()
+    );
+}
+
+#[test]
+fn test_receive_active_mode() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket_active_mode(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,4:aaaa");
+    adapter.client.add_urc_message(b"+IPD,0,3:bbb");
+    assert_eq!(7, adapter.available(&socket));
+
+    // Data is buffered by the adapter, so no CIPRECVLEN or CIPRECVDATA command is sent
+    adapter.refresh_available_data().unwrap();
+
+    let mut buffer = [b' '; 5];
+    assert_eq!(5, adapter.receive(&mut socket, &mut buffer).unwrap());
+    assert_eq!(b"aaaab", &buffer);
+    assert_eq!(2, adapter.available(&socket));
+
+    assert_eq!(2, adapter.receive(&mut socket, &mut buffer).unwrap());
+    assert_eq!(b"bb", &buffer[..2]);
+    assert_eq!(
+        nb::Error::WouldBlock,
+        adapter.receive(&mut socket, &mut buffer).unwrap_err()
+    );
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_receive_active_mode_buffer_overflow() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket_active_mode(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,12:aaaaaaaaaaaa");
+    adapter.client.add_urc_message(b"+IPD,0,8:bbbbbbbb");
+
+    let mut buffer = [b' '; 32];
+    assert_eq!(
+        nb::Error::Other(Error::ReceiveBufferOverflow),
+        adapter.receive(&mut socket, &mut buffer).unwrap_err()
+    );
+
+    // Data buffered before the overflow is kept
+    assert_eq!(16, adapter.receive(&mut socket, &mut buffer).unwrap());
+    assert_eq!(b"aaaaaaaaaaaabbbb", &buffer[..16]);
+}
+
+#[test]
+fn test_receive_active_mode_data_dropped_completely() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket_active_mode(&mut adapter);
+
+    // No data of the dropped +IPD message is buffered
+    adapter.session.sockets[0].rx_overflow = true;
+
+    let mut buffer = [b' '; 8];
+    assert_eq!(
+        nb::Error::Other(Error::ReceiveBufferOverflow),
+        adapter.receive(&mut socket, &mut buffer).unwrap_err()
+    );
+    assert_eq!(
+        nb::Error::WouldBlock,
+        adapter.receive(&mut socket, &mut buffer).unwrap_err()
+    );
+
+    adapter.session.sockets[0].rx_overflow = true;
+    assert_eq!(
+        nb::Error::Other(Error::ReceiveBufferOverflow),
+        adapter.receive_with(&socket, |data| data.len()).unwrap_err()
+    );
+    assert_eq!(
+        nb::Error::WouldBlock,
+        adapter.receive_with(&socket, |data| data.len()).unwrap_err()
+    );
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_receive_active_mode_message_exceeding_rx_size() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket_active_mode(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,20:aaaaaaaabbbbbbbbcccc");
+
+    let mut buffer = [b' '; 8];
+    assert_eq!(
+        nb::Error::Other(Error::ReceiveBufferOverflow),
+        adapter.receive(&mut socket, &mut buffer).unwrap_err()
+    );
+
+    // Data fitting in RX_SIZE is kept
+    assert_eq!(8, adapter.receive(&mut socket, &mut buffer).unwrap());
+    assert_eq!(b"aaaaaaaa", &buffer);
+    assert_eq!(8, adapter.receive(&mut socket, &mut buffer).unwrap());
+    assert_eq!(b"bbbbbbbb", &buffer);
+    assert_eq!(
+        nb::Error::WouldBlock,
+        adapter.receive(&mut socket, &mut buffer).unwrap_err()
+    );
+}
+
+#[test]
+fn test_set_receive_mode_without_receive_buffer() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: Adapter<MockAtatClient, MockTimer, 1_000_000, 32, 16, 16> =
+        Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    assert_eq!(
+        Error::ReceiveBufferDisabled,
+        adapter.set_receive_mode(ReceiveMode::Active).unwrap_err()
+    );
+    adapter.set_receive_mode(ReceiveMode::Passive).unwrap();
+}
+
+#[test]
+fn test_receive_active_mode_wrapping_buffer() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket_active_mode(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,12:aaaaaaaaaaaa");
+    let mut buffer = [b' '; 16];
+    assert_eq!(12, adapter.receive(&mut socket, &mut buffer).unwrap());
+
+    // Data may wrap around the end of the receive buffer
+    adapter.client.add_urc_message(b"+IPD,0,8:bbbbcccc");
+    assert_eq!(8, adapter.receive(&mut socket, &mut buffer).unwrap());
+    assert_eq!(b"bbbbcccc", &buffer[..8]);
+    assert_eq!(0, adapter.available(&socket));
+}
+
+#[test]
+fn test_receive_with_active_mode() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let socket = connect_socket_active_mode(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,4:aaaa");
+    adapter.client.add_urc_message(b"+IPD,0,3:bbb");
+
+    // Unconsumed data stays buffered
+    let length = adapter
+        .receive_with(&socket, |data| {
+            assert_eq!(b"aaaabbb", data);
+            2
+        })
+        .unwrap();
+    assert_eq!(2, length);
+    assert_eq!(5, adapter.available(&socket));
+
+    // Consumed length is limited to the passed data
+    let length = adapter
+        .receive_with(&socket, |data| {
+            assert_eq!(b"aabbb", data);
+            16
+        })
+        .unwrap();
+    assert_eq!(5, length);
+    assert_eq!(0, adapter.available(&socket));
+
+    assert_eq!(
+        nb::Error::WouldBlock,
+        adapter.receive_with(&socket, |data| data.len()).unwrap_err()
+    );
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_receive_with_buffer_overflow() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let socket = connect_socket_active_mode(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,12:aaaaaaaaaaaa");
+    adapter.client.add_urc_message(b"+IPD,0,8:bbbbbbbb");
+
+    assert_eq!(
+        nb::Error::Other(Error::ReceiveBufferOverflow),
+        adapter.receive_with(&socket, |data| data.len()).unwrap_err()
+    );
+    assert_eq!(16, adapter.receive_with(&socket, |data| data.len()).unwrap());
+}
+
+#[test]
+fn test_receive_with_passive_mode() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let socket = connect_socket(&mut adapter);
+
+    assert_eq!(
+        nb::Error::Other(Error::ActiveReceiveModeRequired),
+        adapter.receive_with(&socket, |data| data.len()).unwrap_err()
+    );
+}
+
+#[test]
+fn test_receive_active_mode_ignores_other_links() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket_active_mode(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,1,4:aaaa");
+    adapter.client.add_urc_message(b"+IPD,12,4:aaaa");
+
+    let mut buffer = [b' '; 16];
+    assert_eq!(
+        nb::Error::WouldBlock,
+        adapter.receive(&mut socket, &mut buffer).unwrap_err()
+    );
+}
+
+#[test]
+fn test_set_receive_mode_applied_by_next_connect() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    connect_socket(&mut adapter);
+
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=0\r\n"), None));
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=1,\"TCP\",\"127.0.0.1\",5000\r\n"),
+        Some(&[b"1,CONNECT\r\n"]),
+    ));
+
+    // Setting the current mode again does not send any command
+    adapter.set_receive_mode(ReceiveMode::Passive).unwrap();
+    adapter.set_receive_mode(ReceiveMode::Active).unwrap();
+
+    let mut socket = adapter.socket().unwrap();
+    adapter
+        .connect(&mut socket, SocketAddr::from_str("127.0.0.1:5000").unwrap())
+        .unwrap();
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_set_receive_mode_error() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.set_receive_mode(ReceiveMode::Active).unwrap();
+
+    adapter.client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    adapter
+        .client
+        .add_response(MockedCommand::error(Some(b"AT+CIPRECVMODE=0\r\n"), None));
+
+    let mut socket = adapter.socket().unwrap();
+    let error = adapter
+        .connect(&mut socket, SocketAddr::from_str("127.0.0.1:5000").unwrap())
+        .unwrap_err();
+    assert_eq!(
+        nb::Error::Other(Error::ConfiguringReceiveModeFailed(AtError::Parse)),
+        error
+    );
+}
+
+#[test]
+/// Out-of-spec response covering bug in older ESP-AT firmware versions
+/// See https://github.com/atlas-aero/rt-esp-at-nal/issues/23
+fn test_receive_correct_command_out_of_spec() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,4\r\n");
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPRECVDATA=0,16\r\n"),
+        Some(&[b"+CIPRECVDATA,4:aaaa"]),
+    ));
+
+    let mut buffer = [b' '; 16];
+    let length = adapter.receive(&mut socket, &mut buffer).unwrap();
+
+    assert_eq!(4, length);
+    assert_eq!(b"aaaa", &buffer[..4]);
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_receive_data_received_buffer_bigger_then_block_size() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,24\r\n");
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPRECVDATA=0,16\r\n"),
+        Some(&[b"+CIPRECVDATA:16,aaaaaaaaaaaaaaaa"]),
+    ));
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPRECVDATA=0,16\r\n"),
+        Some(&[b"+CIPRECVDATA:8,bbbbbbbb"]),
+    ));
+
+    let mut buffer = [b' '; 64];
+    let length = adapter.receive(&mut socket, &mut buffer).unwrap();
+
+    assert_eq!(24, length);
+    assert_eq!(b"aaaaaaaaaaaaaaaabbbbbbbb", &buffer[..length]);
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_receive_data_received_buffer_smaller_then_block_size() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    // Signal data is available
+    adapter.client.add_urc_message(b"+IPD,0,5\r\n");
+
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPRECVDATA=0,2\r\n"),
+        Some(&[b"+CIPRECVDATA:2,aa"]),
+    ));
+    let mut buffer = [b' '; 2];
+    let length = adapter.receive(&mut socket, &mut buffer).unwrap();
+    assert_eq!(2, length);
+    assert_eq!(b"aa", &buffer);
+
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPRECVDATA=0,2\r\n"),
+        Some(&[b"+CIPRECVDATA:2,bb"]),
+    ));
+    let mut buffer = [b' '; 2];
+    let length = adapter.receive(&mut socket, &mut buffer).unwrap();
+    assert_eq!(2, length);
+    assert_eq!(b"bb", &buffer);
+
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPRECVDATA=0,2\r\n"),
+        Some(&[b"+CIPRECVDATA:1,c"]),
+    ));
+    let mut buffer = [b' '; 2];
+    let length = adapter.receive(&mut socket, &mut buffer).unwrap();
+    assert_eq!(1, length);
+    assert_eq!(b"c ", &buffer);
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_receive_data_received_less_data_received_then_requested() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,10\r\n");
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPRECVDATA=0,16\r\n"),
+        Some(&[b"+CIPRECVDATA:4,aaaa"]),
+    ));
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPRECVDATA=0,16\r\n"),
         Some(&[b"+CIPRECVDATA:4,bbbb"]),
     ));
     adapter.client.add_response(MockedCommand::ok(
@@ -1157,13 +1807,32 @@ fn test_is_connected_true() {
     let client = MockAtatClient::new(&channel);
     let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
 
-    // Receiving socket
+    let socket = connect_socket(&mut adapter);
+    assert!(adapter.is_connected(&socket).unwrap());
+}
+
+#[test]
+fn test_is_connected_unconnected_socket_assigned_to_server_connection() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
     adapter.client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
-    let socket = adapter.socket().unwrap();
+    let mut socket = adapter.socket().unwrap();
 
+    // Link id was assigned by ESP-AT to a remote client of the TCP server
     adapter.client.add_urc_first_socket_connected();
 
-    assert!(adapter.is_connected(&socket).unwrap());
+    assert!(!adapter.is_connected(&socket).unwrap());
+    let error = adapter
+        .connect(&mut socket, SocketAddr::from_str("127.0.0.1:5000").unwrap())
+        .unwrap_err();
+    assert_eq!(nb::Error::Other(Error::AlreadyConnected), error);
+
+    // Pending server connection is kept
+    adapter.close(socket).unwrap();
+    adapter.client.assert_all_cmds_sent();
 }
 
 #[test]
@@ -1182,6 +1851,311 @@ fn test_is_connected_closing() {
     assert!(!adapter.is_connected(&socket).unwrap());
 }
 
+#[test]
+fn test_bind_releases_link_id() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut server = adapter.socket().unwrap();
+    adapter.bind(&mut server, 80).unwrap();
+
+    assert_eq!(0, adapter.socket().unwrap().link_id);
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_bind_server_already_bound() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut server1 = adapter.socket().unwrap();
+    adapter.bind(&mut server1, 80).unwrap();
+
+    let mut server2 = adapter.socket().unwrap();
+    assert_eq!(Error::ServerAlreadyBound, adapter.bind(&mut server2, 81).unwrap_err());
+}
+
+#[test]
+fn test_bind_connected_socket() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    assert_eq!(Error::AlreadyConnected, adapter.bind(&mut socket, 80).unwrap_err());
+}
+
+#[test]
+fn test_listen_not_bound() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut socket = adapter.socket().unwrap();
+    assert_eq!(Error::SocketNotBound, adapter.listen(&mut socket).unwrap_err());
+}
+
+#[test]
+fn test_listen_correct_commands() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    listen_server(&mut adapter);
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_listen_correct_commands_configured() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSERVERMAXCONN=2\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSERVER=1,80\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSTO=60\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.set_server_max_connections(2);
+    adapter.set_server_timeout_s(60);
+
+    let mut server = adapter.socket().unwrap();
+    adapter.bind(&mut server, 80).unwrap();
+    adapter.listen(&mut server).unwrap();
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_listen_server_command_error() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::error(Some(b"AT+CIPSERVER=1,80\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut server = adapter.socket().unwrap();
+    adapter.bind(&mut server, 80).unwrap();
+    assert_eq!(
+        Error::ServerStartError(AtError::Parse),
+        adapter.listen(&mut server).unwrap_err()
+    );
+}
+
+#[test]
+fn test_accept_not_listening() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut server = adapter.socket().unwrap();
+    adapter.bind(&mut server, 80).unwrap();
+    assert_eq!(
+        nb::Error::Other(Error::SocketNotBound),
+        adapter.accept(&mut server).unwrap_err()
+    );
+}
+
+#[test]
+fn test_accept_no_pending_connection() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut server = listen_server(&mut adapter);
+    assert_eq!(nb::Error::WouldBlock, adapter.accept(&mut server).unwrap_err());
+}
+
+#[test]
+fn test_accept_incoming_connection() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut server = listen_server(&mut adapter);
+
+    adapter.client.add_urc_message(b"1,CONNECT\r\n");
+    adapter.client.add_response(MockedCommand::new(
+        Some(b"AT+CIPSTATE?\r\n"),
+        b"+CIPSTATE:0,\"TCP\",\"10.0.0.1\",5000,41000,0\r\n+CIPSTATE:1,\"TCP\",\"10.0.0.2\",51234,80,1\r\n",
+        None,
+    ));
+
+    let (socket, remote) = adapter.accept(&mut server).unwrap();
+    assert_eq!(1, socket.link_id);
+    assert_eq!("10.0.0.2:51234", remote.to_string());
+    assert!(adapter.is_connected(&socket).unwrap());
+    assert!(!adapter.is_connected(&server).unwrap());
+
+    // Accepted just once
+    assert_eq!(nb::Error::WouldBlock, adapter.accept(&mut server).unwrap_err());
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_accept_incoming_connection_on_closing_link() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut socket = connect_socket(&mut adapter);
+    adapter.client.add_urc_first_socket_closed();
+
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+CIPSERVER=1,80\r\n"), None));
+    let mut server = adapter.socket().unwrap();
+    adapter.bind(&mut server, 80).unwrap();
+    adapter.listen(&mut server).unwrap();
+
+    // ESP-AT reuses the link id of the remotely closed connection, which was not closed by the user yet
+    adapter.client.add_urc_message(b"0,CONNECT\r\n");
+    assert!(!adapter.is_connected(&socket).unwrap());
+    assert_eq!(
+        nb::Error::Other(Error::SocketUnconnected),
+        adapter.send(&mut socket, b"test").unwrap_err()
+    );
+
+    adapter.client.add_response(MockedCommand::new(
+        Some(b"AT+CIPSTATE?\r\n"),
+        b"+CIPSTATE:0,\"TCP\",\"10.0.0.2\",51234,80,1\r\n",
+        None,
+    ));
+    let (accepted, remote) = adapter.accept(&mut server).unwrap();
+    assert_eq!(0, accepted.link_id);
+    assert_eq!("10.0.0.2:51234", remote.to_string());
+    assert!(adapter.is_connected(&accepted).unwrap());
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_accept_connection_state_missing() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut server = listen_server(&mut adapter);
+
+    adapter.client.add_urc_message(b"1,CONNECT\r\n");
+    adapter.client.add_response(MockedCommand::new(
+        Some(b"AT+CIPSTATE?\r\n"),
+        b"+CIPSTATE:0,\"TCP\",\"10.0.0.1\",5000,41000,0\r\n",
+        None,
+    ));
+
+    assert_eq!(
+        nb::Error::Other(Error::ConnectionStateError(AtError::InvalidResponse)),
+        adapter.accept(&mut server).unwrap_err()
+    );
+}
+
+#[test]
+fn test_accept_connection_closed_before_accepted() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut server = listen_server(&mut adapter);
+
+    adapter.client.add_urc_message(b"0,CONNECT\r\n");
+    adapter.client.add_urc_message(b"0,CLOSED\r\n");
+
+    assert_eq!(nb::Error::WouldBlock, adapter.accept(&mut server).unwrap_err());
+    assert_eq!(0, adapter.socket().unwrap().link_id);
+}
+
+#[test]
+fn test_server_socket_not_usable_as_client() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut server = listen_server(&mut adapter);
+
+    assert_eq!(
+        nb::Error::Other(Error::ServerSocket),
+        adapter.send(&mut server, b"test").unwrap_err()
+    );
+    assert_eq!(
+        nb::Error::Other(Error::ServerSocket),
+        adapter.receive(&mut server, &mut [0x0; 16]).unwrap_err()
+    );
+    assert_eq!(
+        nb::Error::Other(Error::ServerSocket),
+        adapter.receive_with(&server, |data| data.len()).unwrap_err()
+    );
+    assert_eq!(
+        nb::Error::Other(Error::ServerSocket),
+        adapter
+            .connect(&mut server, SocketAddr::from_str("127.0.0.1:5000").unwrap())
+            .unwrap_err()
+    );
+}
+
+#[test]
+fn test_close_server_socket() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let server = listen_server(&mut adapter);
+
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+CIPSERVER=0\r\n"), None));
+    adapter.close(server).unwrap();
+    adapter.client.assert_all_cmds_sent();
+
+    // Server may be bound again
+    let mut server = adapter.socket().unwrap();
+    adapter.bind(&mut server, 80).unwrap();
+}
+
+/// Helper for creating a TCP server listening on port 80
+fn listen_server(adapter: &mut AdapterType) -> Socket {
+    adapter.client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+CIPSERVER=1,80\r\n"), None));
+
+    let mut server = adapter.socket().unwrap();
+    adapter.bind(&mut server, 80).unwrap();
+    adapter.listen(&mut server).unwrap();
+
+    server
+}
+
 /// Helper for opening & connecting a socket
 fn connect_socket(adapter: &mut AdapterType) -> Socket {
     adapter.client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
@@ -1201,3 +2175,24 @@ fn connect_socket(adapter: &mut AdapterType) -> Socket {
 
     socket
 }
+
+/// Helper for opening & connecting a socket in active receiving mode
+fn connect_socket_active_mode(adapter: &mut AdapterType) -> Socket {
+    adapter.set_receive_mode(ReceiveMode::Active).unwrap();
+
+    adapter.client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=0\r\n"), None));
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"TCP\",\"127.0.0.1\",5000\r\n"),
+        Some(&[b"0,CONNECT\r\n"]),
+    ));
+
+    let mut socket = adapter.socket().unwrap();
+    adapter
+        .connect(&mut socket, SocketAddr::from_str("127.0.0.1:5000").unwrap())
+        .unwrap();
+
+    socket
+}
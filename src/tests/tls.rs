@@ -0,0 +1,315 @@
+use crate::stack::Error;
+use crate::tests::mock::{MockAtatClient, MockTimer, MockedCommand};
+use crate::tls::{TlsAuthMode, TlsConfig};
+use crate::urc::URCMessages;
+use crate::wifi::Adapter;
+use atat::Error as AtError;
+use core::net::SocketAddr;
+use core::str::FromStr;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::pubsub::PubSubChannel;
+use embedded_nal::TcpClientStack;
+
+type AdapterType<'a> = Adapter<'a, MockAtatClient<'a>, MockTimer, 1_000_000, 32, 16, 16>;
+
+#[test]
+fn test_connect_tls_no_auth_ipv4() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSSLCCONF=0,0\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSSLCALPN=0,0\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"SSL\",\"127.0.0.1\",443\r\n"),
+        Some(&[b"0,CONNECT\r\n"]),
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut socket = adapter.socket().unwrap();
+    adapter
+        .connect_tls(
+            &mut socket,
+            SocketAddr::from_str("127.0.0.1:443").unwrap(),
+            &TlsConfig::default(),
+        )
+        .unwrap();
+
+    assert!(adapter.is_connected(&socket).unwrap());
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_connect_tls_ipv6() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSSLCCONF=0,0\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSSLCALPN=0,0\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"SSLv6\",\"2001:0db8:0:0:0:0:0:0001\",443\r\n"),
+        Some(&[b"0,CONNECT\r\n"]),
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut socket = adapter.socket().unwrap();
+    adapter
+        .connect_tls(
+            &mut socket,
+            SocketAddr::from_str("[2001:db8::1]:443").unwrap(),
+            &TlsConfig::default(),
+        )
+        .unwrap();
+
+    assert!(adapter.is_connected(&socket).unwrap());
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_connect_tls_auth_modes() {
+    assert_auth_mode(
+        TlsAuthMode::ClientCertificate { pki_number: 1 },
+        b"AT+CIPSSLCCONF=0,1,1,0\r\n",
+    );
+    assert_auth_mode(
+        TlsAuthMode::ServerVerification { ca_number: 2 },
+        b"AT+CIPSSLCCONF=0,2,0,2\r\n",
+    );
+    assert_auth_mode(
+        TlsAuthMode::Mutual {
+            pki_number: 1,
+            ca_number: 2,
+        },
+        b"AT+CIPSSLCCONF=0,3,1,2\r\n",
+    );
+}
+
+#[test]
+fn test_connect_tls_sni_and_alpn() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSSLCCONF=0,2,0,0\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSSLCSNI=0,\"example.org\"\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSSLCALPN=0,3,\"h2\",\"http/1.1\",\"mqtt\"\r\n"),
+        None,
+    ));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"SSL\",\"127.0.0.1\",443\r\n"),
+        Some(&[b"0,CONNECT\r\n"]),
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let config = TlsConfig {
+        auth_mode: TlsAuthMode::ServerVerification { ca_number: 0 },
+        sni: Some("example.org"),
+        alpn: &["h2", "http/1.1", "mqtt"],
+    };
+
+    let mut socket = adapter.socket().unwrap();
+    adapter
+        .connect_tls(&mut socket, SocketAddr::from_str("127.0.0.1:443").unwrap(), &config)
+        .unwrap();
+
+    assert!(adapter.is_connected(&socket).unwrap());
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_connect_tls_too_many_alpn_protocols() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let config = TlsConfig {
+        alpn: &["a", "b", "c", "d"],
+        ..TlsConfig::default()
+    };
+
+    let mut socket = adapter.socket().unwrap();
+    let error = adapter
+        .connect_tls(&mut socket, SocketAddr::from_str("127.0.0.1:443").unwrap(), &config)
+        .unwrap_err();
+
+    assert_eq!(Error::InvalidTlsConfig, error);
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_connect_tls_configuration_error() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::error(Some(b"AT+CIPSSLCCONF=0,2,0,5\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let config = TlsConfig {
+        auth_mode: TlsAuthMode::ServerVerification { ca_number: 5 },
+        ..TlsConfig::default()
+    };
+
+    let mut socket = adapter.socket().unwrap();
+    let error = adapter
+        .connect_tls(&mut socket, SocketAddr::from_str("127.0.0.1:443").unwrap(), &config)
+        .unwrap_err();
+
+    assert_eq!(Error::TlsConfigurationError(AtError::Parse), error);
+}
+
+#[test]
+fn test_connect_tls_sni_error() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSSLCCONF=0,0\r\n"), None));
+    client.add_response(MockedCommand::error(Some(b"AT+CIPSSLCSNI=0,\"example.org\"\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let config = TlsConfig {
+        sni: Some("example.org"),
+        ..TlsConfig::default()
+    };
+
+    let mut socket = adapter.socket().unwrap();
+    let error = adapter
+        .connect_tls(&mut socket, SocketAddr::from_str("127.0.0.1:443").unwrap(), &config)
+        .unwrap_err();
+
+    assert_eq!(Error::TlsServerNameError(AtError::Parse), error);
+}
+
+#[test]
+fn test_connect_tls_alpn_error() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSSLCCONF=0,0\r\n"), None));
+    client.add_response(MockedCommand::error(Some(b"AT+CIPSSLCALPN=0,1,\"mqtt\"\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let config = TlsConfig {
+        alpn: &["mqtt"],
+        ..TlsConfig::default()
+    };
+
+    let mut socket = adapter.socket().unwrap();
+    let error = adapter
+        .connect_tls(&mut socket, SocketAddr::from_str("127.0.0.1:443").unwrap(), &config)
+        .unwrap_err();
+
+    assert_eq!(Error::TlsAlpnError(AtError::Parse), error);
+}
+
+#[test]
+fn test_connect_tls_handshake_error() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSSLCCONF=0,0\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSSLCALPN=0,0\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::error(
+        Some(b"AT+CIPSTART=0,\"SSL\",\"127.0.0.1\",443\r\n"),
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut socket = adapter.socket().unwrap();
+    let error = adapter
+        .connect_tls(
+            &mut socket,
+            SocketAddr::from_str("127.0.0.1:443").unwrap(),
+            &TlsConfig::default(),
+        )
+        .unwrap_err();
+
+    assert_eq!(Error::TlsConnectError(AtError::Parse), error);
+}
+
+#[test]
+fn test_connect_tls_already_connected() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"TCP\",\"127.0.0.1\",80\r\n"),
+        Some(&[b"0,CONNECT\r\n"]),
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut socket = adapter.socket().unwrap();
+    adapter
+        .connect(&mut socket, SocketAddr::from_str("127.0.0.1:80").unwrap())
+        .unwrap();
+
+    let error = adapter
+        .connect_tls(
+            &mut socket,
+            SocketAddr::from_str("127.0.0.1:443").unwrap(),
+            &TlsConfig::default(),
+        )
+        .unwrap_err();
+
+    assert_eq!(Error::AlreadyConnected, error);
+    adapter.client.assert_all_cmds_sent();
+}
+
+fn assert_auth_mode(auth_mode: TlsAuthMode, expected_command: &'static [u8]) {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(expected_command), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSSLCALPN=0,0\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"SSL\",\"127.0.0.1\",443\r\n"),
+        Some(&[b"0,CONNECT\r\n"]),
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let config = TlsConfig {
+        auth_mode,
+        ..TlsConfig::default()
+    };
+
+    let mut socket = adapter.socket().unwrap();
+    adapter
+        .connect_tls(&mut socket, SocketAddr::from_str("127.0.0.1:443").unwrap(), &config)
+        .unwrap();
+    adapter.client.assert_all_cmds_sent();
+}
@@ -0,0 +1,684 @@
+use crate::queue::VecQueue;
+use crate::stack::{Error, LinkType, ReceiveMode, Socket};
+use crate::tests::mock::{MockAtatClient, MockTimer, MockedCommand};
+use crate::udp::MAX_RX_DATAGRAMS;
+use crate::urc::URCMessages;
+use crate::wifi::Adapter;
+use alloc::string::ToString;
+use alloc::vec;
+use atat::Error as AtError;
+use core::net::SocketAddr;
+use core::str::FromStr;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::pubsub::PubSubChannel;
+use embedded_nal::{TcpClientStack, UdpClientStack, UdpFullStack};
+
+type AdapterType<'a> = Adapter<'a, MockAtatClient<'a>, MockTimer, 1_000_000, 32, 16, 16, 5, VecQueue<16>>;
+
+#[test]
+fn test_socket_shares_link_ids_with_tcp() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(0, TcpClientStack::socket(&mut adapter).unwrap().link_id);
+    assert_eq!(1, UdpClientStack::socket(&mut adapter).unwrap().link_id);
+    assert_eq!(2, TcpClientStack::socket(&mut adapter).unwrap().link_id);
+    assert_eq!(3, UdpClientStack::socket(&mut adapter).unwrap().link_id);
+    assert_eq!(4, UdpClientStack::socket(&mut adapter).unwrap().link_id);
+
+    let result = UdpClientStack::socket(&mut adapter).unwrap_err();
+    assert_eq!(Error::NoSocketAvailable, result);
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_connect_correct_commands_ipv4() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"UDP\",\"127.0.0.1\",5000\r\n"),
+        Some(&[b"0,CONNECT\r\n"]),
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut socket = UdpClientStack::socket(&mut adapter).unwrap();
+    UdpClientStack::connect(
+        &mut adapter,
+        &mut socket,
+        SocketAddr::from_str("127.0.0.1:5000").unwrap(),
+    )
+    .unwrap();
+
+    assert!(adapter.is_connected(&socket).unwrap());
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_connect_correct_commands_ipv6() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"UDPv6\",\"2001:0db8:0:0:0:0:0:0001\",8080\r\n"),
+        Some(&[b"0,CONNECT\r\n"]),
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut socket = UdpClientStack::socket(&mut adapter).unwrap();
+    UdpClientStack::connect(
+        &mut adapter,
+        &mut socket,
+        SocketAddr::from_str("[2001:db8::1]:8080").unwrap(),
+    )
+    .unwrap();
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_connect_command_error() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::error(
+        Some(b"AT+CIPSTART=0,\"UDP\",\"127.0.0.1\",5000\r\n"),
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut socket = UdpClientStack::socket(&mut adapter).unwrap();
+    let error = UdpClientStack::connect(
+        &mut adapter,
+        &mut socket,
+        SocketAddr::from_str("127.0.0.1:5000").unwrap(),
+    )
+    .unwrap_err();
+
+    assert_eq!(Error::ConnectError(AtError::Parse), error);
+}
+
+#[test]
+fn test_connect_unconfirmed() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"UDP\",\"127.0.0.1\",5000\r\n"),
+        None,
+    ));
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPSTATE?\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut socket = UdpClientStack::socket(&mut adapter).unwrap();
+    let error = UdpClientStack::connect(
+        &mut adapter,
+        &mut socket,
+        SocketAddr::from_str("127.0.0.1:5000").unwrap(),
+    )
+    .unwrap_err();
+
+    assert_eq!(Error::UnconfirmedSocketState, error);
+}
+
+#[test]
+fn test_send_not_connected() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = UdpClientStack::socket(&mut adapter).unwrap();
+
+    let error = UdpClientStack::send(&mut adapter, &mut socket, b"test").unwrap_err();
+    assert_eq!(nb::Error::Other(Error::SocketUnconnected), error);
+}
+
+#[test]
+fn test_send_correct_commands() {
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(1).returning(|_| Ok(()));
+
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+CIPSEND=0,4\r\n"), None));
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"test"),
+        Some(&[b"Recv 4 bytes\r\n", b"SEND OK\r\n"]),
+    ));
+
+    UdpClientStack::send(&mut adapter, &mut socket, b"test").unwrap();
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_send_datagram_bigger_then_block_size() {
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(1).returning(|_| Ok(()));
+
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+CIPSEND=0,46\r\n"), None));
+    adapter.client.add_response(MockedCommand::ok(Some(&[b'A'; 32]), None));
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"second message"),
+        Some(&[b"Recv 46 bytes\r\n", b"SEND OK\r\n"]),
+    ));
+
+    let mut buffer = vec![b'A'; 32];
+    buffer.extend_from_slice(b"second message");
+
+    UdpClientStack::send(&mut adapter, &mut socket, buffer.as_slice()).unwrap();
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_send_byte_count_not_matching() {
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(1).returning(|_| Ok(()));
+
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+CIPSEND=0,4\r\n"), None));
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"test"),
+        Some(&[b"Recv 3 bytes\r\n", b"SEND OK\r\n"]),
+    ));
+
+    let error = UdpClientStack::send(&mut adapter, &mut socket, b"test").unwrap_err();
+    assert_eq!(nb::Error::Other(Error::PartialSend), error);
+}
+
+#[test]
+fn test_send_fail_urc_message() {
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(1).returning(|_| Ok(()));
+
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+CIPSEND=0,4\r\n"), None));
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"test"), Some(&[b"SEND FAIL\r\n"])));
+
+    let error = UdpClientStack::send(&mut adapter, &mut socket, b"test").unwrap_err();
+    assert_eq!(nb::Error::Other(Error::SendFailed(AtError::Error)), error);
+}
+
+#[test]
+fn test_receive_no_data_available() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    let mut buffer = [0x0; 16];
+    let error = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap_err();
+    assert_eq!(nb::Error::WouldBlock, error);
+}
+
+#[test]
+fn test_receive_correct_command() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,4\r\n");
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPRECVDATA=0,16\r\n"),
+        Some(&[b"+CIPRECVDATA:4,aaaa"]),
+    ));
+
+    let mut buffer = [b' '; 16];
+    let (length, remote) = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap();
+
+    assert_eq!(4, length);
+    assert_eq!(b"aaaa", &buffer[..4]);
+    assert_eq!("127.0.0.1:5000", remote.to_string());
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_close_socket_closed_successfully() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let socket = connect_socket(&mut adapter);
+
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+CIPCLOSE=0\r\n"), Some(&[b"0,CLOSED\r\n"])));
+
+    UdpClientStack::close(&mut adapter, socket).unwrap();
+    adapter.client.assert_all_cmds_sent();
+
+    // Link id gets reused
+    assert_eq!(0, UdpClientStack::socket(&mut adapter).unwrap().link_id);
+}
+
+#[test]
+fn test_bind_correct_commands() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let socket = bind_socket(&mut adapter);
+    assert!(adapter.is_connected(&socket).unwrap());
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_bind_socket_info() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let socket = bind_socket(&mut adapter);
+    let info = adapter.socket_info(&socket).unwrap();
+
+    assert_eq!(Some(LinkType::Udp), info.link_type);
+    assert_eq!(Some(7000), info.local_port);
+    assert_eq!(None, info.remote);
+}
+
+#[test]
+fn test_bind_remote_info_error() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    client.add_response(MockedCommand::error(Some(b"AT+CIPDINFO=1\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut socket = UdpClientStack::socket(&mut adapter).unwrap();
+    let error = adapter.bind(&mut socket, 7000).unwrap_err();
+
+    assert_eq!(Error::EnablingRemoteInfoFailed(AtError::Parse), error);
+}
+
+#[test]
+fn test_bind_remote_info_cmd_sent_once() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    bind_socket(&mut adapter);
+
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=1,\"UDP\",\"0.0.0.0\",0,7001,2\r\n"),
+        Some(&[b"1,CONNECT\r\n"]),
+    ));
+
+    let mut socket = UdpClientStack::socket(&mut adapter).unwrap();
+    adapter.bind(&mut socket, 7001).unwrap();
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_bind_already_connected() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    let mut socket = connect_socket(&mut adapter);
+    adapter.client.add_response(MockedCommand::ok(Some(b"AT+CIPDINFO=1\r\n"), None));
+
+    let error = adapter.bind(&mut socket, 7000).unwrap_err();
+    assert_eq!(Error::AlreadyConnected, error);
+}
+
+#[test]
+fn test_send_to_correct_commands_ipv4() {
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(1).returning(|_| Ok(()));
+
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = bind_socket(&mut adapter);
+
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+CIPSEND=0,4,\"10.0.0.2\",6000\r\n"), None));
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"pong"),
+        Some(&[b"Recv 4 bytes\r\n", b"SEND OK\r\n"]),
+    ));
+
+    adapter
+        .send_to(&mut socket, SocketAddr::from_str("10.0.0.2:6000").unwrap(), b"pong")
+        .unwrap();
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_send_to_correct_commands_ipv6() {
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(1).returning(|_| Ok(()));
+
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = bind_socket(&mut adapter);
+
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSEND=0,4,\"2001:0db8:0:0:0:0:0:0001\",6000\r\n"),
+        None,
+    ));
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"pong"), Some(&[b"SEND OK\r\n"])));
+
+    adapter
+        .send_to(
+            &mut socket,
+            SocketAddr::from_str("[2001:db8::1]:6000").unwrap(),
+            b"pong",
+        )
+        .unwrap();
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_send_to_not_bound() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = UdpClientStack::socket(&mut adapter).unwrap();
+
+    let error = adapter
+        .send_to(&mut socket, SocketAddr::from_str("10.0.0.2:6000").unwrap(), b"pong")
+        .unwrap_err();
+    assert_eq!(nb::Error::Other(Error::SocketUnconnected), error);
+}
+
+#[test]
+fn test_receive_from_remote_info() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = bind_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,4,\"10.0.0.2\",6000\r\n");
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPRECVDATA=0,16\r\n"),
+        Some(&[b"+CIPRECVDATA:4,\"10.0.0.2\",6000,ping"]),
+    ));
+
+    let mut buffer = [b' '; 16];
+    let (length, remote) = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap();
+
+    assert_eq!(4, length);
+    assert_eq!(b"ping", &buffer[..4]);
+    assert_eq!("10.0.0.2:6000", remote.to_string());
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_receive_from_datagrams_of_different_remotes() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = bind_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,4,\"10.0.0.2\",6000\r\n");
+    adapter.client.add_urc_message(b"+IPD,0,8,\"10.0.0.3\",7000\r\n");
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPRECVDATA=0,16\r\n"),
+        Some(&[b"+CIPRECVDATA:4,\"10.0.0.2\",6000,ping"]),
+    ));
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPRECVDATA=0,16\r\n"),
+        Some(&[b"+CIPRECVDATA:4,\"10.0.0.3\",7000,pong"]),
+    ));
+
+    let mut buffer = [b' '; 16];
+    let (length, remote) = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap();
+    assert_eq!(4, length);
+    assert_eq!(b"ping", &buffer[..4]);
+    assert_eq!("10.0.0.2:6000", remote.to_string());
+
+    let (length, remote) = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap();
+    assert_eq!(4, length);
+    assert_eq!(b"pong", &buffer[..4]);
+    assert_eq!("10.0.0.3:7000", remote.to_string());
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_receive_from_remote_info_missing() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = bind_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,4\r\n");
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPRECVDATA=0,16\r\n"),
+        Some(&[b"+CIPRECVDATA:4,ping"]),
+    ));
+
+    let mut buffer = [b' '; 16];
+    let error = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap_err();
+    assert_eq!(nb::Error::Other(Error::ReceiveFailed(AtError::InvalidResponse)), error);
+}
+
+#[test]
+fn test_receive_from_active_mode() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = bind_socket_active_mode(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,4,\"10.0.0.2\",6000:ping");
+
+    let mut buffer = [b' '; 16];
+    let (length, remote) = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap();
+
+    assert_eq!(4, length);
+    assert_eq!(b"ping", &buffer[..4]);
+    assert_eq!("10.0.0.2:6000", remote.to_string());
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_receive_from_active_mode_datagrams_of_different_remotes() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = bind_socket_active_mode(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,4,\"10.0.0.2\",6000:ping");
+    adapter.client.add_urc_message(b"+IPD,0,6,\"10.0.0.3\",7000:hello!");
+
+    // Rest of a datagram exceeding the buffer is returned by the next call
+    let mut buffer = [b' '; 3];
+    let (length, remote) = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap();
+    assert_eq!(b"pin", &buffer[..length]);
+    assert_eq!("10.0.0.2:6000", remote.to_string());
+
+    let (length, remote) = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap();
+    assert_eq!(b"g", &buffer[..length]);
+    assert_eq!("10.0.0.2:6000", remote.to_string());
+
+    let mut buffer = [b' '; 16];
+    let (length, remote) = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap();
+    assert_eq!(b"hello!", &buffer[..length]);
+    assert_eq!("10.0.0.3:7000", remote.to_string());
+
+    assert_eq!(
+        nb::Error::WouldBlock,
+        UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap_err()
+    );
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_receive_from_active_mode_too_many_datagrams() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let mut socket = bind_socket_active_mode(&mut adapter);
+
+    for _ in 0..=MAX_RX_DATAGRAMS {
+        adapter.client.add_urc_message(b"+IPD,0,1,\"10.0.0.2\",6000:a");
+    }
+
+    let mut buffer = [b' '; 16];
+    assert_eq!(
+        nb::Error::Other(Error::ReceiveBufferOverflow),
+        UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap_err()
+    );
+
+    // Last datagram was dropped completely
+    for _ in 0..MAX_RX_DATAGRAMS {
+        let (length, _) = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap();
+        assert_eq!(1, length);
+    }
+    assert_eq!(
+        nb::Error::WouldBlock,
+        UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap_err()
+    );
+}
+
+#[test]
+fn test_receive_with_active_mode_single_datagram() {
+    let timer = MockTimer::new();
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let socket = bind_socket_active_mode(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,4,\"10.0.0.2\",6000:ping");
+    adapter.client.add_urc_message(b"+IPD,0,4,\"10.0.0.3\",7000:pong");
+
+    let length = adapter
+        .receive_with(&socket, |data| {
+            assert_eq!(b"ping", data);
+            16
+        })
+        .unwrap();
+    assert_eq!(4, length);
+
+    let length = adapter
+        .receive_with(&socket, |data| {
+            assert_eq!(b"pong", data);
+            data.len()
+        })
+        .unwrap();
+    assert_eq!(4, length);
+    assert_eq!(0, adapter.available(&socket));
+}
+
+/// Helper for opening & binding a socket to local port 7000
+fn bind_socket(adapter: &mut AdapterType) -> Socket {
+    adapter.client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    adapter.client.add_response(MockedCommand::ok(Some(b"AT+CIPDINFO=1\r\n"), None));
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"UDP\",\"0.0.0.0\",0,7000,2\r\n"),
+        Some(&[b"0,CONNECT\r\n"]),
+    ));
+
+    let mut socket = UdpClientStack::socket(adapter).unwrap();
+    adapter.bind(&mut socket, 7000).unwrap();
+
+    socket
+}
+
+/// Helper for opening & binding a socket to local port 7000 in active receiving mode
+fn bind_socket_active_mode(adapter: &mut AdapterType) -> Socket {
+    adapter.set_receive_mode(ReceiveMode::Active).unwrap();
+
+    adapter.client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    adapter.client.add_response(MockedCommand::ok(Some(b"AT+CIPDINFO=1\r\n"), None));
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=0\r\n"), None));
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"UDP\",\"0.0.0.0\",0,7000,2\r\n"),
+        Some(&[b"0,CONNECT\r\n"]),
+    ));
+
+    let mut socket = UdpClientStack::socket(adapter).unwrap();
+    adapter.bind(&mut socket, 7000).unwrap();
+
+    socket
+}
+
+/// Helper for opening & connecting a socket
+fn connect_socket(adapter: &mut AdapterType) -> Socket {
+    adapter.client.add_response(MockedCommand::ok(Some(b"AT+CIPMUX=1\r\n"), None));
+    adapter
+        .client
+        .add_response(MockedCommand::ok(Some(b"AT+CIPRECVMODE=1\r\n"), None));
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTART=0,\"UDP\",\"127.0.0.1\",5000\r\n"),
+        Some(&[b"0,CONNECT\r\n"]),
+    ));
+
+    let mut socket = UdpClientStack::socket(adapter).unwrap();
+    UdpClientStack::connect(adapter, &mut socket, SocketAddr::from_str("127.0.0.1:5000").unwrap()).unwrap();
+
+    socket
+}
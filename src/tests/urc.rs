@@ -1,5 +1,8 @@
 use crate::urc::URCMessages;
+use crate::wifi::MacAddress;
 use atat::{AtatUrc, Parser};
+use core::net::{Ipv4Addr, SocketAddr};
+use core::str::FromStr;
 use heapless::Vec;
 
 #[test]
@@ -78,6 +81,7 @@ fn test_first_parse_wifi() {
 fn test_first_parse_connection_connected() {
     assert_result(b"0,CONNECT\r\n", 11, b"0,CONNECT\r\nNEXT LINE\r\n");
     assert_result(b"0,CONNECT\r\n", 15, b"\r\n\r\n0,CONNECT\r\n");
+    assert_result(b"12,CONNECT\r\n", 12, b"12,CONNECT\r\nNEXT LINE\r\n");
 }
 
 #[test]
@@ -85,6 +89,13 @@ fn test_first_parse_connection_closed() {
     assert_result(b"0,CLOSED\r\n", 10, b"0,CLOSED\r\nNEXT LINE\r\n");
     assert_result(b"0,CLOSED\r\n", 12, b"\r\n0,CLOSED\r\n");
     assert_result(b"0,CLOSED\r\n", 14, b"\r\n\r\n0,CLOSED\r\n");
+    assert_result(b"12,CLOSED\r\n", 11, b"12,CLOSED\r\nNEXT LINE\r\n");
+}
+
+#[test]
+fn test_first_parse_connection_invalid_link_id() {
+    assert!(<URCMessages<32> as Parser>::parse(b"A,CONNECT\r\n").is_err());
+    assert!(<URCMessages<32> as Parser>::parse(b"+1,CLOSED\r\n").is_err());
 }
 
 #[test]
@@ -114,6 +125,49 @@ fn test_first_parse_send_fail() {
     assert_result(b"SEND FAIL\r\n", 17, b"\r\n\r\n\r\nSEND FAIL\r\n");
 }
 
+#[test]
+fn test_first_parse_time_updated() {
+    assert_result(b"+TIME_UPDATED\r\n", 15, b"+TIME_UPDATED\r\n");
+    assert_result(b"+TIME_UPDATED\r\n", 17, b"\r\n+TIME_UPDATED\r\nNEXT LINE\r\n");
+}
+
+#[test]
+fn test_first_parse_ping_timeout() {
+    assert_result(b"+PING:TIMEOUT\r\n", 15, b"+PING:TIMEOUT\r\n\r\nERROR\r\n");
+    assert!(<URCMessages<32> as Parser>::parse(b"+PING:37\r\n\r\nOK\r\n").is_err());
+}
+
+#[test]
+fn test_first_parse_station_events() {
+    assert_result(
+        b"+STA_CONNECTED:\"18:31:bf:4b:8a:f2\"\r\n",
+        36,
+        b"+STA_CONNECTED:\"18:31:bf:4b:8a:f2\"\r\nNEXT LINE\r\n",
+    );
+    assert_result(
+        b"+STA_DISCONNECTED:\"18:31:bf:4b:8a:f2\"\r\n",
+        41,
+        b"\r\n+STA_DISCONNECTED:\"18:31:bf:4b:8a:f2\"\r\n",
+    );
+    assert_result(
+        b"+DIST_STA_IP:\"18:31:bf:4b:8a:f2\",\"192.168.4.2\"\r\n",
+        48,
+        b"+DIST_STA_IP:\"18:31:bf:4b:8a:f2\",\"192.168.4.2\"\r\n",
+    );
+}
+
+#[test]
+fn test_first_parse_join_failure() {
+    assert_result(b"+CWJAP:2\r\n", 10, b"+CWJAP:2\r\n\r\nERROR\r\n");
+    assert_result(b"+CWJAP:1\r\n", 12, b"\r\n+CWJAP:1\r\n");
+}
+
+#[test]
+fn test_first_parse_join_query_response_no_match() {
+    let data = b"+CWJAP:\"test_wifi\",\"c4:6e:1f:a0:b1:c2\",6,-56\r\n\r\nOK\r\n";
+    assert!(<URCMessages<32> as Parser>::parse(data).is_err());
+}
+
 #[test]
 fn test_first_parse_data_available() {
     assert_result(b"+IPD,0,100\r\n", 12, b"+IPD,0,100\r\n");
@@ -121,6 +175,15 @@ fn test_first_parse_data_available() {
     assert_result(b"+IPD,0,100\r\n", 18, b"\r\n\r\n\r\n+IPD,0,100\r\n");
 }
 
+#[test]
+fn test_first_parse_data_available_remote_info() {
+    assert_result(
+        b"+IPD,0,100,\"10.0.0.1\",5000\r\n",
+        28,
+        b"+IPD,0,100,\"10.0.0.1\",5000\r\n",
+    );
+}
+
 #[test]
 fn test_first_parse_data_prefix_incomplete() {
     assert!(<URCMessages<32> as Parser>::parse(b"+CIPRECVDATA").is_err());
@@ -140,6 +203,15 @@ fn test_first_parse_data_serial_data_incomplete() {
     assert!(<URCMessages<32> as Parser>::parse(b"\r\n+CIPRECVDATA:5,abcd").is_err());
 }
 
+#[test]
+fn test_first_parse_data_remote_info_incomplete() {
+    assert!(<URCMessages<32> as Parser>::parse(b"+CIPRECVDATA:5,\"10.0.").is_err());
+    assert!(<URCMessages<32> as Parser>::parse(b"+CIPRECVDATA:5,\"10.0.0.1\"").is_err());
+    assert!(<URCMessages<32> as Parser>::parse(b"+CIPRECVDATA:5,\"10.0.0.1\",50").is_err());
+    assert!(<URCMessages<32> as Parser>::parse(b"+CIPRECVDATA:5,\"10.0.0.1\",5000,abcd").is_err());
+    assert!(<URCMessages<32> as Parser>::parse(b"+CIPRECVDATA,5,\"10.0.0.1\",5000:abcd").is_err());
+}
+
 #[test]
 fn test_first_parse_boot_incomplete() {
     assert!(<URCMessages<32> as Parser>::parse(b"ets Jan  8 2013,rst cause:1, boot mode:(3,7)").is_err());
@@ -177,6 +249,66 @@ fn test_first_parse_data_fully_received() {
     assert_result(b"+CIPRECVDATA:5,abcde", 24, b"\r\n\r\n+CIPRECVDATA:5,abcde");
 }
 
+#[test]
+fn test_first_parse_data_remote_info_fully_received() {
+    // Out of spec implementation for covering older ESP-AT version bug
+    assert_result(
+        b"+CIPRECVDATA,5,\"10.0.0.1\",5000:abcde",
+        36,
+        b"+CIPRECVDATA,5,\"10.0.0.1\",5000:abcde\r\n\r\nOK\r\n",
+    );
+
+    // Actual correct response according to spec
+    assert_result(
+        b"+CIPRECVDATA:5,\"10.0.0.1\",5000,abcde",
+        36,
+        b"+CIPRECVDATA:5,\"10.0.0.1\",5000,abcde\r\n\r\nOK\r\n",
+    );
+    assert_result(
+        b"+CIPRECVDATA:5,\"2001:db8::1\",5000,abcde",
+        39,
+        b"+CIPRECVDATA:5,\"2001:db8::1\",5000,abcde\r\n\r\nOK\r\n",
+    );
+}
+
+#[test]
+fn test_first_parse_data_starting_with_quote() {
+    assert_result(b"+CIPRECVDATA:4,\"ab\"", 19, b"+CIPRECVDATA:4,\"ab\"\r\n\r\nOK\r\n");
+    assert_result(b"+CIPRECVDATA:3,\"ab", 18, b"+CIPRECVDATA:3,\"ab\r\n\r\nOK\r\n");
+    assert_result(b"+CIPRECVDATA,4:\"ab\"", 19, b"+CIPRECVDATA,4:\"ab\"\r\n\r\nOK\r\n");
+}
+
+#[test]
+fn test_first_parse_active_data_fully_received() {
+    assert_result(b"+IPD,0,5:abcde", 14, b"+IPD,0,5:abcde");
+    assert_result(b"+IPD,0,5:abcde", 18, b"\r\n\r\n+IPD,0,5:abcde\r\nOK\r\n");
+    assert_result(b"+IPD,12,4:a\r\nb", 14, b"+IPD,12,4:a\r\nb+IPD,0,1:c");
+    assert_result(b"+IPD,0,3::,\"", 12, b"+IPD,0,3::,\"");
+}
+
+#[test]
+fn test_first_parse_active_data_remote_info_fully_received() {
+    assert_result(
+        b"+IPD,0,5,\"10.0.0.1\",5000:abcde",
+        30,
+        b"+IPD,0,5,\"10.0.0.1\",5000:abcde\r\n",
+    );
+    assert_result(
+        b"+IPD,0,5,\"2001:db8::1\",5000:abcde",
+        33,
+        b"+IPD,0,5,\"2001:db8::1\",5000:abcde",
+    );
+}
+
+#[test]
+fn test_first_parse_active_data_incomplete() {
+    assert!(<URCMessages<32> as Parser>::parse(b"+IPD,0,5").is_err());
+    assert!(<URCMessages<32> as Parser>::parse(b"+IPD,0,5:abcd").is_err());
+    assert!(<URCMessages<32> as Parser>::parse(b"\r\n+IPD,0,5:abcd").is_err());
+    assert!(<URCMessages<32> as Parser>::parse(b"+IPD,0,5,\"2001:db8").is_err());
+    assert!(<URCMessages<32> as Parser>::parse(b"+IPD,0,5,\"10.0.0.1\",5000:abcd").is_err());
+}
+
 #[test]
 fn test_second_parse_ready() {
     assert_eq!(
@@ -225,9 +357,19 @@ fn test_second_parse_socket_connected_valid_link_id() {
     );
 }
 
+#[test]
+fn test_second_parse_socket_connected_multi_digit_link_id() {
+    assert_eq!(
+        URCMessages::SocketConnected(12),
+        <URCMessages<32> as AtatUrc>::parse(b"12,CONNECT\r\n").unwrap()
+    );
+}
+
 #[test]
 fn test_second_parse_socket_connected_invalid_link_id() {
-    assert!(<URCMessages<32> as AtatUrc>::parse(b"5,CONNECT\r\n").is_none())
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"A,CONNECT\r\n").is_none());
+    assert!(<URCMessages<32> as AtatUrc>::parse(b",CONNECT\r\n").is_none());
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"+1,CONNECT\r\n").is_none());
 }
 
 #[test]
@@ -238,9 +380,18 @@ fn test_second_parse_socket_closed_valid_link_id() {
     );
 }
 
+#[test]
+fn test_second_parse_socket_closed_multi_digit_link_id() {
+    assert_eq!(
+        URCMessages::SocketClosed(10),
+        <URCMessages<32> as AtatUrc>::parse(b"10,CLOSED\r\n").unwrap()
+    );
+}
+
 #[test]
 fn test_second_parse_socket_closed_invalid_link_id() {
-    assert!(<URCMessages<32> as AtatUrc>::parse(b"5,CLOSED\r\n").is_none())
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"A,CLOSED\r\n").is_none());
+    assert!(<URCMessages<32> as AtatUrc>::parse(b",CLOSED\r\n").is_none());
 }
 
 #[test]
@@ -251,6 +402,22 @@ fn test_second_parse_already_connected() {
     );
 }
 
+#[test]
+fn test_second_parse_time_updated() {
+    assert_eq!(
+        URCMessages::TimeUpdated,
+        <URCMessages<32> as AtatUrc>::parse(b"+TIME_UPDATED\r\n").unwrap()
+    );
+}
+
+#[test]
+fn test_second_parse_ping_timeout() {
+    assert_eq!(
+        URCMessages::PingTimeout,
+        <URCMessages<32> as AtatUrc>::parse(b"+PING:TIMEOUT\r\n").unwrap()
+    );
+}
+
 #[test]
 fn test_second_parse_received_bytes_valid_byte_count() {
     assert_eq!(
@@ -281,18 +448,86 @@ fn test_second_parse_send_fail() {
     );
 }
 
+#[test]
+fn test_second_parse_join_failure() {
+    assert_eq!(
+        URCMessages::JoinFailed(3),
+        <URCMessages<32> as AtatUrc>::parse(b"+CWJAP:3\r\n").unwrap()
+    );
+    assert_eq!(
+        URCMessages::Unknown,
+        <URCMessages<32> as AtatUrc>::parse(b"+CWJAP:\"test_wifi\"\r\n").unwrap()
+    );
+}
+
+#[test]
+fn test_second_parse_station_connected() {
+    assert_eq!(
+        URCMessages::StationConnected(MacAddress([0x18, 0x31, 0xbf, 0x4b, 0x8a, 0xf2])),
+        <URCMessages<32> as AtatUrc>::parse(b"+STA_CONNECTED:\"18:31:bf:4b:8a:f2\"\r\n").unwrap()
+    );
+}
+
+#[test]
+fn test_second_parse_station_disconnected() {
+    assert_eq!(
+        URCMessages::StationDisconnected(MacAddress([0x18, 0x31, 0xbf, 0x4b, 0x8a, 0xf2])),
+        <URCMessages<32> as AtatUrc>::parse(b"+STA_DISCONNECTED:\"18:31:bf:4b:8a:f2\"\r\n").unwrap()
+    );
+}
+
+#[test]
+fn test_second_parse_station_ip_assigned() {
+    assert_eq!(
+        URCMessages::StationIpAssigned(
+            MacAddress([0x18, 0x31, 0xbf, 0x4b, 0x8a, 0xf2]),
+            Ipv4Addr::new(192, 168, 4, 2)
+        ),
+        <URCMessages<32> as AtatUrc>::parse(b"+DIST_STA_IP:\"18:31:bf:4b:8a:f2\",\"192.168.4.2\"\r\n").unwrap()
+    );
+}
+
+#[test]
+fn test_second_parse_station_ip_assigned_invalid_ip() {
+    assert_eq!(
+        URCMessages::Unknown,
+        <URCMessages<32> as AtatUrc>::parse(b"+DIST_STA_IP:\"18:31:bf:4b:8a:f2\",\"192.168.4\"\r\n").unwrap()
+    );
+}
+
 #[test]
 fn test_second_parse_data_available_correct() {
     assert_eq!(
-        URCMessages::DataAvailable(3, 256),
+        URCMessages::DataAvailable(3, 256, None),
         <URCMessages<32> as AtatUrc>::parse(b"+IPD,3,256\r\n").unwrap()
     );
 }
 
+#[test]
+fn test_second_parse_data_available_remote_info() {
+    assert_eq!(
+        URCMessages::DataAvailable(3, 256, Some(SocketAddr::from_str("10.0.0.1:5000").unwrap())),
+        <URCMessages<32> as AtatUrc>::parse(b"+IPD,3,256,\"10.0.0.1\",5000\r\n").unwrap()
+    );
+    assert_eq!(
+        URCMessages::DataAvailable(3, 256, Some(SocketAddr::from_str("[2001:db8::1]:5000").unwrap())),
+        <URCMessages<32> as AtatUrc>::parse(b"+IPD,3,256,\"2001:db8::1\",5000\r\n").unwrap()
+    );
+}
+
+#[test]
+fn test_second_parse_data_available_invalid_remote_info() {
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"+IPD,3,256,\"10.0.0.1\"\r\n").is_none());
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"+IPD,3,256,\"10.0.0\",5000\r\n").is_none());
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"+IPD,3,256,\"10.0.0.1\",A\r\n").is_none());
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"+IPD,3,256,\"10.0.0.1\",5000,1\r\n").is_none());
+}
+
 #[test]
 fn test_second_parse_data_available_incomplete() {
     assert!(<URCMessages<32> as AtatUrc>::parse(b"+IPD,3,\r\n").is_none());
     assert!(<URCMessages<32> as AtatUrc>::parse(b"+IPD,,200\r\n").is_none());
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"+IPD,+3,200\r\n").is_none());
     assert!(<URCMessages<32> as AtatUrc>::parse(b"+IPD,3\r\n").is_none());
     assert!(<URCMessages<32> as AtatUrc>::parse(b"+IPD,\r\n").is_none());
     assert!(<URCMessages<32> as AtatUrc>::parse(b"+IPD\r\n").is_none());
@@ -306,21 +541,86 @@ fn test_second_parse_data_available_invalid_numbers() {
     assert!(<URCMessages<32> as AtatUrc>::parse(b"+IPD,0,-5\r\n").is_none());
 }
 
+#[test]
+fn test_second_parse_received_data() {
+    assert_eq!(
+        URCMessages::<32>::ReceivedData(3, Vec::from_slice(b"abcde").unwrap(), None, false),
+        <URCMessages<32> as AtatUrc>::parse(b"+IPD,3,5:abcde").unwrap()
+    );
+    assert_eq!(
+        URCMessages::<32>::ReceivedData(12, Vec::from_slice(b"a\r\n").unwrap(), None, false),
+        <URCMessages<32> as AtatUrc>::parse(b"+IPD,12,3:a\r\n").unwrap()
+    );
+}
+
+#[test]
+fn test_second_parse_received_data_remote_info() {
+    assert_eq!(
+        URCMessages::<32>::ReceivedData(
+            3,
+            Vec::from_slice(b"abcde").unwrap(),
+            Some(SocketAddr::from_str("10.0.0.1:5000").unwrap()),
+            false
+        ),
+        <URCMessages<32> as AtatUrc>::parse(b"+IPD,3,5,\"10.0.0.1\",5000:abcde").unwrap()
+    );
+    assert_eq!(
+        URCMessages::<32>::ReceivedData(
+            3,
+            Vec::from_slice(b"abcde").unwrap(),
+            Some(SocketAddr::from_str("[2001:db8::1]:5000").unwrap()),
+            false
+        ),
+        <URCMessages<32> as AtatUrc>::parse(b"+IPD,3,5,\"2001:db8::1\",5000:abcde").unwrap()
+    );
+}
+
+#[test]
+fn test_second_parse_received_data_invalid() {
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"+IPD,3,A:abcde").is_none());
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"+IPD,3,5:abcd").is_none());
+}
+
+#[test]
+fn test_second_parse_received_data_exceeding_rx_size() {
+    assert_eq!(
+        URCMessages::<4>::ReceivedData(3, Vec::from_slice(b"abcd").unwrap(), None, true),
+        <URCMessages<4> as AtatUrc>::parse(b"+IPD,3,5:abcde").unwrap()
+    );
+}
+
 #[test]
 fn test_second_parse_data() {
     // Out of spec implementation for covering older ESP-AT version bug
     assert_eq!(
-        URCMessages::<32>::Data(Vec::from_slice(b"abcde").unwrap()),
+        URCMessages::<32>::Data(Vec::from_slice(b"abcde").unwrap(), None),
         <URCMessages<32> as AtatUrc>::parse(b"+CIPRECVDATA,5:abcde").unwrap()
     );
 
     // Actual correct response according to spec
     assert_eq!(
-        URCMessages::<32>::Data(Vec::from_slice(b"abcde").unwrap()),
+        URCMessages::<32>::Data(Vec::from_slice(b"abcde").unwrap(), None),
         <URCMessages<32> as AtatUrc>::parse(b"+CIPRECVDATA:5,abcde").unwrap()
     );
 }
 
+#[test]
+fn test_second_parse_data_remote_info() {
+    let remote = Some(SocketAddr::from_str("10.0.0.1:5000").unwrap());
+
+    // Out of spec implementation for covering older ESP-AT version bug
+    assert_eq!(
+        URCMessages::<32>::Data(Vec::from_slice(b"abcde").unwrap(), remote),
+        <URCMessages<32> as AtatUrc>::parse(b"+CIPRECVDATA,5,\"10.0.0.1\",5000:abcde").unwrap()
+    );
+
+    // Actual correct response according to spec
+    assert_eq!(
+        URCMessages::<32>::Data(Vec::from_slice(b"abcde").unwrap(), remote),
+        <URCMessages<32> as AtatUrc>::parse(b"+CIPRECVDATA:5,\"10.0.0.1\",5000,abcde").unwrap()
+    );
+}
+
 #[test]
 fn test_second_parse_longer_then_block_size() {
     // Basically this can just happen if ESP-AT sends more data then requested, which is a protocol violation
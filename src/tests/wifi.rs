@@ -1,10 +1,16 @@
 use crate::tests::mock::{MockAtatClient, MockTimer, MockedCommand};
 use crate::urc::URCMessages;
-use crate::wifi::{Adapter, JoinError};
-use crate::wifi::{CommandError, WifiAdapter};
+use crate::wifi::{
+    AccessPoint, AddressErrors, AuthMode, Cipher, CommandError, ConnectedApInfo, DhcpInterface, HostnameError,
+    MacAddress, ScanOptions, StationState, WifiAdapter, WifiMode,
+};
+use crate::wifi::{Adapter, JoinError, JoinFailureReason};
 use atat::Error;
+use core::net::{Ipv4Addr, Ipv6Addr};
+use core::str::FromStr;
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_sync::pubsub::PubSubChannel;
+use heapless::String;
 
 type AdapterType<'a> = Adapter<'a, MockAtatClient<'a>, MockTimer, 1_000_000, 32, 16, 16>;
 
@@ -373,3 +379,875 @@ fn test_set_auto_connect_correct_command() {
     adapter.set_auto_connect(false).unwrap();
     adapter.client.assert_all_cmds_sent();
 }
+
+#[test]
+fn test_scan_all_fields() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CWLAP\r\n"),
+        b"+CWLAP:(3,\"test_wifi\",-56,\"c4:6e:1f:a0:b1:c2\",6,-1,-1,4,4,7,1)\r\n+CWLAP:(0,\"open\",-80,\"10:fe:ed:05:ba:50\",11,0,0,0,0,3,0)\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let access_points = adapter.scan::<4>().unwrap();
+
+    assert_eq!(2, access_points.len());
+    assert_eq!(
+        AccessPoint {
+            auth_mode: Some(AuthMode::Wpa2Psk),
+            ssid: Some("test_wifi".try_into().unwrap()),
+            rssi: Some(-56),
+            mac: Some(MacAddress([0xc4, 0x6e, 0x1f, 0xa0, 0xb1, 0xc2])),
+            channel: Some(6),
+            freq_offset: Some(-1),
+            freqcal_val: Some(-1),
+            pairwise_cipher: Some(Cipher::Ccmp),
+            group_cipher: Some(Cipher::Ccmp),
+            bgn: Some(7),
+            wps: Some(true),
+        },
+        access_points[0]
+    );
+    assert_eq!(AuthMode::Open, access_points[1].auth_mode.unwrap());
+    assert_eq!("open", access_points[1].ssid.as_ref().unwrap().as_str());
+    assert_eq!(Some(false), access_points[1].wps);
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_scan_older_firmware_without_parentheses() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::new(
+        None,
+        b"+CWLAP:4,\"test_wifi\",-70,\"c4:6e:1f:a0:b1:c2\",1,-3,0\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let access_points = adapter.scan::<4>().unwrap();
+
+    assert_eq!(AuthMode::WpaWpa2Psk, access_points[0].auth_mode.unwrap());
+    assert_eq!(Some(-3), access_points[0].freq_offset);
+    assert_eq!(None, access_points[0].pairwise_cipher);
+    assert_eq!(None, access_points[0].wps);
+}
+
+#[test]
+fn test_scan_escaped_ssid() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::new(
+        None,
+        b"+CWLAP:(3,\"a,b\\\"c\\\\\",-56,\"c4:6e:1f:a0:b1:c2\",6,-1,-1,4,4,7,0)\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let access_points = adapter.scan::<4>().unwrap();
+
+    assert_eq!("a,b\"c\\", access_points[0].ssid.as_ref().unwrap().as_str());
+    assert_eq!(Some(-56), access_points[0].rssi);
+}
+
+#[test]
+fn test_scan_capacity_exceeded() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::new(
+        None,
+        b"+CWLAP:(3,\"first\",-50,\"c4:6e:1f:a0:b1:c2\",6,0,0,4,4,7,0)\r\n+CWLAP:(3,\"second\",-60,\"c4:6e:1f:a0:b1:c3\",6,0,0,4,4,7,0)\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let access_points = adapter.scan::<1>().unwrap();
+
+    assert_eq!(1, access_points.len());
+    assert_eq!("first", access_points[0].ssid.as_ref().unwrap().as_str());
+}
+
+#[test]
+fn test_scan_invalid_response() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::new(None, b"+CWLAP:(abc,\"test_wifi\",-56)\r\n", None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let error = adapter.scan::<4>().unwrap_err();
+
+    assert_eq!(CommandError::CommandFailed(Error::Parse), error);
+}
+
+#[test]
+fn test_scan_malformed_line_skipped() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::new(
+        None,
+        b"+CWLAP:(3,\"first\",-50,\"c4:6e:1f:a0:b1:c2\",6,0,0,4,4,7,0)\r\n+CWLAP:(abc,\"bad\",-56)\r\n+CWLAP:(3,\"second\",-60,\"c4:6e:1f:a0:b1:c3\",6,0,0,4,4,7,0)\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let access_points = adapter.scan::<4>().unwrap();
+
+    assert_eq!(2, access_points.len());
+    assert_eq!("first", access_points[0].ssid.as_ref().unwrap().as_str());
+    assert_eq!("second", access_points[1].ssid.as_ref().unwrap().as_str());
+}
+
+#[test]
+fn test_scan_command_error() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::error(Some(b"AT+CWLAP\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let error = adapter.scan::<4>().unwrap_err();
+
+    assert_eq!(CommandError::CommandFailed(Error::Parse), error);
+}
+
+#[test]
+fn test_set_scan_options_field_mask_applied() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWLAPOPT=1,6,-70\r\n"), None));
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CWLAP\r\n"),
+        b"+CWLAP:(\"test_wifi\",-56)\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter
+        .set_scan_options(ScanOptions {
+            sort_by_rssi: true,
+            fields: ScanOptions::FIELD_SSID | ScanOptions::FIELD_RSSI,
+            rssi_filter: Some(-70),
+            auth_mode_mask: None,
+        })
+        .unwrap();
+    let access_points = adapter.scan::<4>().unwrap();
+
+    assert_eq!(
+        AccessPoint {
+            ssid: Some("test_wifi".try_into().unwrap()),
+            rssi: Some(-56),
+            ..Default::default()
+        },
+        access_points[0]
+    );
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_set_scan_options_auth_mode_mask_default_rssi() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWLAPOPT=0,2047,-100,8\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter
+        .set_scan_options(ScanOptions {
+            auth_mode_mask: Some(1 << 3),
+            ..Default::default()
+        })
+        .unwrap();
+
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_set_scan_options_error() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::error(Some(b"AT+CWLAPOPT=0,2047\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let error = adapter.set_scan_options(ScanOptions::default()).unwrap_err();
+
+    assert_eq!(CommandError::CommandFailed(Error::Parse), error);
+}
+
+#[test]
+fn test_set_mode_correct_commands() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWMODE=0\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CWMODE=2\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CWMODE=1,0\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.set_mode(WifiMode::Null, None).unwrap();
+    adapter.set_mode(WifiMode::SoftAp, None).unwrap();
+    adapter.set_mode(WifiMode::Station, Some(false)).unwrap();
+
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_set_mode_error() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::error(Some(b"AT+CWMODE=3,1\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let error = adapter.set_mode(WifiMode::SoftApStation, Some(true)).unwrap_err();
+
+    assert_eq!(CommandError::CommandFailed(Error::Parse), error);
+}
+
+#[test]
+fn test_get_mode() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::new(Some(b"AT+CWMODE?\r\n"), b"+CWMODE:3\r\n", None));
+    client.add_response(MockedCommand::new(Some(b"AT+CWMODE?\r\n"), b"+CWMODE:0\r\n", None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(WifiMode::SoftApStation, adapter.get_mode().unwrap());
+    assert_eq!(WifiMode::Null, adapter.get_mode().unwrap());
+}
+
+#[test]
+fn test_get_mode_invalid_mode() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::new(Some(b"AT+CWMODE?\r\n"), b"+CWMODE:4\r\n", None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(
+        CommandError::CommandFailed(Error::Parse),
+        adapter.get_mode().unwrap_err()
+    );
+}
+
+#[test]
+fn test_join_soft_ap_mode_active() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::new(Some(b"AT+CWMODE?\r\n"), b"+CWMODE:2\r\n", None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CWMODE=3\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CWJAP=\"test_wifi\",\"secret\"\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CWJAP=\"test_wifi\",\"secret\"\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.get_mode().unwrap();
+
+    // Switching to SoftAP+Station mode, so that access point keeps running
+    adapter.join("test_wifi", "secret").unwrap();
+
+    // Mode is already correct
+    adapter.join("test_wifi", "secret").unwrap();
+
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_join_soft_ap_station_mode_active() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWMODE=3\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CWJAP=\"test_wifi\",\"secret\"\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.set_mode(WifiMode::SoftApStation, None).unwrap();
+    adapter.join("test_wifi", "secret").unwrap();
+
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_join_failure_reasons() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    let codes: [&'static [&'static [u8]]; 5] = [
+        &[b"+CWJAP:1\r\n"],
+        &[b"+CWJAP:2\r\n"],
+        &[b"+CWJAP:3\r\n"],
+        &[b"+CWJAP:4\r\n"],
+        &[b"+CWJAP:9\r\n"],
+    ];
+    for code in codes {
+        client.add_response(MockedCommand::ok(Some(b"AT+CWMODE=1\r\n"), None));
+        client.add_response(MockedCommand::error(
+            Some(b"AT+CWJAP=\"test_wifi\",\"secret\"\r\n"),
+            Some(code),
+        ));
+    }
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let expected = [
+        JoinFailureReason::Timeout,
+        JoinFailureReason::WrongPassword,
+        JoinFailureReason::AccessPointNotFound,
+        JoinFailureReason::ConnectionFailed,
+        JoinFailureReason::Unknown(9),
+    ];
+    for reason in expected {
+        assert_eq!(
+            JoinError::JoinFailed(reason),
+            adapter.join("test_wifi", "secret").unwrap_err()
+        );
+    }
+}
+
+#[test]
+fn test_join_failure_reason_of_previous_attempt_discarded() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWMODE=1\r\n"), None));
+    client.add_response(MockedCommand::error(
+        Some(b"AT+CWJAP=\"test_wifi\",\"secret\"\r\n"),
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.client.add_urc_message(b"+CWJAP:2\r\n");
+
+    assert_eq!(
+        JoinError::ConnectError(Error::Parse),
+        adapter.join("test_wifi", "secret").unwrap_err()
+    );
+}
+
+#[test]
+fn test_join_and_wait_already_completed() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CWJAP=\"test_wifi\",\"secret\"\r\n"),
+        Some(&[b"WIFI CONNECTED\r\n", b"WIFI GOT IP\r\n"]),
+    ));
+
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(1).returning(|duration| {
+        assert_eq!(duration, MockTimer::duration_ms(10_000));
+        Ok(())
+    });
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let state = adapter
+        .join_and_wait("test_wifi", "secret", MockTimer::duration_ms(10_000))
+        .unwrap();
+
+    assert!(state.connected);
+    assert!(state.ip_assigned);
+}
+
+#[test]
+fn test_join_and_wait_timeout() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CWJAP=\"test_wifi\",\"secret\"\r\n"),
+        Some(&[b"WIFI CONNECTED\r\n"]),
+    ));
+
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(1).returning(|_| Ok(()));
+    timer
+        .expect_wait()
+        .times(2)
+        .returning(|| nb::Result::Err(nb::Error::WouldBlock));
+    timer.expect_wait().times(1).returning(|| nb::Result::Ok(()));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let error = adapter
+        .join_and_wait("test_wifi", "secret", MockTimer::duration_ms(10_000))
+        .unwrap_err();
+
+    assert_eq!(JoinError::Timeout, error);
+}
+
+#[test]
+fn test_join_and_wait_timer_error() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWMODE=1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CWJAP=\"test_wifi\",\"secret\"\r\n"), None));
+
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(1).returning(|_| Ok(()));
+    timer.expect_wait().times(1).returning(|| nb::Result::Err(nb::Error::Other(1)));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let error = adapter
+        .join_and_wait("test_wifi", "secret", MockTimer::duration_ms(10_000))
+        .unwrap_err();
+
+    assert_eq!(JoinError::TimerError, error);
+}
+
+#[test]
+fn test_leave_wifi_disconnected() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(1).returning(|duration| {
+        assert_eq!(duration, MockTimer::duration_ms(5_000));
+        Ok(())
+    });
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.client.add_urc_wifi_connected();
+    adapter.client.add_urc_wifi_got_ip();
+    adapter.client.add_response(MockedCommand::ok(
+        Some(b"AT+CWQAP\r\n"),
+        Some(&[b"WIFI DISCONNECT\r\n"]),
+    ));
+    assert!(adapter.get_join_status().connected);
+
+    adapter.leave().unwrap();
+
+    assert!(!adapter.get_join_status().connected);
+    assert!(!adapter.get_join_status().ip_assigned);
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_leave_disconnect_timeout() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(1).returning(|_| Ok(()));
+    timer
+        .expect_wait()
+        .times(1)
+        .returning(|| nb::Result::Err(nb::Error::WouldBlock));
+    timer.expect_wait().times(1).returning(|| nb::Result::Ok(()));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.client.add_urc_wifi_connected();
+    adapter.client.add_response(MockedCommand::ok(Some(b"AT+CWQAP\r\n"), None));
+
+    let error = adapter.leave().unwrap_err();
+    assert_eq!(CommandError::DisconnectTimeout, error);
+}
+
+#[test]
+fn test_leave_command_error() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::error(Some(b"AT+CWQAP\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let error = adapter.leave().unwrap_err();
+
+    assert_eq!(CommandError::CommandFailed(Error::Parse), error);
+}
+
+#[test]
+fn test_get_access_point_info() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CWJAP?\r\n"),
+        b"+CWJAP:\"test\\,wifi\",\"ca:d7:19:d8:a6:44\",6,-52,0,1,3,0,1\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let info = adapter.get_access_point_info().unwrap().unwrap();
+
+    assert_eq!(
+        ConnectedApInfo {
+            ssid: String::try_from("test,wifi").unwrap(),
+            bssid: MacAddress([0xca, 0xd7, 0x19, 0xd8, 0xa6, 0x44]),
+            channel: 6,
+            rssi: -52,
+            pci_enabled: Some(false),
+            reconnect_interval: Some(1),
+            listen_interval: Some(3),
+            scan_mode: Some(0),
+            pmf: Some(1),
+        },
+        info
+    );
+}
+
+#[test]
+fn test_get_access_point_info_old_firmware() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CWJAP?\r\n"),
+        b"+CWJAP:\"test_wifi\",\"ca:d7:19:d8:a6:44\",11,-70\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let info = adapter.get_access_point_info().unwrap().unwrap();
+
+    assert_eq!("test_wifi", info.ssid.as_str());
+    assert_eq!(11, info.channel);
+    assert_eq!(-70, info.rssi);
+    assert_eq!(None, info.pci_enabled);
+    assert_eq!(None, info.pmf);
+}
+
+#[test]
+fn test_get_access_point_info_not_connected() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::new(Some(b"AT+CWJAP?\r\n"), b"No AP\r\n", None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(None, adapter.get_access_point_info().unwrap());
+}
+
+#[test]
+fn test_get_access_point_info_invalid_response() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CWJAP?\r\n"),
+        b"+CWJAP:\"test_wifi\",\"ca:d7:19:d8:a6:44\",invalid,-70\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(
+        CommandError::CommandFailed(Error::Parse),
+        adapter.get_access_point_info().unwrap_err()
+    );
+}
+
+#[test]
+fn test_get_station_state_syncs_join_status() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CWSTATE?\r\n"),
+        b"+CWSTATE:2,\"test_wifi\"\r\n",
+        None,
+    ));
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CWSTATE?\r\n"),
+        b"+CWSTATE:1,\"test_wifi\"\r\n",
+        None,
+    ));
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CWSTATE?\r\n"),
+        b"+CWSTATE:3,\"test_wifi\"\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    assert_eq!(StationState::IpAssigned, adapter.get_station_state().unwrap());
+    let status = adapter.get_join_status();
+    assert!(status.connected);
+    assert!(status.ip_assigned);
+
+    assert_eq!(StationState::Connected, adapter.get_station_state().unwrap());
+    let status = adapter.get_join_status();
+    assert!(status.connected);
+    assert!(!status.ip_assigned);
+
+    assert_eq!(StationState::Connecting, adapter.get_station_state().unwrap());
+    let status = adapter.get_join_status();
+    assert!(!status.connected);
+    assert!(!status.ip_assigned);
+}
+
+#[test]
+fn test_get_station_state_not_started() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CWSTATE?\r\n"),
+        b"+CWSTATE:0,\"\"\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.client.add_urc_wifi_connected();
+
+    assert_eq!(StationState::NotStarted, adapter.get_station_state().unwrap());
+    assert!(!adapter.get_join_status().connected);
+}
+
+#[test]
+fn test_get_station_state_command_error() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::error(Some(b"AT+CWSTATE?\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(
+        CommandError::CommandFailed(Error::Parse),
+        adapter.get_station_state().unwrap_err()
+    );
+}
+
+#[test]
+fn test_set_dhcp() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWDHCP=0,1\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CWDHCP=1,2\r\n"), None));
+    client.add_response(MockedCommand::ok(Some(b"AT+CWDHCP=1,3\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.set_dhcp(DhcpInterface::Station, false).unwrap();
+    adapter.set_dhcp(DhcpInterface::SoftAp, true).unwrap();
+    adapter.set_dhcp(DhcpInterface::Both, true).unwrap();
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_set_dhcp_command_error() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::error(Some(b"AT+CWDHCP=0,1\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(
+        CommandError::CommandFailed(Error::Parse),
+        adapter.set_dhcp(DhcpInterface::Station, false).unwrap_err()
+    );
+}
+
+#[test]
+fn test_set_static_ip() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::ok(
+        Some(b"AT+CIPSTA=\"10.0.0.100\",\"10.0.0.1\",\"255.255.255.0\"\r\n"),
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter
+        .set_static_ip(
+            Ipv4Addr::new(10, 0, 0, 100),
+            Ipv4Addr::new(10, 0, 0, 1),
+            Ipv4Addr::new(255, 255, 255, 0),
+        )
+        .unwrap();
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_set_static_ip_command_error() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::error(
+        Some(b"AT+CIPSTA=\"10.0.0.100\",\"10.0.0.1\",\"255.255.255.0\"\r\n"),
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let error = adapter
+        .set_static_ip(
+            Ipv4Addr::new(10, 0, 0, 100),
+            Ipv4Addr::new(10, 0, 0, 1),
+            Ipv4Addr::new(255, 255, 255, 0),
+        )
+        .unwrap_err();
+    assert_eq!(CommandError::CommandFailed(Error::Parse), error);
+}
+
+#[test]
+fn test_get_ip_config() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CIPSTA?\r\n"),
+        b"+CIPSTA:ip:\"10.0.0.100\"\r\n+CIPSTA:gateway:\"10.0.0.1\"\r\n+CIPSTA:netmask:\"255.255.255.0\"\r\n+CIPSTA:ip6ll:\"fe80::e6fe:d8ff:fe2a:a58\"\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    let address = adapter.get_ip_config().unwrap();
+
+    assert_eq!(Some(Ipv4Addr::new(10, 0, 0, 100)), address.ipv4);
+    assert_eq!(Some(Ipv4Addr::new(10, 0, 0, 1)), address.gateway);
+    assert_eq!(Some(Ipv4Addr::new(255, 255, 255, 0)), address.netmask);
+    assert_eq!(
+        Some(Ipv6Addr::from_str("fe80::e6fe:d8ff:fe2a:a58").unwrap()),
+        address.ipv6_link_local
+    );
+    assert_eq!(None, address.ipv6_global);
+    assert_eq!(None, address.mac);
+}
+
+#[test]
+fn test_get_ip_config_invalid_address() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CIPSTA?\r\n"),
+        b"+CIPSTA:ip:\"10.0.0.100\"\r\n+CIPSTA:gateway:\"10.0.0.256\"\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(AddressErrors::AddressParseError, adapter.get_ip_config().unwrap_err());
+}
+
+#[test]
+fn test_get_ip_config_command_error() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::error(Some(b"AT+CIPSTA?\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(
+        AddressErrors::CommandError(Error::Parse),
+        adapter.get_ip_config().unwrap_err()
+    );
+}
+
+#[test]
+fn test_set_hostname() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::ok(Some(b"AT+CWHOSTNAME=\"sensor-01\"\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    adapter.set_hostname("sensor-01").unwrap();
+    adapter.client.assert_all_cmds_sent();
+}
+
+#[test]
+fn test_set_hostname_invalid_length() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    assert_eq!(HostnameError::InvalidLength, adapter.set_hostname("").unwrap_err());
+    assert_eq!(
+        HostnameError::InvalidLength,
+        adapter.set_hostname("abcdefghijklmnopqrstuvwxyz0123456").unwrap_err()
+    );
+}
+
+#[test]
+fn test_set_hostname_invalid_characters() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+
+    assert_eq!(
+        HostnameError::InvalidCharacters,
+        adapter.set_hostname("sensor 01").unwrap_err()
+    );
+    assert_eq!(
+        HostnameError::InvalidCharacters,
+        adapter.set_hostname("sensor\"01").unwrap_err()
+    );
+}
+
+#[test]
+fn test_set_hostname_command_error() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::error(Some(b"AT+CWHOSTNAME=\"sensor-01\"\r\n"), None));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!(
+        HostnameError::CommandFailed(Error::Parse),
+        adapter.set_hostname("sensor-01").unwrap_err()
+    );
+}
+
+#[test]
+fn test_get_hostname() {
+    let channel: PubSubChannel<CriticalSectionRawMutex, URCMessages<16>, 16, 1, 1> = PubSubChannel::new();
+    let mut client = MockAtatClient::new(&channel);
+    let timer = MockTimer::new();
+
+    client.add_response(MockedCommand::new(
+        Some(b"AT+CWHOSTNAME?\r\n"),
+        b"+CWHOSTNAME:espressif\r\n",
+        None,
+    ));
+
+    let mut adapter: AdapterType = Adapter::new(client, channel.subscriber().unwrap(), timer);
+    assert_eq!("espressif", adapter.get_hostname().unwrap().as_str());
+}
@@ -0,0 +1,142 @@
+//! # TLS client connections
+//!
+//! ESP-AT supports encrypted client connections by the SSL link type. Once connected, TLS sockets are used like
+//! regular TCP sockets by [TcpClientStack](embedded_nal::TcpClientStack), e.g. for sending and receiving data.
+//!
+//! Certificates and private keys need to be flashed to the ESP-AT in advance. The configured verification mode
+//! references them by their index, s. [TlsAuthMode].
+//!
+//! ## Example
+//!
+//! ````
+//! # use core::str::FromStr;
+//! # use core::net::SocketAddr;
+//! # use embedded_nal::TcpClientStack;
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::tls::{TlsAuthMode, TlsConfig};
+//! # use esp_at_nal::wifi::Adapter;
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let urc_channel = AtClient::urc_channel();
+//! let client = AtClient::init(&urc_channel);
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 128, 8> = Adapter::new(client, urc_channel.subscriber().unwrap(), ExampleTimer::default());
+//!
+//! // Verifying the server certificate by the first CA certificate
+//! let config = TlsConfig {
+//!     auth_mode: TlsAuthMode::ServerVerification { ca_number: 0 },
+//!     sni: Some("example.org"),
+//!     alpn: &["http/1.1"],
+//! };
+//!
+//! let mut socket = adapter.socket().unwrap();
+//! adapter.connect_tls(&mut socket, SocketAddr::from_str("10.0.0.1:443").unwrap(), &config).unwrap();
+//!
+//! // Sending some data
+//! adapter.send(&mut socket, b"hallo!").unwrap();
+//! ````
+
+use crate::commands::{ConnectCommand, SslAlpnCommand, SslConfigurationCommand, SslServerNameCommand};
+use crate::queue::ReceiveQueue;
+use crate::stack::{Error, Socket};
+use crate::wifi::Adapter;
+use atat::blocking::AtatClient;
+use core::net::SocketAddr;
+use core::str::FromStr;
+use fugit_timer::Timer;
+use heapless::{String, Vec};
+
+/// Maximum length of the server name indication
+pub const MAX_SNI_LENGTH: usize = 128;
+
+/// Maximum number of ALPN protocols
+pub const MAX_ALPN_COUNT: usize = 3;
+
+/// Maximum length of a single ALPN protocol
+pub const MAX_ALPN_LENGTH: usize = 32;
+
+/// Certificate verification mode of a TLS connection
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TlsAuthMode {
+    /// No certificate verification
+    #[default]
+    None,
+
+    /// Client provides the certificate at the given index for the server to verify
+    ClientCertificate {
+        /// Index of the client certificate and private key, s. `client_cert` and `client_key` of the mfg partition
+        pki_number: usize,
+    },
+
+    /// Server certificate is verified by the CA certificate at the given index
+    ServerVerification {
+        /// Index of the CA certificate, s. `client_ca` of the mfg partition
+        ca_number: usize,
+    },
+
+    /// Both client and server certificates are verified
+    Mutual {
+        /// Index of the client certificate and private key, s. `client_cert` and `client_key` of the mfg partition
+        pki_number: usize,
+
+        /// Index of the CA certificate, s. `client_ca` of the mfg partition
+        ca_number: usize,
+    },
+}
+
+/// Configuration of a TLS connection
+#[derive(Clone, Debug, Default)]
+pub struct TlsConfig<'a> {
+    /// Certificate verification mode
+    pub auth_mode: TlsAuthMode,
+
+    /// Server name indication sent in ClientHello. If None, the SNI of the link is kept unchanged.
+    pub sni: Option<&'a str>,
+
+    /// Application layer protocols (ALPN) sent in ClientHello, at most [MAX_ALPN_COUNT]
+    pub alpn: &'a [&'a str],
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > Adapter<'_, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    /// Configures the link of the given socket and opens a TLS connection to the given remote.
+    /// Both IPv4 and IPv6 are supported.
+    ///
+    /// Failed handshakes or certificate verifications are returned as [Error::TlsConnectError].
+    /// Returns [Error::InvalidTlsConfig] if server name or ALPN protocols exceed the supported limits.
+    pub fn connect_tls(&mut self, socket: &mut Socket, remote: SocketAddr, config: &TlsConfig) -> Result<(), Error> {
+        self.process_urc_messages();
+        self.assert_socket_connectable(socket)?;
+
+        let sni = match config.sni {
+            None => None,
+            Some(sni) => Some(String::from_str(sni).map_err(|_| Error::InvalidTlsConfig)?),
+        };
+
+        let mut protocols = Vec::new();
+        for protocol in config.alpn {
+            let protocol = String::from_str(protocol).map_err(|_| Error::InvalidTlsConfig)?;
+            protocols.push(protocol).map_err(|_| Error::InvalidTlsConfig)?;
+        }
+
+        self.send_command(SslConfigurationCommand::new(socket.link_id, config.auth_mode))?;
+        if let Some(sni) = sni {
+            self.send_command(SslServerNameCommand::new(socket.link_id, sni))?;
+        }
+        self.send_command(SslAlpnCommand::new(socket.link_id, protocols))?;
+
+        let command = match remote {
+            SocketAddr::V4(address) => ConnectCommand::ssl_v4(socket.link_id, address),
+            SocketAddr::V6(address) => ConnectCommand::ssl_v6(socket.link_id, address),
+        };
+        self.open_connection(socket, Some(remote), command)
+    }
+}
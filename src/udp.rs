@@ -0,0 +1,203 @@
+//! # UDP stack
+//!
+//! This crate implements both [UdpClientStack] and [UdpFullStack] of [embedded_nal].
+//!
+//! UDP sockets share the same link ids as TCP sockets. So the maximum number of sockets (TCP + UDP) is
+//! limited by the `MAX_SOCKETS` generic of [Adapter], five by default.
+//! As [Adapter] implements both [TcpClientStack](embedded_nal::TcpClientStack) and [UdpClientStack], the
+//! stack needs to be specified explicitly if both traits are in scope, e.g. `UdpClientStack::socket(&mut adapter)`.
+//!
+//! ## Example
+//!
+//! ````
+//! # use core::str::FromStr;
+//! # use core::net::SocketAddr;
+//! # use embedded_nal::{UdpClientStack};
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::wifi::{Adapter, WifiAdapter};
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let urc_channel = AtClient::urc_channel();
+//! let client = AtClient::init(&urc_channel);
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 128, 8> = Adapter::new(client, urc_channel.subscriber().unwrap(), ExampleTimer::default());
+//!
+//! // Creating a UDP socket
+//! let mut  socket = adapter.socket().unwrap();
+//! adapter.connect(&mut socket, SocketAddr::from_str("10.0.0.1:5000").unwrap()).unwrap();
+//!
+//! // Sending a datagram
+//! adapter.send(&mut socket, b"hallo!").unwrap();
+//!
+//! // Receiving some data
+//! let mut  rx_buffer = [0x0; 64];
+//! let (length, remote) = adapter.receive(&mut socket, &mut rx_buffer).unwrap();
+//! assert_eq!(16, length);
+//! assert_eq!(b"nice to see you!", &rx_buffer[..16]);
+//! assert_eq!("10.0.0.1:5000", remote.to_string());
+//!
+//! // Closing socket
+//! adapter.close(socket).unwrap();
+//! ````
+//!
+//! ## Bound sockets
+//!
+//! Sockets bound to a local port by [bind()](UdpFullStack::bind) accept datagrams of any remote.
+//! The remote of received data is returned by `receive()`, so [send_to()](UdpFullStack::send_to) may reply to it.
+//! Binding is currently supported for IPv4 only.
+//!
+//! ````
+//! # use core::str::FromStr;
+//! # use core::net::SocketAddr;
+//! # use embedded_nal::{UdpClientStack, UdpFullStack};
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::wifi::{Adapter, WifiAdapter};
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let urc_channel = AtClient::urc_channel();
+//! let client = AtClient::init(&urc_channel);
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 128, 8> = Adapter::new(client, urc_channel.subscriber().unwrap(), ExampleTimer::default());
+//!
+//! // Listening on local port 7000
+//! let mut socket = adapter.socket().unwrap();
+//! adapter.bind(&mut socket, 7000).unwrap();
+//!
+//! // Receiving a datagram
+//! let mut  rx_buffer = [0x0; 32];
+//! let (length, remote) = adapter.receive(&mut socket, &mut rx_buffer).unwrap();
+//! assert_eq!(b"ping", &rx_buffer[..length]);
+//! assert_eq!("10.0.0.2:6000", remote.to_string());
+//!
+//! // Replying to the sender
+//! adapter.send_to(&mut socket, remote, b"pong").unwrap();
+//! ````
+
+use crate::commands::ConnectCommand;
+use crate::queue::ReceiveQueue;
+use crate::stack::{Error, Socket};
+use crate::wifi::Adapter;
+use atat::blocking::AtatClient;
+use atat::Error as AtError;
+use core::net::SocketAddr;
+use embedded_nal::{UdpClientStack, UdpFullStack};
+use fugit_timer::Timer;
+
+/// Max. number of datagrams buffered per socket in active receiving mode. If exceeded, further datagrams are dropped.
+pub(crate) const MAX_RX_DATAGRAMS: usize = 8;
+
+/// Datagram buffered in the receive queue of a socket in active receiving mode
+#[derive(Copy, Clone)]
+pub(crate) struct ReceivedDatagram {
+    /// Length of the datagram data not read yet
+    pub(crate) length: usize,
+
+    /// Remote address of the datagram, if CIPDINFO is enabled
+    pub(crate) remote: Option<SocketAddr>,
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > UdpClientStack for Adapter<'_, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    type UdpSocket = Socket;
+    type Error = Error;
+
+    /// Opens and returns a new socket
+    /// Up to `MAX_SOCKETS` parallel sockets (TCP + UDP) are supported. If not socket is available [Error::NoSocketAvailable] is returned.
+    ///
+    /// On first call ESP-AT is configured to support multiple connections.
+    fn socket(&mut self) -> Result<Self::UdpSocket, Self::Error> {
+        self.enable_multiple_connections()?;
+        self.open_socket()
+    }
+
+    /// Opens a new UDP transmission to the given remote. Both IPv4 and IPv6 are supported.
+    /// Returns [Error::AlreadyConnected] if socket is already connected.
+    ///
+    /// On first call ESP-AT is configured for the socket receiving mode, s. [set_receive_mode()](Adapter::set_receive_mode).
+    /// In active receiving mode up to eight datagrams are buffered per socket, s. [receive()](UdpClientStack::receive).
+    fn connect(&mut self, socket: &mut Self::UdpSocket, remote: SocketAddr) -> Result<(), Self::Error> {
+        let command = match remote {
+            SocketAddr::V4(address) => ConnectCommand::udp_v4(socket.link_id, address),
+            SocketAddr::V6(address) => ConnectCommand::udp_v6(socket.link_id, address),
+        };
+
+        self.open_connection(socket, Some(remote), command)
+    }
+
+    /// Sends the given buffer as a single datagram.
+    /// The data is written in blocks of TX_SIZE, but transmitted by the ESP-AT at once.
+    fn send(&mut self, socket: &mut Self::UdpSocket, buffer: &[u8]) -> nb::Result<(), Self::Error> {
+        self.send_datagram(socket, None, buffer)
+    }
+
+    /// Receives data (if available) and writes it to the given buffer.
+    /// Returns the received length and the remote address of the data.
+    ///
+    /// The remote address is reported by ESP-AT for bound sockets. For connected sockets the
+    /// remote address of the connection is returned otherwise.
+    ///
+    /// Just a single block is read per call, so that data of different remotes is not merged. The block size
+    /// is defined by the generic constant RX_SIZE. In active receiving mode a single datagram is read per call
+    /// instead. If the buffer is too small, the rest of the datagram is returned by the next call.
+    fn receive(
+        &mut self,
+        socket: &mut Self::UdpSocket,
+        buffer: &mut [u8],
+    ) -> nb::Result<(usize, SocketAddr), Self::Error> {
+        let length = self.receive_data(socket, buffer, true)?;
+
+        match self.session.data_remote.or(self.session.sockets[socket.link_id].remote) {
+            None => nb::Result::Err(nb::Error::Other(Error::ReceiveFailed(AtError::InvalidResponse))),
+            Some(remote) => nb::Result::Ok((length, remote)),
+        }
+    }
+
+    /// Closes a socket
+    ///
+    /// If the socket is not connected, no command is sent to the ESP-AT but only the internal status is set.
+    /// In case of an error (which is returned) the socket is internally set to closed so that it is not lost and can be reused.
+    fn close(&mut self, socket: Self::UdpSocket) -> Result<(), Self::Error> {
+        self.close_socket(&socket)
+    }
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const URC_CAPACITY: usize,
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > UdpFullStack for Adapter<'_, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
+{
+    /// Binds the socket to the given local port, accepting datagrams of any remote (IPv4 only).
+    /// Returns [Error::AlreadyConnected] if socket is already connected or bound.
+    ///
+    /// On first call ESP-AT is configured to report the remote address of received data.
+    fn bind(&mut self, socket: &mut Self::UdpSocket, local_port: u16) -> Result<(), Self::Error> {
+        self.enable_remote_info()?;
+
+        let command = ConnectCommand::udp_bind(socket.link_id, local_port);
+        self.open_connection(socket, None, command)
+    }
+
+    /// Sends the given buffer as a single datagram to the given remote.
+    /// Socket needs to be bound by [bind()](UdpFullStack::bind) first.
+    fn send_to(
+        &mut self,
+        socket: &mut Self::UdpSocket,
+        remote: SocketAddr,
+        buffer: &[u8],
+    ) -> nb::Result<(), Self::Error> {
+        self.send_datagram(socket, Some(remote), buffer)
+    }
+}
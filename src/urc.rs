@@ -2,9 +2,13 @@
 //!
 //! This is just used internally, but needs to be public for passing [URCMessages] as a generic to
 //! [AtDigester](atat::digest::AtDigester): `AtDigester<URCMessages>`.
+use crate::responses::unquote;
+use crate::wifi::MacAddress;
 use atat::digest::ParseError;
 use atat::{AtatUrc, Parser};
-use heapless::Vec;
+use core::net::{IpAddr, Ipv4Addr, SocketAddr};
+use core::str::FromStr;
+use heapless::{String, Vec};
 
 /// URC definitions, needs to passed as generic of [AtDigester](atat::digest::AtDigester): `AtDigester<URCMessages>`
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -30,10 +34,27 @@ pub enum URCMessages<const RX_SIZE: usize> {
     /// Transmission of socket data failed
     SendFail,
     /// Data is available in passive receiving mode.
-    /// First value = link_id, Second value = available byte count
-    DataAvailable(usize, usize),
+    /// First value = link_id, Second value = available byte count, Third value = remote address (if CIPDINFO is enabled)
+    DataAvailable(usize, usize, Option<SocketAddr>),
     /// Received the following data requested by CIPRECVDATA command.
-    Data(Vec<u8, RX_SIZE>),
+    /// Second value = remote address of the data (if CIPDINFO is enabled)
+    Data(Vec<u8, RX_SIZE>, Option<SocketAddr>),
+    /// Received socket data in active receiving mode.
+    /// First value = link_id, Second value = data, Third value = remote address (if CIPDINFO is enabled),
+    /// Fourth value = true if the data was truncated, as it exceeded RX_SIZE
+    ReceivedData(usize, Vec<u8, RX_SIZE>, Option<SocketAddr>, bool),
+    /// Joining the access point failed with the given +CWJAP error code
+    JoinFailed(u8),
+    /// Station with the given MAC address joined the SoftAP
+    StationConnected(MacAddress),
+    /// Station with the given MAC address left the SoftAP
+    StationDisconnected(MacAddress),
+    /// SoftAP assigned the given IP to the station with the given MAC address
+    StationIpAssigned(MacAddress, Ipv4Addr),
+    /// Time was synchronized by SNTP
+    TimeUpdated,
+    /// Remote host did not respond to the PING command, followed by an ERROR response
+    PingTimeout,
     /// Unknown URC message
     Unknown,
 }
@@ -43,18 +64,34 @@ impl<const RX_SIZE: usize> AtatUrc for URCMessages<RX_SIZE> {
 
     fn parse(resp: &[u8]) -> Option<Self::Response> {
         if &resp[..4] == b"+IPD" {
+            if ActiveDataMatcher::matches(resp).is_some() {
+                return URCMessages::parse_received_data(resp);
+            }
+
             return URCMessages::parse_data_available(resp);
         }
 
         if resp.len() > 15 && &resp[..12] == b"+CIPRECVDATA" && (resp[12] == b',' || resp[12] == b':') {
             let message = DataResponseParser::new(resp).parse().ok()?;
-            return Some(Self::Data(message.to_vec()?));
+            return Some(Self::Data(message.to_vec()?, message.remote));
+        }
+
+        if let Some(link_id) = resp.strip_suffix(b",CONNECT\r\n") {
+            return Some(Self::SocketConnected(URCMessages::<8>::parse_link_id(link_id)?));
         }
 
-        match &resp[1..resp.len() - 2] {
-            b",CONNECT" => return Some(Self::SocketConnected(URCMessages::<8>::parse_link_id(resp[0])?)),
-            b",CLOSED" => return Some(Self::SocketClosed(URCMessages::<8>::parse_link_id(resp[0])?)),
-            _ => {}
+        if let Some(link_id) = resp.strip_suffix(b",CLOSED\r\n") {
+            return Some(Self::SocketClosed(URCMessages::<8>::parse_link_id(link_id)?));
+        }
+
+        if resp[0] == b'+' {
+            if let Some(message) = URCMessages::parse_station_event(resp) {
+                return Some(message);
+            }
+
+            if let Some(code) = URCMessages::<8>::parse_join_failure(resp) {
+                return Some(Self::JoinFailed(code));
+            }
         }
 
         if &resp[..4] == b"Recv" {
@@ -69,22 +106,22 @@ impl<const RX_SIZE: usize> AtatUrc for URCMessages<RX_SIZE> {
             b"WIFI DISCONNECT" => Some(Self::WifiDisconnected),
             b"WIFI GOT IP" => Some(Self::ReceivedIP),
             b"ALREADY CONNECTED" => Some(Self::AlreadyConnected),
+            b"+TIME_UPDATED" => Some(Self::TimeUpdated),
+            b"+PING:TIMEOUT" => Some(Self::PingTimeout),
             _ => Some(Self::Unknown),
         }
     }
 }
 
 impl<const RX_SIZE: usize> URCMessages<RX_SIZE> {
-    /// Parses the socket id. Currently supports just socket 0-4
-    fn parse_link_id(link_id: u8) -> Option<usize> {
-        match link_id {
-            0x30 => Some(0),
-            0x31 => Some(1),
-            0x32 => Some(2),
-            0x33 => Some(3),
-            0x34 => Some(4),
-            _ => None,
+    /// Parses the socket id, which may consist of multiple digits.
+    /// Link ids exceeding the socket count of the adapter are ignored by the session.
+    fn parse_link_id(link_id: &[u8]) -> Option<usize> {
+        if link_id.is_empty() || !link_id.iter().all(|char| char.is_ascii_digit()) {
+            return None;
         }
+
+        core::str::from_utf8(link_id).ok()?.parse().ok()
     }
 
     /// Tries to parse the N byte count of 'Recv N bytes'
@@ -101,16 +138,87 @@ impl<const RX_SIZE: usize> URCMessages<RX_SIZE> {
         None
     }
 
-    /// Parses the +IPD message
+    /// Parses the +IPD message, e.g. `+IPD,0,100` or `+IPD,0,100,"10.0.0.1",5000` if CIPDINFO is enabled
     fn parse_data_available(data: &[u8]) -> Option<Self> {
         let string = core::str::from_utf8(&data[..data.len() - 2]).ok()?;
         let mut parts = string.split(',');
 
-        let link_id = parts.nth(1)?.parse().ok()?;
-        let length = parts.last()?.parse().ok()?;
+        let link_id = URCMessages::<8>::parse_link_id(parts.nth(1)?.as_bytes())?;
+        let length = parts.next()?.parse().ok()?;
 
-        Some(Self::DataAvailable(link_id, length))
+        let remote = match (parts.next(), parts.next()) {
+            (Some(ip), Some(port)) => Some(parse_remote(ip, port)?),
+            (None, None) => None,
+            _ => return None,
+        };
+
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(Self::DataAvailable(link_id, length, remote))
     }
+
+    /// Parses the +IPD message of active receiving mode, e.g. `+IPD,0,4:data` or `+IPD,0,4,"10.0.0.1",5000:data`.
+    /// Data exceeding RX_SIZE is truncated, so that the loss can be signaled to the receiver.
+    fn parse_received_data(data: &[u8]) -> Option<Self> {
+        let matcher = ActiveDataMatcher::matches(data)?;
+        let message = matcher.parse().ok()?;
+        let link_id = URCMessages::<8>::parse_link_id(matcher.link_id())?;
+
+        let truncated = message.data.len() > RX_SIZE;
+        let data = Vec::from_slice(&message.data[..message.data.len().min(RX_SIZE)]).ok()?;
+
+        Some(Self::ReceivedData(link_id, data, message.remote, truncated))
+    }
+
+    /// Parses the error code of `+CWJAP:<code>`
+    fn parse_join_failure(data: &[u8]) -> Option<u8> {
+        let string = core::str::from_utf8(&data[..data.len() - 2]).ok()?;
+        let code = string.strip_prefix("+CWJAP:")?;
+
+        if code.is_empty() || !code.bytes().all(|char| char.is_ascii_digit()) {
+            return None;
+        }
+
+        code.parse().ok()
+    }
+
+    /// Parses the station events of SoftAP mode, e.g. `+STA_CONNECTED:"18:31:bf:4b:8a:f2"`
+    fn parse_station_event(data: &[u8]) -> Option<Self> {
+        let string = core::str::from_utf8(&data[..data.len() - 2]).ok()?;
+
+        if let Some(mac) = string.strip_prefix("+STA_CONNECTED:") {
+            return Some(Self::StationConnected(parse_mac(mac)?));
+        }
+
+        if let Some(mac) = string.strip_prefix("+STA_DISCONNECTED:") {
+            return Some(Self::StationDisconnected(parse_mac(mac)?));
+        }
+
+        let (mac, ip) = string.strip_prefix("+DIST_STA_IP:")?.split_once(',')?;
+        let ip: String<15> = parse_quoted(ip)?;
+
+        Some(Self::StationIpAssigned(
+            parse_mac(mac)?,
+            Ipv4Addr::from_str(ip.as_str()).ok()?,
+        ))
+    }
+}
+
+/// Parses a (optionally quoted) string value
+fn parse_quoted<const N: usize>(value: &str) -> Option<String<N>> {
+    String::from_str(unquote(value)).ok()
+}
+
+/// Parses a (optionally quoted) MAC address
+fn parse_mac(value: &str) -> Option<MacAddress> {
+    MacAddress::from_str(unquote(value)).ok()
+}
+
+/// Parses a (optionally quoted) remote IP + port pair, as appended by CIPDINFO
+fn parse_remote(ip: &str, port: &str) -> Option<SocketAddr> {
+    Some(SocketAddr::new(IpAddr::from_str(unquote(ip)).ok()?, port.parse().ok()?))
 }
 
 impl<const RX_SIZE: usize> Parser for URCMessages<RX_SIZE> {
@@ -123,6 +231,10 @@ impl<const RX_SIZE: usize> Parser for URCMessages<RX_SIZE> {
             return matcher.handle();
         }
 
+        if let Some(matcher) = ActiveDataMatcher::matches(buf) {
+            return matcher.handle();
+        }
+
         if let Ok(result) = LineBasedMatcher::new(buf).handle() {
             return Ok(result);
         }
@@ -161,11 +273,77 @@ impl<'a> SizeBasedMatcher<'a> {
         let data = &self.buffer[self.start..];
         let message = DataResponseParser::new(data).parse()?;
 
-        let total_length = self.start + 13 + message.length_str.len() + 1 + message.length;
+        let total_length = self.start + message.data_start + message.length;
         Ok((&data[..total_length - self.start], total_length))
     }
 }
 
+/// Matches length defined URC message +IPD of active receiving mode, e.g. `+IPD,0,4:data`.
+/// +IPD messages of passive receiving mode don't contain any data and are matched by [LineBasedMatcher].
+struct ActiveDataMatcher<'a> {
+    buffer: &'a [u8],
+
+    /// First index where the actual message starts
+    start: usize,
+
+    /// Index of the length, relative to start
+    length_start: usize,
+}
+
+impl<'a> ActiveDataMatcher<'a> {
+    /// Returns Self if buffer contains a +IPD message followed by data
+    pub fn matches(buffer: &'a [u8]) -> Option<Self> {
+        let start = buffer.iter().position(|x| x != &b'\r' && x != &b'\n')?;
+
+        let data = &buffer[start..];
+        if data.len() < 9 || &data[..5] != b"+IPD," {
+            return None;
+        }
+
+        let link_id_end = data[5..].iter().position(|x| !x.is_ascii_digit())? + 5;
+        if link_id_end == 5 || data[link_id_end] != b',' {
+            return None;
+        }
+
+        // Header is terminated by a colon, which may also be part of a quoted IPv6 remote address
+        let mut quoted = false;
+        for char in &data[link_id_end + 1..] {
+            match char {
+                b'"' => quoted = !quoted,
+                b':' if !quoted => {
+                    return Some(Self {
+                        buffer,
+                        start,
+                        length_start: link_id_end + 1,
+                    })
+                }
+                b'\r' | b'\n' => return None,
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    /// Parses the message and checks if data is complete
+    pub fn handle(self) -> Result<(&'a [u8], usize), ParseError> {
+        let message = self.parse()?;
+
+        let total_length = self.start + message.data_start + message.length;
+        Ok((&self.buffer[self.start..total_length], total_length))
+    }
+
+    /// Decodes the length, the optional remote address and the data
+    fn parse(&self) -> Result<DataMessage<'a>, ParseError> {
+        DataResponseParser::active(&self.buffer[self.start..], self.length_start).parse()
+    }
+
+    /// Returns the encoded link id
+    fn link_id(&self) -> &'a [u8] {
+        &self.buffer[self.start + 5..self.start + self.length_start - 1]
+    }
+}
+
 /// Matches regular CRLF terminated URC messages
 struct LineBasedMatcher<'a> {
     buffer: &'a [u8],
@@ -226,12 +404,34 @@ impl<'a> LineBasedMatcher<'a> {
             || &line[..4] == "+IPD"
             || line == "SEND OK"
             || line == "SEND FAIL"
-            || &line[1..] == ",CONNECT"
-            || &line[1..] == ",CLOSED"
+            || self.matches_socket_state(line)
             || line == "ALREADY CONNECTED"
+            || line == "+TIME_UPDATED"
+            || line == "+PING:TIMEOUT"
+            || line.starts_with("+STA_CONNECTED:")
+            || line.starts_with("+STA_DISCONNECTED:")
+            || line.starts_with("+DIST_STA_IP:")
+            || self.matches_join_failure(line)
             || self.matches_receive_confirmation(line)
     }
 
+    /// Returns true if line is matching a connection state change of a socket, e.g. "0,CONNECT" or "12,CLOSED"
+    fn matches_socket_state(&self, line: &str) -> bool {
+        match line.strip_suffix(",CONNECT").or_else(|| line.strip_suffix(",CLOSED")) {
+            None => false,
+            Some(link_id) => !link_id.is_empty() && link_id.bytes().all(|char| char.is_ascii_digit()),
+        }
+    }
+
+    /// Returns true if line is matching a join error code, e.g. "+CWJAP:2".
+    /// Query responses of CWJAP are not matched, as they start with a quoted SSID.
+    fn matches_join_failure(&self, line: &str) -> bool {
+        match line.strip_prefix("+CWJAP:") {
+            None => false,
+            Some(code) => !code.is_empty() && code.bytes().all(|char| char.is_ascii_digit()),
+        }
+    }
+
     /// Returns true if line is matching a receive confirmation e.g. "Recv 9 bytes"
     fn matches_receive_confirmation(&self, line: &str) -> bool {
         if line.len() < 12 {
@@ -247,10 +447,13 @@ impl<'a> LineBasedMatcher<'a> {
     }
 }
 
-/// Decodes a +CIPRECVDATA message
+/// Decodes a +CIPRECVDATA message or a +IPD message of active receiving mode
 struct DataResponseParser<'a> {
     buffer: &'a [u8],
 
+    /// Index of the first length digit
+    length_start: usize,
+
     /// Data separator char, needed for backward compatibility with out-of-spec bug with older ESP-AT
     /// firmwares, see: https://github.com/atlas-aero/rt-esp-at-nal/issues/23
     data_separator: u8,
@@ -260,21 +463,39 @@ impl<'a> DataResponseParser<'a> {
     pub fn new(buffer: &'a [u8]) -> Self {
         let data_separator = if buffer[12] == b',' { b':' } else { b',' };
 
-        Self { buffer, data_separator }
+        Self {
+            buffer,
+            length_start: 13,
+            data_separator,
+        }
+    }
+
+    /// Creates a parser for +IPD messages of active receiving mode, which are always separated by colon
+    pub fn active(buffer: &'a [u8], length_start: usize) -> Self {
+        Self {
+            buffer,
+            length_start,
+            data_separator: b':',
+        }
     }
 
-    /// Parses the length and returns both the usize length + length string
+    /// Parses the length, the optional remote address and returns the decoded message
     pub fn parse(self) -> Result<DataMessage<'a>, ParseError> {
-        let separator = self
-            .buffer
+        let length_end = self.buffer[self.length_start..]
             .iter()
-            .enumerate()
-            .find(|x| x.1 == &self.data_separator)
+            .position(|x| !x.is_ascii_digit())
             .ok_or(ParseError::Incomplete)?
-            .0;
-        let length_str = core::str::from_utf8(&self.buffer[13..separator]).map_err(|_| ParseError::NoMatch)?;
+            + self.length_start;
+        let length_str =
+            core::str::from_utf8(&self.buffer[self.length_start..length_end]).map_err(|_| ParseError::NoMatch)?;
         let length_usize = length_str.parse::<usize>().map_err(|_| ParseError::NoMatch)?;
 
+        let (remote, separator) = match self.parse_remote(length_end)? {
+            Some((remote, separator)) => (Some(remote), separator),
+            None if self.buffer[length_end] == self.data_separator => (None, length_end),
+            None => return Err(ParseError::NoMatch),
+        };
+
         let remaining_data = &self.buffer[separator + 1..];
         if remaining_data.len() < length_usize {
             return Err(ParseError::Incomplete);
@@ -282,10 +503,61 @@ impl<'a> DataResponseParser<'a> {
 
         Ok(DataMessage {
             length: length_usize,
-            length_str,
+            data_start: separator + 1,
+            remote,
             data: remaining_data,
         })
     }
+
+    /// Parses the remote address appended by CIPDINFO, e.g. `,"10.0.0.1",5000`
+    /// Returns the remote address and the index of the data separator, or None if no remote address is included.
+    ///
+    /// As the serial data itself could start with a quote, the remote address is just matched if the
+    /// characters are valid for an IP address followed by a port.
+    fn parse_remote(&self, length_end: usize) -> Result<Option<(SocketAddr, usize)>, ParseError> {
+        let prefix = &self.buffer[length_end..];
+        if prefix.len() < 2 {
+            return Err(ParseError::Incomplete);
+        }
+
+        if prefix[0] != b',' || prefix[1] != b'"' {
+            return Ok(None);
+        }
+
+        let ip_end = match prefix[2..].iter().position(|x| !Self::is_ip_char(x)) {
+            None => return Err(ParseError::Incomplete),
+            Some(position) => position + 2,
+        };
+        if prefix[ip_end] != b'"' {
+            return Ok(None);
+        }
+        if prefix.len() < ip_end + 2 {
+            return Err(ParseError::Incomplete);
+        }
+        if prefix[ip_end + 1] != b',' {
+            return Ok(None);
+        }
+
+        let port_start = ip_end + 2;
+        let port_end = match prefix[port_start..].iter().position(|x| !x.is_ascii_digit()) {
+            None => return Err(ParseError::Incomplete),
+            Some(position) => position + port_start,
+        };
+        if prefix[port_end] != self.data_separator {
+            return Ok(None);
+        }
+
+        // Characters have been checked above, so the input is valid UTF-8
+        let ip = core::str::from_utf8(&prefix[2..ip_end]).map_err(|_| ParseError::NoMatch)?;
+        let port = core::str::from_utf8(&prefix[port_start..port_end]).map_err(|_| ParseError::NoMatch)?;
+
+        Ok(parse_remote(ip, port).map(|remote| (remote, length_end + port_end)))
+    }
+
+    /// Returns true if the given char may be part of an IPv4 or IPv6 address
+    fn is_ip_char(char: &u8) -> bool {
+        char.is_ascii_hexdigit() || char == &b'.' || char == &b':'
+    }
 }
 
 /// Decoded data message
@@ -293,8 +565,11 @@ struct DataMessage<'a> {
     /// Serial data length
     pub length: usize,
 
-    /// Serial data length as string
-    pub length_str: &'a str,
+    /// Index of the first serial data byte
+    pub data_start: usize,
+
+    /// Remote address of the data, if CIPDINFO is enabled
+    pub remote: Option<SocketAddr>,
 
     /// All data after separator
     pub data: &'a [u8],
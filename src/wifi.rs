@@ -1,6 +1,7 @@
 //! # WIFI access point client
 //!
-//! Joining a network and obtaining address information is supported.
+//! Joining a network, scanning for access points, querying the connection state, static IP configuration and
+//! obtaining address information is supported.
 //!
 //! Note: If the connection was not successful or is lost, the ESP-AT will try independently fro time
 //! to time (by default every second) to establish connection to the network. The status can be
@@ -11,6 +12,7 @@
 //! ````
 //! # use core::str::FromStr;
 //! # use embedded_nal::{TcpClientStack};
+//! # use fugit::ExtU32;
 //! # use esp_at_nal::example::ExampleTimer;
 //! # use esp_at_nal::wifi::{Adapter, WifiAdapter};
 //! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
@@ -19,34 +21,46 @@
 //! let client = AtClient::init(&urc_channel);
 //! let mut adapter: Adapter<_, _, 1_000_000, 1024, 128, 8> = Adapter::new(client, urc_channel.subscriber().unwrap(), ExampleTimer::default());
 //!
-//! // Setting target WIFI access point
-//! adapter.join("test_wifi", "secret").unwrap();
-//!
-//! // Waiting until a DCHP IP has been assigned
-//! while !adapter.get_join_status().ip_assigned {}
+//! // Joining the WIFI access point and waiting until a DCHP IP has been assigned
+//! adapter.join_and_wait("test_wifi", "secret", 10.secs()).unwrap();
 //!
 //! let address = adapter.get_address().unwrap();
-//! assert_eq!("10:fe:ed:05:ba:50", address.mac.unwrap().as_str());
+//! assert_eq!("10:fe:ed:05:ba:50", address.mac.unwrap().to_string());
 //! assert_eq!("10.0.0.181", address.ipv4.unwrap().to_string());
 //! ````
 use crate::commands::{
-    AccessPointConnectCommand, AutoConnectCommand, CommandErrorHandler, ObtainLocalAddressCommand, RestartCommand,
-    WifiModeCommand,
+    AccessPointConnectCommand, AccessPointDisconnectCommand, AccessPointInfoCommand, AutoConnectCommand,
+    CommandErrorHandler, DhcpCommand, GetHostnameCommand, IpConfigQueryCommand, ListAccessPointsCommand,
+    ObtainLocalAddressCommand, RestartCommand, ScanOptionsCommand, SetHostnameCommand, SoftApMacCommand,
+    StaticIpCommand, StationMacCommand, StationStateCommand, WifiModeCommand, WifiModeQueryCommand,
 };
-use crate::responses::LocalAddressResponse;
-use crate::stack::{ConnectionState, SocketState};
+use crate::queue::{ReceiveQueue, VecQueue};
+use crate::responses::{IpConfigResponse, LocalAddressResponse};
+use crate::softap::{StationEvent, MAX_STATION_EVENTS};
+use crate::stack::{ConnectionState, ReceiveMode, SocketState};
+use crate::udp::{ReceivedDatagram, MAX_RX_DATAGRAMS};
 use crate::urc::URCMessages;
 use atat::blocking::AtatClient;
 use atat::heapless::Vec;
-use atat::{AtatCmd, Error as AtError, UrcSubscription};
+use atat::{AtatCmd, AtatResp, Error as AtError, UrcSubscription};
 use core::fmt::Debug;
-use core::net::{Ipv4Addr, Ipv6Addr};
+use core::mem;
+use core::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
 use core::str::FromStr;
 use fugit::{ExtU32, TimerDurationU32};
 use fugit_timer::Timer;
-use heapless::String;
+use heapless::{Deque, String};
 use nb::Error;
 
+/// Max. length of the station hostname supported by ESP-AT
+pub const MAX_HOSTNAME_LENGTH: usize = 32;
+
+/// Default number of link ids, which is the max. connection count of most ESP-AT firmwares
+pub const DEFAULT_MAX_SOCKETS: usize = 5;
+
+/// Max. number of CIFSR response lines (IPv4, IPv6 link local, IPv6 global and MAC for station, SoftAP and Ethernet)
+pub(crate) const MAX_ADDRESS_RESPONSES: usize = 12;
+
 /// Wifi network adapter trait
 pub trait WifiAdapter {
     /// Error when joining a WIFI network
@@ -61,20 +75,80 @@ pub trait WifiAdapter {
     /// Errors when restarting the module
     type RestartError: Debug;
 
+    /// Errors when scanning for access points
+    type ScanError: Debug;
+
+    /// Errors when leaving the access point
+    type LeaveError: Debug;
+
+    /// Errors when querying the connection state from ESP-AT
+    type StatusError: Debug;
+
+    /// Errors when setting or querying the hostname
+    type HostnameError: Debug;
+
     /// Connects to an WIFI access point and returns the connection state
     fn join(&mut self, ssid: &str, key: &str) -> Result<JoinState, Self::JoinError>;
 
+    /// Sets the WIFI mode. Auto connect defines if ESP-AT connects to the stored AP when switching from SoftAP
+    /// to station mode. Just supported by newer firmware versions, ESP-AT default is used if None.
+    fn set_mode(&mut self, mode: WifiMode, auto_connect: Option<bool>) -> Result<(), Self::ConfigurationErrors>;
+
+    /// Returns the current WIFI mode
+    fn get_mode(&mut self) -> Result<WifiMode, Self::ConfigurationErrors>;
+
     /// Returns the current WIFI connection status
     fn get_join_status(&mut self) -> JoinState;
 
+    /// Disconnects from the current access point and blocks until disconnected
+    fn leave(&mut self) -> Result<(), Self::LeaveError>;
+
+    /// Returns information about the joined access point, or None if not connected
+    fn get_access_point_info(&mut self) -> Result<Option<ConnectedApInfo>, Self::StatusError>;
+
+    /// Queries the WIFI state of the station from ESP-AT
+    fn get_station_state(&mut self) -> Result<StationState, Self::StatusError>;
+
     /// Returns local address information
     fn get_address(&mut self) -> Result<LocalAddress, Self::AddressError>;
 
+    /// Enables/Disables DHCP of the given interface
+    fn set_dhcp(&mut self, interface: DhcpInterface, enabled: bool) -> Result<(), Self::ConfigurationErrors>;
+
+    /// Sets a static IPv4 configuration of the station
+    fn set_static_ip(
+        &mut self,
+        ip: Ipv4Addr,
+        gateway: Ipv4Addr,
+        netmask: Ipv4Addr,
+    ) -> Result<(), Self::ConfigurationErrors>;
+
+    /// Returns the active IP configuration of the station, including gateway and netmask
+    fn get_ip_config(&mut self) -> Result<LocalAddress, Self::AddressError>;
+
+    /// Sets the MAC address of the station
+    fn set_station_mac(&mut self, mac: MacAddress) -> Result<(), Self::ConfigurationErrors>;
+
+    /// Sets the MAC address of the SoftAP
+    fn set_ap_mac(&mut self, mac: MacAddress) -> Result<(), Self::ConfigurationErrors>;
+
+    /// Sets the hostname of the station, e.g. as shown in DHCP tables of routers
+    fn set_hostname(&mut self, hostname: &str) -> Result<(), Self::HostnameError>;
+
+    /// Returns the hostname of the station
+    fn get_hostname(&mut self) -> Result<String<MAX_HOSTNAME_LENGTH>, Self::HostnameError>;
+
     /// Enables/Disables auto connect, so that ESP-AT whether automatically joins to the stored AP when powered on.
     fn set_auto_connect(&mut self, enabled: bool) -> Result<(), Self::ConfigurationErrors>;
 
     /// Restarts the module and blocks until ready
     fn restart(&mut self) -> Result<(), Self::RestartError>;
+
+    /// Scans for access points in range. Returns up to N access points, further results and malformed ones are skipped.
+    fn scan<const N: usize>(&mut self) -> Result<Vec<AccessPoint, N>, Self::ScanError>;
+
+    /// Configures sorting, reported fields and filtering of [scan()](WifiAdapter::scan) results
+    fn set_scan_options(&mut self, options: ScanOptions) -> Result<(), Self::ConfigurationErrors>;
 }
 
 /// Central client for network communication
@@ -85,6 +159,12 @@ pub trait WifiAdapter {
 /// RX_SIZE: Chunk size in bytes when receiving data. Value should be matched to buffer size of `receive()` calls.
 ///
 /// URC_CAPACITY: URC buffer size. It's the same value, as used when initializing the UrcChannel of atat
+///
+/// MAX_SOCKETS: Number of link ids (TCP + UDP sockets), defaults to [DEFAULT_MAX_SOCKETS]. Must not exceed the max.
+/// connection count of the ESP-AT firmware. Lower values save some RAM.
+///
+/// Q: Receive queue of each socket, just required for [active receiving mode](crate::stack::ReceiveMode::Active).
+/// Defaults to [VecQueue<0>](VecQueue), so no buffer is allocated. s. [queue module](crate::queue).
 pub struct Adapter<
     'urc_sub,
     A: AtatClient,
@@ -93,6 +173,8 @@ pub struct Adapter<
     const TX_SIZE: usize,
     const RX_SIZE: usize,
     const URC_CAPACITY: usize,
+    const MAX_SOCKETS: usize = DEFAULT_MAX_SOCKETS,
+    Q: ReceiveQueue = VecQueue<0>,
 > {
     /// ATAT client
     pub(crate) client: A,
@@ -106,30 +188,59 @@ pub struct Adapter<
     /// Timeout for data transmission
     pub(crate) send_timeout: TimerDurationU32<TIMER_HZ>,
 
+    /// Max. connections of the TCP server, uses ESP-AT default if None
+    pub(crate) server_max_connections: Option<usize>,
+
+    /// Timeout in seconds of connections accepted by the TCP server, uses ESP-AT default if None
+    pub(crate) server_timeout: Option<u16>,
+
+    /// Number of idle `receive()` calls after which the buffered data length is queried, disabled if None
+    pub(crate) receive_refresh_interval: Option<u32>,
+
+    /// Count of consecutive `receive()` calls without data of each socket, used for `receive_refresh_interval`
+    pub(crate) receive_idle_calls: [u32; MAX_SOCKETS],
+
     /// Network state
-    pub(crate) session: Session<RX_SIZE>,
+    pub(crate) session: Session<RX_SIZE, MAX_SOCKETS, Q>,
 }
 
 /// Collection of network state
-#[derive(Default)]
-pub(crate) struct Session<const RX_SIZE: usize> {
+pub(crate) struct Session<const RX_SIZE: usize, const MAX_SOCKETS: usize, Q: ReceiveQueue> {
     /// Currently joined to WIFI network? Gets updated by URC messages.
-    joined: bool,
+    pub(crate) joined: bool,
 
     /// True if an IP was assigned by access point. Get updated by URC message.
-    ip_assigned: bool,
+    pub(crate) ip_assigned: bool,
 
     /// True if a URC ready message arrived.
-    ready: bool,
+    pub(crate) ready: bool,
 
     /// True if multiple connections have been enabled
     pub(crate) multi_connections_enabled: bool,
 
-    /// True if socket passive receiving mode is enabled
-    pub(crate) passive_mode_enabled: bool,
+    /// Socket receiving mode, applied by the next connect or listen call
+    pub(crate) receive_mode: ReceiveMode,
+
+    /// True if the socket receiving mode has been configured
+    pub(crate) receive_mode_configured: bool,
+
+    /// True if remote address information of received data is enabled (CIPDINFO)
+    pub(crate) remote_info_enabled: bool,
+
+    /// Local port of the TCP server, if a socket is bound
+    pub(crate) server_port: Option<u16>,
+
+    /// True if the TCP server was created by `listen()`
+    pub(crate) server_listening: bool,
 
     /// Current socket states, array index = link_id
-    pub(crate) sockets: [SocketState; 5],
+    pub(crate) sockets: [SocketState; MAX_SOCKETS],
+
+    /// Data received in active receiving mode, array index = link_id
+    pub(crate) rx_buffers: [Q; MAX_SOCKETS],
+
+    /// Datagrams buffered by rx_buffers of UDP sockets, array index = link_id
+    pub(crate) rx_datagrams: [Deque<ReceivedDatagram, MAX_RX_DATAGRAMS>; MAX_SOCKETS],
 
     /// Received byte count confirmed by URC message. Gets reset to NONE by 'send()' method
     pub(crate) recv_byte_count: Option<usize>,
@@ -144,9 +255,72 @@ pub(crate) struct Session<const RX_SIZE: usize> {
 
     /// Received socket data by URC message
     pub(crate) data: Option<Vec<u8, RX_SIZE>>,
+
+    /// Remote address of the received socket data, if reported by ESP-AT (CIPDINFO)
+    pub(crate) data_remote: Option<SocketAddr>,
+
+    /// Fields of scan results as configured by CWLAPOPT, all fields are reported if None
+    pub(crate) scan_fields: Option<u16>,
+
+    /// Reason of the last failed join attempt, reported by URC message
+    pub(crate) join_failure: Option<JoinFailureReason>,
+
+    /// Last known WIFI mode, set by this crate or queried from ESP-AT
+    pub(crate) wifi_mode: Option<WifiMode>,
+
+    /// Unhandled station events of SoftAP mode
+    pub(crate) station_events: Deque<StationEvent, MAX_STATION_EVENTS>,
+
+    /// True if the time was synchronized by SNTP, signaled by URC message
+    pub(crate) time_synchronized: bool,
+
+    /// True if the last PING command timed out, signaled by URC message
+    pub(crate) ping_timeout: bool,
+}
+
+impl<const RX_SIZE: usize, const MAX_SOCKETS: usize, Q: ReceiveQueue> Default for Session<RX_SIZE, MAX_SOCKETS, Q> {
+    fn default() -> Self {
+        Self {
+            joined: false,
+            ip_assigned: false,
+            ready: false,
+            multi_connections_enabled: false,
+            receive_mode: ReceiveMode::Passive,
+            receive_mode_configured: false,
+            remote_info_enabled: false,
+            server_port: None,
+            server_listening: false,
+            sockets: [SocketState::default(); MAX_SOCKETS],
+            rx_buffers: core::array::from_fn(|_| Q::default()),
+            rx_datagrams: core::array::from_fn(|_| Deque::new()),
+            recv_byte_count: None,
+            send_confirmed: None,
+            already_connected: false,
+            data: None,
+            data_remote: None,
+            scan_fields: None,
+            join_failure: None,
+            wifi_mode: None,
+            station_events: Deque::new(),
+            time_synchronized: false,
+            ping_timeout: false,
+        }
+    }
 }
 
-impl<const RX_SIZE: usize> Session<RX_SIZE> {
+impl<const RX_SIZE: usize, const MAX_SOCKETS: usize, Q: ReceiveQueue> Session<RX_SIZE, MAX_SOCKETS, Q> {
+    /// Resets the state after restarting ESP-AT. The receive queues are kept, as their storage may be
+    /// statically assigned, but buffered data gets dropped.
+    pub(crate) fn reset(&mut self) {
+        let mut rx_buffers = mem::replace(&mut self.rx_buffers, core::array::from_fn(|_| Q::default()));
+        rx_buffers.iter_mut().for_each(Q::clear);
+
+        *self = Self {
+            rx_buffers,
+            ..Self::default()
+        };
+    }
+
     /// Handles a single URC message
     pub(crate) fn handle_urc(&mut self, message: URCMessages<RX_SIZE>) {
         match message {
@@ -157,21 +331,95 @@ impl<const RX_SIZE: usize> Session<RX_SIZE> {
             URCMessages::ReceivedIP => self.ip_assigned = true,
             URCMessages::WifiConnected => self.joined = true,
             URCMessages::Ready => self.ready = true,
-            URCMessages::SocketConnected(link_id) => self.sockets[link_id].state = ConnectionState::Connected,
-            URCMessages::SocketClosed(link_id) => self.sockets[link_id].state = ConnectionState::Closing,
+            URCMessages::SocketConnected(link_id) => {
+                if let Some(socket) = self.sockets.get_mut(link_id) {
+                    match socket.state {
+                        ConnectionState::Connecting => socket.state = ConnectionState::Connected,
+                        ConnectionState::Connected | ConnectionState::Incoming => {}
+                        // Connections not opened by this crate are established by remote clients of the TCP server.
+                        // ESP-AT may assign any link not connected on its side, including unconnected or closing sockets.
+                        _ => {
+                            *socket = SocketState::incoming();
+                            self.rx_buffers[link_id].clear();
+                        }
+                    }
+                }
+            }
+            URCMessages::SocketClosed(link_id) => {
+                if let Some(socket) = self.sockets.get_mut(link_id) {
+                    // Pending server connection closed before accepted, so link id may be reused directly
+                    if socket.state == ConnectionState::Incoming {
+                        *socket = SocketState::default();
+                    } else {
+                        socket.state = ConnectionState::Closing;
+                    }
+                }
+            }
             URCMessages::AlreadyConnected => self.already_connected = true,
             URCMessages::ReceivedBytes(count) => self.recv_byte_count = Some(count),
             URCMessages::SendConfirmation => self.send_confirmed = Some(true),
             URCMessages::SendFail => self.send_confirmed = Some(false),
-            URCMessages::DataAvailable(link_id, length) => {
-                if link_id < self.sockets.len() {
-                    self.sockets[link_id].data_available = length;
+            URCMessages::DataAvailable(link_id, length, _) => {
+                if let Some(socket) = self.sockets.get_mut(link_id) {
+                    socket.data_available = length;
                 }
             }
-            URCMessages::Data(data) => self.data = Some(data),
+            URCMessages::Data(data, remote) => {
+                self.data = Some(data);
+                self.data_remote = remote;
+            }
+            URCMessages::ReceivedData(link_id, data, remote, truncated) => {
+                self.buffer_received_data(link_id, &data, remote, truncated)
+            }
+            URCMessages::JoinFailed(code) => self.join_failure = Some(JoinFailureReason::from(code)),
+            URCMessages::StationConnected(mac) => self.push_station_event(StationEvent::Connected(mac)),
+            URCMessages::StationDisconnected(mac) => self.push_station_event(StationEvent::Disconnected(mac)),
+            URCMessages::StationIpAssigned(mac, ip) => self.push_station_event(StationEvent::IpAssigned(mac, ip)),
+            URCMessages::TimeUpdated => self.time_synchronized = true,
+            URCMessages::PingTimeout => self.ping_timeout = true,
             URCMessages::Unknown => {}
         }
     }
+
+    /// Marks all connections as closing, e.g. as they got lost by leaving the network.
+    /// Pending server connections are dropped, as they can not be accepted anymore.
+    pub(crate) fn close_connections(&mut self) {
+        for socket in self.sockets.iter_mut() {
+            match socket.state {
+                ConnectionState::Connected => socket.state = ConnectionState::Closing,
+                ConnectionState::Incoming => *socket = SocketState::default(),
+                _ => {}
+            }
+        }
+    }
+
+    /// Updates the join status by the station state reported by ESP-AT
+    pub(crate) fn sync_station_state(&mut self, state: StationState) {
+        match state {
+            StationState::Connected => {
+                self.joined = true;
+                self.ip_assigned = false;
+            }
+            StationState::IpAssigned => {
+                self.joined = true;
+                self.ip_assigned = true;
+            }
+            StationState::Unknown(_) => {}
+            _ => {
+                self.joined = false;
+                self.ip_assigned = false;
+            }
+        }
+    }
+
+    /// Queues the given station event. Drops the oldest event if the queue is full.
+    fn push_station_event(&mut self, event: StationEvent) {
+        if self.station_events.is_full() {
+            self.station_events.pop_front();
+        }
+
+        let _ = self.station_events.push_back(event);
+    }
 }
 
 /// Possible errors when joining an access point
@@ -186,6 +434,15 @@ pub enum JoinError {
     /// Error while setting WIFI credentials
     ConnectError(AtError),
 
+    /// Joining failed for the given reason reported by ESP-AT
+    JoinFailed(JoinFailureReason),
+
+    /// Connection or IP assignment not completed within the timeout of `join_and_wait()`
+    Timeout,
+
+    /// Upstream timer error
+    TimerError,
+
     /// Given SSD is longer then the max. size of 32 chars
     InvalidSSDLength,
 
@@ -197,6 +454,55 @@ pub enum JoinError {
     UnexpectedWouldBlock,
 }
 
+impl JoinError {
+    /// Replaces a failed CWJAP command by the failure reason, if reported by ESP-AT
+    pub(crate) fn with_failure_reason(self, reason: Option<JoinFailureReason>) -> Self {
+        match (self, reason) {
+            (Self::ConnectError(_), Some(reason)) => Self::JoinFailed(reason),
+            (error, _) => error,
+        }
+    }
+
+    /// Maps the error of switching the WIFI mode
+    pub(crate) fn from_mode_error(error: CommandError) -> Self {
+        match error {
+            CommandError::CommandFailed(error) => Self::ModeError(error),
+            _ => Self::UnexpectedWouldBlock,
+        }
+    }
+}
+
+/// Reason of a failed join attempt, reported by `+CWJAP:<code>`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum JoinFailureReason {
+    /// Connection timed out
+    Timeout,
+
+    /// Wrong password
+    WrongPassword,
+
+    /// Target access point was not found
+    AccessPointNotFound,
+
+    /// Connection failed
+    ConnectionFailed,
+
+    /// Error code not known by this crate
+    Unknown(u8),
+}
+
+impl From<u8> for JoinFailureReason {
+    fn from(code: u8) -> Self {
+        match code {
+            1 => Self::Timeout,
+            2 => Self::WrongPassword,
+            3 => Self::AccessPointNotFound,
+            4 => Self::ConnectionFailed,
+            _ => Self::Unknown(code),
+        }
+    }
+}
+
 /// Errors when receiving local address information
 #[derive(Clone, Debug, PartialEq)]
 pub enum AddressErrors {
@@ -220,6 +526,9 @@ pub enum CommandError {
     /// No ready message received within timout (5 seconds)
     ReadyTimeout,
 
+    /// No WIFI DISCONNECT message received within timeout (5 seconds)
+    DisconnectTimeout,
+
     /// Upstream timer error
     TimerError,
 
@@ -228,6 +537,38 @@ pub enum CommandError {
     UnexpectedWouldBlock,
 }
 
+/// Errors when setting or querying the hostname
+#[derive(Clone, Debug, PartialEq)]
+pub enum HostnameError {
+    /// Given hostname is empty or longer then [MAX_HOSTNAME_LENGTH]
+    InvalidLength,
+
+    /// Given hostname contains chars other then ASCII letters, digits and hyphens
+    InvalidCharacters,
+
+    /// CWHOSTNAME command failed
+    CommandFailed(AtError),
+
+    /// Received an unexpected WouldBlock. The most common cause of errors is an incorrect mode of the client.
+    /// This must be either timeout or blocking.
+    UnexpectedWouldBlock,
+}
+
+impl HostnameError {
+    /// Validates the given hostname
+    pub(crate) fn validate(hostname: &str) -> Result<String<MAX_HOSTNAME_LENGTH>, Self> {
+        if hostname.is_empty() {
+            return Err(Self::InvalidLength);
+        }
+
+        if !hostname.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(Self::InvalidCharacters);
+        }
+
+        String::from_str(hostname).map_err(|_| Self::InvalidLength)
+    }
+}
+
 /// Current WIFI connection state
 #[derive(Copy, Clone, Debug)]
 pub struct JoinState {
@@ -245,12 +586,18 @@ impl<
         const TX_SIZE: usize,
         const RX_SIZE: usize,
         const URC_CAPACITY: usize,
-    > WifiAdapter for Adapter<'_, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, URC_CAPACITY>
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > WifiAdapter for Adapter<'_, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
 {
     type JoinError = JoinError;
     type AddressError = AddressErrors;
     type ConfigurationErrors = CommandError;
     type RestartError = CommandError;
+    type ScanError = CommandError;
+    type LeaveError = CommandError;
+    type StatusError = CommandError;
+    type HostnameError = HostnameError;
 
     /// Connects to an WIFI access point and returns the connection state
     ///
@@ -258,8 +605,12 @@ impl<
     /// If the connection was not successful or is lost, the ESP-AT will try independently fro time
     /// to time (by default every second) to establish connection to the network. The status can be
     /// queried using `get_join_state()`.
+    ///
+    /// Switches to station mode, unless SoftAP mode is known to be active by [set_mode()](WifiAdapter::set_mode),
+    /// [get_mode()](WifiAdapter::get_mode) or [start_access_point()](Adapter::start_access_point). In this case
+    /// SoftAP+Station mode is used, so that the access point keeps running.
     fn join(&mut self, ssid: &str, key: &str) -> Result<JoinState, JoinError> {
-        self.set_station_mode()?;
+        self.enable_station_mode()?;
         self.connect_access_point(ssid, key)?;
         self.process_urc_messages();
 
@@ -269,6 +620,23 @@ impl<
         })
     }
 
+    /// Sets the WIFI mode. Auto connect defines if ESP-AT connects to the stored AP when switching from SoftAP
+    /// to station mode. Just supported by newer firmware versions, ESP-AT default is used if None.
+    fn set_mode(&mut self, mode: WifiMode, auto_connect: Option<bool>) -> Result<(), CommandError> {
+        self.send_command(WifiModeCommand::new(mode, auto_connect))?;
+        self.session.wifi_mode = Some(mode);
+        Ok(())
+    }
+
+    /// Returns the current WIFI mode
+    fn get_mode(&mut self) -> Result<WifiMode, CommandError> {
+        let response = self.send_command(WifiModeQueryCommand::default())?;
+        let mode = WifiMode::try_from(response.mode).map_err(|_| CommandError::CommandFailed(AtError::Parse))?;
+
+        self.session.wifi_mode = Some(mode);
+        Ok(mode)
+    }
+
     /// Returns the current WIFI connection status
     fn get_join_status(&mut self) -> JoinState {
         self.process_urc_messages();
@@ -278,12 +646,111 @@ impl<
         }
     }
 
+    /// Disconnects from the current access point and blocks until the WIFI DISCONNECT message arrived.
+    /// If not disconnected within five seconds, [CommandError::DisconnectTimeout] is returned.
+    ///
+    /// Open connections get lost, so all sockets are marked as closing and need to be closed.
+    fn leave(&mut self) -> Result<(), CommandError> {
+        self.process_urc_messages();
+        self.send_command(AccessPointDisconnectCommand::default())?;
+        self.session.close_connections();
+
+        self.timer.start(5.secs()).map_err(|_| CommandError::TimerError)?;
+        loop {
+            self.process_urc_messages();
+            if !self.session.joined {
+                break;
+            }
+
+            if let nb::Result::Err(error) = self.timer.wait() {
+                match error {
+                    Error::Other(_) => return Err(CommandError::TimerError),
+                    Error::WouldBlock => {}
+                }
+            } else {
+                return Err(CommandError::DisconnectTimeout);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns information about the joined access point, e.g. for diagnosing the link quality.
+    /// Returns None if not connected.
+    fn get_access_point_info(&mut self) -> Result<Option<ConnectedApInfo>, CommandError> {
+        Ok(self.send_command(AccessPointInfoCommand::default())?.pop())
+    }
+
+    /// Queries the WIFI state of the station from ESP-AT.
+    ///
+    /// As the state is authoritative, the join status gets updated accordingly, e.g. in case URC messages got lost.
+    /// Requires ESP-AT version >= 2.1.
+    fn get_station_state(&mut self) -> Result<StationState, CommandError> {
+        self.process_urc_messages();
+        let response = self.send_command(StationStateCommand::default())?;
+
+        let state = StationState::from(response.state);
+        self.session.sync_station_state(state);
+        Ok(state)
+    }
+
     /// Returns local address information
     fn get_address(&mut self) -> Result<LocalAddress, AddressErrors> {
         let responses = self.send_command(ObtainLocalAddressCommand::new())?;
         LocalAddress::from_responses(responses)
     }
 
+    /// Enables/Disables DHCP of the given interface. Disabling station DHCP without setting a static IP by
+    /// [set_static_ip()](WifiAdapter::set_static_ip) leaves the station without an IP address.
+    fn set_dhcp(&mut self, interface: DhcpInterface, enabled: bool) -> Result<(), CommandError> {
+        self.send_command(DhcpCommand::new(interface, enabled))?;
+        Ok(())
+    }
+
+    /// Sets a static IPv4 configuration of the station. ESP-AT disables station DHCP implicitly.
+    ///
+    /// Note: The configuration is stored in flash if system storage (AT+SYSSTORE) is enabled.
+    fn set_static_ip(&mut self, ip: Ipv4Addr, gateway: Ipv4Addr, netmask: Ipv4Addr) -> Result<(), CommandError> {
+        self.send_command(StaticIpCommand::new(&ip, &gateway, &netmask))?;
+        Ok(())
+    }
+
+    /// Returns the active IP configuration of the station (CIPSTA), including gateway and netmask.
+    /// In contrast to [get_address()](WifiAdapter::get_address), the MAC address is not included.
+    fn get_ip_config(&mut self) -> Result<LocalAddress, AddressErrors> {
+        let responses = self.send_command(IpConfigQueryCommand::default())?;
+        LocalAddress::from_ip_config(responses)
+    }
+
+    /// Sets the MAC address of the station. Requires station mode to be enabled.
+    ///
+    /// Note: MAC addresses of station and SoftAP need to differ. Multicast addresses are rejected by ESP-AT.
+    fn set_station_mac(&mut self, mac: MacAddress) -> Result<(), CommandError> {
+        self.send_command(StationMacCommand::new(&mac))?;
+        Ok(())
+    }
+
+    /// Sets the MAC address of the SoftAP. Requires SoftAP mode to be enabled.
+    ///
+    /// Note: MAC addresses of station and SoftAP need to differ. Multicast addresses are rejected by ESP-AT.
+    fn set_ap_mac(&mut self, mac: MacAddress) -> Result<(), CommandError> {
+        self.send_command(SoftApMacCommand::new(&mac))?;
+        Ok(())
+    }
+
+    /// Sets the hostname of the station. Hostname must consist of 1-32 alphanumeric chars or hyphens.
+    /// Requires station mode to be enabled and gets applied when (re)joining the access point.
+    fn set_hostname(&mut self, hostname: &str) -> Result<(), HostnameError> {
+        let hostname = HostnameError::validate(hostname)?;
+        self.send_command(SetHostnameCommand::new(hostname))?;
+        Ok(())
+    }
+
+    /// Returns the hostname of the station
+    fn get_hostname(&mut self) -> Result<String<MAX_HOSTNAME_LENGTH>, HostnameError> {
+        Ok(self.send_command(GetHostnameCommand::default())?.hostname)
+    }
+
     /// Enables auto connect, so that ESP-AT automatically connects to the stored AP when powered on.
     fn set_auto_connect(&mut self, enabled: bool) -> Result<(), CommandError> {
         self.send_command(AutoConnectCommand::new(enabled))?;
@@ -296,7 +763,7 @@ impl<
         self.session.ready = false;
         self.send_command(RestartCommand::default())?;
 
-        self.session = Session::default();
+        self.session.reset();
 
         self.timer.start(5.secs()).map_err(|_| CommandError::TimerError)?;
         while !self.session.ready {
@@ -314,6 +781,20 @@ impl<
 
         Ok(())
     }
+
+    /// Scans for access points in range. Returns up to N access points, further results and malformed ones are skipped.
+    /// Sorting and filtering may be configured by [set_scan_options()](WifiAdapter::set_scan_options).
+    fn scan<const N: usize>(&mut self) -> Result<Vec<AccessPoint, N>, CommandError> {
+        let fields = self.session.scan_fields.unwrap_or(ScanOptions::ALL_FIELDS);
+        self.send_command(ListAccessPointsCommand::new(fields))
+    }
+
+    /// Configures sorting, reported fields and filtering of [scan()](WifiAdapter::scan) results
+    fn set_scan_options(&mut self, options: ScanOptions) -> Result<(), CommandError> {
+        self.send_command(ScanOptionsCommand::new(&options))?;
+        self.session.scan_fields = Some(options.fields);
+        Ok(())
+    }
 }
 
 impl<
@@ -324,7 +805,9 @@ impl<
         const TX_SIZE: usize,
         const RX_SIZE: usize,
         const URC_CAPACITY: usize,
-    > Adapter<'urc_sub, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, URC_CAPACITY>
+        const MAX_SOCKETS: usize,
+        Q: ReceiveQueue,
+    > Adapter<'urc_sub, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, URC_CAPACITY, MAX_SOCKETS, Q>
 {
     /// Creates a new network adapter. Client needs to be in timeout or blocking mode
     pub fn new(
@@ -337,10 +820,43 @@ impl<
             urc_subscription,
             timer,
             send_timeout: 5_000.millis(),
+            server_max_connections: None,
+            server_timeout: None,
+            receive_refresh_interval: None,
+            receive_idle_calls: [0; MAX_SOCKETS],
             session: Session::default(),
         }
     }
 
+    /// Connects to an WIFI access point and blocks until connected and an IP has been assigned.
+    /// If not completed within the given timeout, [JoinError::Timeout] is returned.
+    ///
+    /// ESP-AT keeps trying to connect in background after a timeout, s. [join()](WifiAdapter::join).
+    pub fn join_and_wait(
+        &mut self,
+        ssid: &str,
+        key: &str,
+        timeout: TimerDurationU32<TIMER_HZ>,
+    ) -> Result<JoinState, JoinError> {
+        let mut state = self.join(ssid, key)?;
+
+        self.timer.start(timeout).map_err(|_| JoinError::TimerError)?;
+        while !state.connected || !state.ip_assigned {
+            if let nb::Result::Err(error) = self.timer.wait() {
+                match error {
+                    Error::Other(_) => return Err(JoinError::TimerError),
+                    Error::WouldBlock => {}
+                }
+            } else {
+                return Err(JoinError::Timeout);
+            }
+
+            state = self.get_join_status();
+        }
+
+        Ok(state)
+    }
+
     /// Processes all pending messages in the queue
     pub(crate) fn process_urc_messages(&mut self) {
         while let Some(message) = self.urc_subscription.try_next_message_pure() {
@@ -348,12 +864,15 @@ impl<
         }
     }
 
-    /// Sends the command for switching to station mode
-    fn set_station_mode(&mut self) -> Result<(), JoinError> {
-        let command = WifiModeCommand::station_mode();
-        self.send_command(command)?;
+    /// Switches to station mode, or to SoftAP+Station mode if SoftAP mode is active
+    fn enable_station_mode(&mut self) -> Result<(), JoinError> {
+        let mode = match self.session.wifi_mode {
+            Some(WifiMode::SoftApStation) => return Ok(()),
+            Some(WifiMode::SoftAp) => WifiMode::SoftApStation,
+            _ => WifiMode::Station,
+        };
 
-        Ok(())
+        self.set_mode(mode, None).map_err(JoinError::from_mode_error)
     }
 
     /// Sends the command for setting the WIFI credentials
@@ -366,8 +885,15 @@ impl<
             return Err(JoinError::InvalidPasswordLength);
         }
 
+        // Discarding reasons of previous attempts
+        self.process_urc_messages();
+        self.session.join_failure = None;
+
         let command = AccessPointConnectCommand::new(String::from_str(ssid).unwrap(), String::from_str(key).unwrap());
-        self.send_command(command)?;
+        if let Err(error) = self.send_command(command) {
+            self.process_urc_messages();
+            return Err(error.with_failure_reason(self.session.join_failure.take()));
+        }
 
         Ok(())
     }
@@ -387,13 +913,44 @@ impl<
 }
 
 /// Local IP and MAC addresses
+///
+/// Top level fields contain the addresses of the station. Addresses of SoftAP and Ethernet interface are just
+/// reported by [get_address()](WifiAdapter::get_address), if the corresponding interface is enabled.
 #[derive(Default, Clone, Debug)]
 pub struct LocalAddress {
     /// Local IPv4 address if assigned
     pub ipv4: Option<Ipv4Addr>,
 
+    /// IPv4 address of the gateway. Just reported by [get_ip_config()](WifiAdapter::get_ip_config).
+    pub gateway: Option<Ipv4Addr>,
+
+    /// IPv4 netmask. Just reported by [get_ip_config()](WifiAdapter::get_ip_config).
+    pub netmask: Option<Ipv4Addr>,
+
     /// Local MAC address
-    pub mac: Option<String<17>>,
+    pub mac: Option<MacAddress>,
+
+    /// Link local IPv6 address if assigned
+    pub ipv6_link_local: Option<Ipv6Addr>,
+
+    /// Global IPv6 address if assigned
+    pub ipv6_global: Option<Ipv6Addr>,
+
+    /// Addresses of the SoftAP (SoftAP or SoftAP+Station mode)
+    pub soft_ap: InterfaceAddress,
+
+    /// Addresses of the Ethernet interface (Ethernet firmware builds only)
+    pub ethernet: InterfaceAddress,
+}
+
+/// IP and MAC addresses of a single network interface
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct InterfaceAddress {
+    /// IPv4 address if assigned
+    pub ipv4: Option<Ipv4Addr>,
+
+    /// MAC address
+    pub mac: Option<MacAddress>,
 
     /// Link local IPv6 address if assigned
     pub ipv6_link_local: Option<Ipv6Addr>,
@@ -402,41 +959,600 @@ pub struct LocalAddress {
     pub ipv6_global: Option<Ipv6Addr>,
 }
 
+impl InterfaceAddress {
+    /// Sets the address of the given type (IP, IP6LL, IP6GL or MAC). Unknown types are ignored.
+    fn set_address(&mut self, address_type: &[u8], address: &str) -> Result<(), AddressErrors> {
+        match address_type {
+            b"IP" => self.ipv4 = Some(Ipv4Addr::from_str(address).map_err(|_| AddressErrors::AddressParseError)?),
+            b"IP6LL" => {
+                self.ipv6_link_local = Some(Ipv6Addr::from_str(address).map_err(|_| AddressErrors::AddressParseError)?)
+            }
+            b"IP6GL" => {
+                self.ipv6_global = Some(Ipv6Addr::from_str(address).map_err(|_| AddressErrors::AddressParseError)?)
+            }
+            b"MAC" => self.mac = Some(MacAddress::from_str(address).map_err(|_| AddressErrors::AddressParseError)?),
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
 impl LocalAddress {
-    pub(crate) fn from_responses(responses: Vec<LocalAddressResponse, 4>) -> Result<Self, AddressErrors> {
+    pub(crate) fn from_responses(
+        responses: Vec<LocalAddressResponse, MAX_ADDRESS_RESPONSES>,
+    ) -> Result<Self, AddressErrors> {
+        let mut data = Self::default();
+        let mut station = InterfaceAddress::default();
+
+        for response in responses {
+            let address_type = response.address_type.as_slice();
+            let address = response.address.as_str();
+
+            if let Some(address_type) = address_type.strip_prefix(b"STA") {
+                station.set_address(address_type, address)?;
+            } else if let Some(address_type) = address_type.strip_prefix(b"AP") {
+                data.soft_ap.set_address(address_type, address)?;
+            } else if let Some(address_type) = address_type.strip_prefix(b"ETH") {
+                data.ethernet.set_address(address_type, address)?;
+            }
+        }
+
+        data.ipv4 = station.ipv4;
+        data.mac = station.mac;
+        data.ipv6_link_local = station.ipv6_link_local;
+        data.ipv6_global = station.ipv6_global;
+        Ok(data)
+    }
+
+    pub(crate) fn from_ip_config(responses: Vec<IpConfigResponse, 5>) -> Result<Self, AddressErrors> {
         let mut data = Self::default();
 
         for response in responses {
-            match response.address_type.as_slice() {
-                b"STAIP" => {
-                    data.ipv4 = Some(
-                        Ipv4Addr::from_str(response.address.as_str()).map_err(|_| AddressErrors::AddressParseError)?,
-                    )
+            let address = response.address.as_str();
+            match response.address_type.as_str() {
+                "ip" => data.ipv4 = Some(Ipv4Addr::from_str(address).map_err(|_| AddressErrors::AddressParseError)?),
+                "gateway" => {
+                    data.gateway = Some(Ipv4Addr::from_str(address).map_err(|_| AddressErrors::AddressParseError)?)
                 }
-                b"STAIP6LL" => {
-                    data.ipv6_link_local = Some(
-                        Ipv6Addr::from_str(response.address.as_str()).map_err(|_| AddressErrors::AddressParseError)?,
-                    )
+                "netmask" => {
+                    data.netmask = Some(Ipv4Addr::from_str(address).map_err(|_| AddressErrors::AddressParseError)?)
                 }
-                b"STAIP6GL" => {
-                    data.ipv6_global = Some(
-                        Ipv6Addr::from_str(response.address.as_str()).map_err(|_| AddressErrors::AddressParseError)?,
-                    )
+                "ip6ll" => {
+                    data.ipv6_link_local =
+                        Some(Ipv6Addr::from_str(address).map_err(|_| AddressErrors::AddressParseError)?)
                 }
-                b"STAMAC" => {
-                    if response.address.len() > 17 {
-                        return Err(AddressErrors::AddressParseError);
-                    }
-
-                    data.mac = match String::from_str(response.address.as_str()) {
-                        Ok(string) => Some(string),
-                        Err(_) => None,
-                    };
+                "ip6gl" => {
+                    data.ipv6_global = Some(Ipv6Addr::from_str(address).map_err(|_| AddressErrors::AddressParseError)?)
                 }
-                &_ => {}
+                _ => {}
             }
         }
 
         Ok(data)
     }
 }
+
+/// MAC address, formatted as `aa:bb:cc:dd:ee:ff`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MacAddress(pub [u8; 6]);
+
+impl MacAddress {
+    /// Returns the octets of the address
+    pub fn octets(&self) -> [u8; 6] {
+        self.0
+    }
+
+    /// Returns true if this is a unicast address (bit 0 of the first octet is unset)
+    pub fn is_unicast(&self) -> bool {
+        self.0[0] & 0x01 == 0
+    }
+}
+
+impl From<[u8; 6]> for MacAddress {
+    fn from(octets: [u8; 6]) -> Self {
+        Self(octets)
+    }
+}
+
+impl FromStr for MacAddress {
+    type Err = ();
+
+    /// Parses six colon separated hex octets, e.g. `10:fe:ed:05:ba:50`. Case is ignored.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut octets = [0u8; 6];
+        let mut parts = value.split(':');
+
+        for octet in octets.iter_mut() {
+            let part = parts.next().ok_or(())?;
+            if part.len() != 2 || !part.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(());
+            }
+
+            *octet = u8::from_str_radix(part, 16).map_err(|_| ())?;
+        }
+
+        match parts.next() {
+            None => Ok(Self(octets)),
+            Some(_) => Err(()),
+        }
+    }
+}
+
+impl core::fmt::Display for MacAddress {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(f, "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}", a, b, c, d, e, g)
+    }
+}
+
+/// Network interface for enabling/disabling DHCP
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DhcpInterface {
+    /// DHCP client of the station
+    Station,
+
+    /// DHCP server of the SoftAP
+    SoftAp,
+
+    /// Station and SoftAP
+    Both,
+}
+
+impl From<DhcpInterface> for usize {
+    fn from(interface: DhcpInterface) -> Self {
+        match interface {
+            DhcpInterface::Station => 1,
+            DhcpInterface::SoftAp => 2,
+            DhcpInterface::Both => 3,
+        }
+    }
+}
+
+/// WIFI state of the station, as reported by CWSTATE
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StationState {
+    /// No connection attempt was started yet
+    NotStarted,
+
+    /// Connected to an access point, but no IPv4 address was assigned yet
+    Connected,
+
+    /// Connected to an access point and an IPv4 address was assigned
+    IpAssigned,
+
+    /// Connecting or reconnecting to an access point
+    Connecting,
+
+    /// Disconnected from the access point
+    Disconnected,
+
+    /// State not known by this crate, contains the raw ESP-AT value
+    Unknown(u8),
+}
+
+impl From<u8> for StationState {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::NotStarted,
+            1 => Self::Connected,
+            2 => Self::IpAssigned,
+            3 => Self::Connecting,
+            4 => Self::Disconnected,
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+/// Information about the joined access point, s. [get_access_point_info()](WifiAdapter::get_access_point_info)
+///
+/// Optional fields are None if not supported by the ESP-AT firmware version.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConnectedApInfo {
+    /// SSID of the access point
+    pub ssid: String<32>,
+
+    /// MAC address (BSSID) of the access point
+    pub bssid: MacAddress,
+
+    /// WIFI channel
+    pub channel: u8,
+
+    /// Signal strength in dBm
+    pub rssi: i16,
+
+    /// True if PCI authentication is enabled
+    pub pci_enabled: Option<bool>,
+
+    /// Interval in seconds between reconnection attempts. 0 = ESP-AT does not reconnect.
+    pub reconnect_interval: Option<u16>,
+
+    /// Interval for listening to beacons, in units of the beacon interval
+    pub listen_interval: Option<u16>,
+
+    /// Scan mode. 0: Fast scan, 1: All-channel scan
+    pub scan_mode: Option<u8>,
+
+    /// Protected Management Frames. Bit 0: PMF capable, bit 1: PMF required
+    pub pmf: Option<u8>,
+}
+
+impl AtatResp for ConnectedApInfo {}
+
+impl ConnectedApInfo {
+    /// Parses the +CWJAP line of the response. Result is empty if not connected (No AP).
+    pub(crate) fn from_response(response: &[u8]) -> Option<Vec<Self, 1>> {
+        let response = core::str::from_utf8(response).ok()?;
+        let mut info = Vec::new();
+
+        for line in response.lines() {
+            if let Some(line) = line.trim().strip_prefix("+CWJAP:") {
+                info.push(Self::from_line(line)?).ok()?;
+                break;
+            }
+        }
+
+        Some(info)
+    }
+
+    /// Parses the fields of a single line
+    fn from_line(line: &str) -> Option<Self> {
+        let mut values = AccessPoint::split_line(line)?.into_iter();
+
+        Some(Self {
+            ssid: values.next()?,
+            bssid: MacAddress::from_str(values.next()?.as_str()).ok()?,
+            channel: values.next()?.as_str().parse().ok()?,
+            rssi: values.next()?.as_str().parse().ok()?,
+            pci_enabled: values
+                .next()
+                .map(|v| v.as_str().parse::<u8>())
+                .transpose()
+                .ok()?
+                .map(|v| v == 1),
+            reconnect_interval: values.next().map(|v| v.as_str().parse()).transpose().ok()?,
+            listen_interval: values.next().map(|v| v.as_str().parse()).transpose().ok()?,
+            scan_mode: values.next().map(|v| v.as_str().parse()).transpose().ok()?,
+            pmf: values.next().map(|v| v.as_str().parse()).transpose().ok()?,
+        })
+    }
+}
+
+/// WIFI mode of ESP-AT
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WifiMode {
+    /// WIFI RF is disabled
+    Null,
+
+    /// Station mode, for joining an access point
+    Station,
+
+    /// SoftAP mode, s. [softap module](crate::softap)
+    SoftAp,
+
+    /// SoftAP and station mode are both active
+    SoftApStation,
+}
+
+impl From<WifiMode> for usize {
+    fn from(mode: WifiMode) -> Self {
+        match mode {
+            WifiMode::Null => 0,
+            WifiMode::Station => 1,
+            WifiMode::SoftAp => 2,
+            WifiMode::SoftApStation => 3,
+        }
+    }
+}
+
+impl TryFrom<usize> for WifiMode {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Null),
+            1 => Ok(Self::Station),
+            2 => Ok(Self::SoftAp),
+            3 => Ok(Self::SoftApStation),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Authentication mode of an access point
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AuthMode {
+    /// Open network without authentication (0)
+    Open,
+
+    /// WEP (1)
+    Wep,
+
+    /// WPA with pre-shared key (2)
+    WpaPsk,
+
+    /// WPA2 with pre-shared key (3)
+    Wpa2Psk,
+
+    /// WPA or WPA2 with pre-shared key (4)
+    WpaWpa2Psk,
+
+    /// WPA2 Enterprise, e.g. EAP-TLS or PEAP (5)
+    Wpa2Enterprise,
+
+    /// WPA3 with simultaneous authentication of equals (6)
+    Wpa3Psk,
+
+    /// WPA2 with pre-shared key or WPA3 (7)
+    Wpa2Wpa3Psk,
+
+    /// WAPI with pre-shared key (8)
+    WapiPsk,
+
+    /// Opportunistic wireless encryption, i.e. open network with encryption (9)
+    Owe,
+
+    /// Mode not known by this crate, contains the raw ESP-AT value
+    Unknown(u8),
+}
+
+impl From<u8> for AuthMode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::Open,
+            1 => Self::Wep,
+            2 => Self::WpaPsk,
+            3 => Self::Wpa2Psk,
+            4 => Self::WpaWpa2Psk,
+            5 => Self::Wpa2Enterprise,
+            6 => Self::Wpa3Psk,
+            7 => Self::Wpa2Wpa3Psk,
+            8 => Self::WapiPsk,
+            9 => Self::Owe,
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+/// Pairwise or group cipher of an access point
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Cipher {
+    /// No cipher (0)
+    None,
+
+    /// WEP with 40 bit key (1)
+    Wep40,
+
+    /// WEP with 104 bit key (2)
+    Wep104,
+
+    /// TKIP (3)
+    Tkip,
+
+    /// CCMP, i.e. AES (4)
+    Ccmp,
+
+    /// TKIP and CCMP (5)
+    TkipCcmp,
+
+    /// AES-CMAC-128 (6)
+    AesCmac128,
+
+    /// Cipher not known by this crate or ESP-AT, contains the raw ESP-AT value
+    Unknown(u8),
+}
+
+impl From<u8> for Cipher {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::None,
+            1 => Self::Wep40,
+            2 => Self::Wep104,
+            3 => Self::Tkip,
+            4 => Self::Ccmp,
+            5 => Self::TkipCcmp,
+            6 => Self::AesCmac128,
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+/// Access point found by [scan()](WifiAdapter::scan)
+///
+/// Fields are None if excluded by [ScanOptions::fields] or not supported by the ESP-AT firmware version.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccessPoint {
+    /// Authentication mode
+    pub auth_mode: Option<AuthMode>,
+
+    /// SSID of the access point
+    pub ssid: Option<String<32>>,
+
+    /// Signal strength in dBm
+    pub rssi: Option<i16>,
+
+    /// MAC address (BSSID) of the access point
+    pub mac: Option<MacAddress>,
+
+    /// WIFI channel
+    pub channel: Option<u8>,
+
+    /// Frequency offset in KHz
+    pub freq_offset: Option<i32>,
+
+    /// Frequency calibration value
+    pub freqcal_val: Option<i32>,
+
+    /// Pairwise cipher type
+    pub pairwise_cipher: Option<Cipher>,
+
+    /// Group cipher type
+    pub group_cipher: Option<Cipher>,
+
+    /// Supported 802.11 standards. Bit 0: 802.11b, bit 1: 802.11g, bit 2: 802.11n
+    pub bgn: Option<u8>,
+
+    /// True if WPS is enabled
+    pub wps: Option<bool>,
+}
+
+impl AtatResp for AccessPoint {}
+
+impl AccessPoint {
+    /// Parses all +CWLAP lines of the response, containing only the fields enabled by the given mask.
+    /// Lines exceeding the capacity N are skipped, as well as malformed lines. Returns None if all lines are malformed.
+    pub(crate) fn from_response<const N: usize>(response: &[u8], fields: u16) -> Option<Vec<Self, N>> {
+        let response = core::str::from_utf8(response).ok()?;
+        let mut access_points = Vec::new();
+        let mut malformed = false;
+
+        for line in response.lines() {
+            let line = match line.trim().strip_prefix("+CWLAP:") {
+                None => continue,
+                Some(line) => line,
+            };
+
+            // Newer firmware versions wrap the fields in parentheses
+            let line = line.strip_prefix('(').and_then(|l| l.strip_suffix(')')).unwrap_or(line);
+
+            if access_points.is_full() {
+                break;
+            }
+
+            let Some(access_point) = Self::from_line(line, fields) else {
+                malformed = true;
+                continue;
+            };
+
+            // Capacity is checked above
+            let _ = access_points.push(access_point);
+        }
+
+        if malformed && access_points.is_empty() {
+            return None;
+        }
+
+        Some(access_points)
+    }
+
+    /// Parses the fields of a single line. Bit n of the mask corresponds to the n-th field.
+    fn from_line(line: &str, fields: u16) -> Option<Self> {
+        let values = Self::split_line(line)?;
+        let mut values = values.into_iter();
+        let mut next = |bit: u16| match fields & (1 << bit) {
+            0 => None,
+            _ => values.next(),
+        };
+
+        Some(Self {
+            auth_mode: next(0).map(|v| v.as_str().parse::<u8>()).transpose().ok()?.map(AuthMode::from),
+            ssid: next(1),
+            rssi: next(2).map(|v| v.as_str().parse()).transpose().ok()?,
+            mac: match next(3) {
+                None => None,
+                Some(mac) => Some(MacAddress::from_str(mac.as_str()).ok()?),
+            },
+            channel: next(4).map(|v| v.as_str().parse()).transpose().ok()?,
+            freq_offset: next(5).map(|v| v.as_str().parse()).transpose().ok()?,
+            freqcal_val: next(6).map(|v| v.as_str().parse()).transpose().ok()?,
+            pairwise_cipher: next(7).map(|v| v.as_str().parse::<u8>()).transpose().ok()?.map(Cipher::from),
+            group_cipher: next(8).map(|v| v.as_str().parse::<u8>()).transpose().ok()?.map(Cipher::from),
+            bgn: next(9).map(|v| v.as_str().parse()).transpose().ok()?,
+            wps: next(10).map(|v| v.as_str().parse::<u8>()).transpose().ok()?.map(|v| v == 1),
+        })
+    }
+
+    /// Splits a line by commas. Quotes and escape characters of strings (e.g. SSID) are removed.
+    pub(crate) fn split_line(line: &str) -> Option<Vec<String<32>, 11>> {
+        let mut values = Vec::new();
+        let mut value = String::new();
+        let mut quoted = false;
+        let mut escaped = false;
+
+        for char in line.chars() {
+            if escaped {
+                value.push(char).ok()?;
+                escaped = false;
+                continue;
+            }
+
+            match char {
+                '\\' if quoted => escaped = true,
+                '"' => quoted = !quoted,
+                ',' if !quoted => values.push(core::mem::take(&mut value)).ok()?,
+                _ => value.push(char).ok()?,
+            }
+        }
+        values.push(value).ok()?;
+
+        Some(values)
+    }
+}
+
+/// Options for scanning access points (CWLAPOPT)
+#[derive(Copy, Clone, Debug)]
+pub struct ScanOptions {
+    /// Sorts the results by RSSI, strongest first. Ignored by newer ESP-AT versions, which are always sorting.
+    pub sort_by_rssi: bool,
+
+    /// Bitmask of the reported fields, s. FIELD_* constants. Defaults to all fields.
+    pub fields: u16,
+
+    /// Only access points with a signal strength >= the given RSSI (dBm) are reported.
+    /// ESP-AT default (-100) is used if None.
+    pub rssi_filter: Option<i16>,
+
+    /// Only access points with the given authentication modes are reported.
+    /// Bit n corresponds to the n-th [AuthMode] variant, e.g. bit 0 = [AuthMode::Open].
+    /// All modes are reported if None.
+    pub auth_mode_mask: Option<u16>,
+}
+
+impl ScanOptions {
+    /// Enables `<ecn>`, reported as [AccessPoint::auth_mode]
+    pub const FIELD_AUTH_MODE: u16 = 1 << 0;
+
+    /// Enables `<ssid>`, reported as [AccessPoint::ssid]
+    pub const FIELD_SSID: u16 = 1 << 1;
+
+    /// Enables `<rssi>`, reported as [AccessPoint::rssi]
+    pub const FIELD_RSSI: u16 = 1 << 2;
+
+    /// Enables `<mac>`, reported as [AccessPoint::mac]
+    pub const FIELD_MAC: u16 = 1 << 3;
+
+    /// Enables `<channel>`, reported as [AccessPoint::channel]
+    pub const FIELD_CHANNEL: u16 = 1 << 4;
+
+    /// Enables `<freq_offset>`, reported as [AccessPoint::freq_offset]
+    pub const FIELD_FREQ_OFFSET: u16 = 1 << 5;
+
+    /// Enables `<freqcal_val>`, reported as [AccessPoint::freqcal_val]
+    pub const FIELD_FREQCAL_VAL: u16 = 1 << 6;
+
+    /// Enables `<pairwise_cipher>`, reported as [AccessPoint::pairwise_cipher]
+    pub const FIELD_PAIRWISE_CIPHER: u16 = 1 << 7;
+
+    /// Enables `<group_cipher>`, reported as [AccessPoint::group_cipher]
+    pub const FIELD_GROUP_CIPHER: u16 = 1 << 8;
+
+    /// Enables `<bgn>`, reported as [AccessPoint::bgn]
+    pub const FIELD_BGN: u16 = 1 << 9;
+
+    /// Enables `<wps>`, reported as [AccessPoint::wps]
+    pub const FIELD_WPS: u16 = 1 << 10;
+
+    /// All fields supported by ESP-AT
+    pub const ALL_FIELDS: u16 = 0x7FF;
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            sort_by_rssi: false,
+            fields: Self::ALL_FIELDS,
+            rssi_filter: None,
+            auth_mode_mask: None,
+        }
+    }
+}